@@ -13,7 +13,7 @@
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
 use crate::aq::{MessageDeliveryMode, MessageState, MsgRecipient};
-use crate::types::{Json, Object, Timestamp};
+use crate::types::{Json, Object, ObjectType, Timestamp};
 use crate::utils::*;
 use crate::Error;
 use crate::Result;
@@ -25,12 +25,17 @@ use std::time::Duration;
 #[odpic_doc]
 pub struct MsgProps {
     pub(crate) handle: *mut dpiMsgProps,
+    // the payload type of the queue which dequeued this message
+    payload_type: Option<ObjectType>,
 }
 
 #[odpic_doc]
 impl MsgProps {
-    pub(crate) fn new(handle: *mut dpiMsgProps) -> MsgProps {
-        MsgProps { handle }
+    pub(crate) fn new(handle: *mut dpiMsgProps, payload_type: Option<ObjectType>) -> MsgProps {
+        MsgProps {
+            handle,
+            payload_type,
+        }
     }
 
     pub fn num_attempts(&self) -> Result<i32> {
@@ -86,7 +91,7 @@ impl MsgProps {
         let obj = if obj.is_null() {
             None
         } else {
-            Some(Object::with_add_ref(obj))
+            Some(Object::with_add_ref(obj, self.payload_type.clone()))
         };
         let bytes = if value.is_null() {
             None
@@ -192,6 +197,7 @@ impl Clone for MsgProps {
         unsafe { dpiMsgProps_addRef(self.handle) };
         MsgProps {
             handle: self.handle,
+            payload_type: self.payload_type.clone(),
         }
     }
 }
@@ -201,3 +207,33 @@ impl Drop for MsgProps {
         release_handle!(dpiMsgProps_release(self.handle));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_util, Result};
+    use std::time::Duration;
+
+    #[crate::test]
+    async fn dequeued_payload_has_queue_payload_type() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let objtype = conn.object_type("UDT_BOOK").await?;
+        let attrs = objtype.attributes()?;
+        let queue = conn.new_queue("BOOK_QUEUE", Some(&objtype))?;
+        let book = objtype.create_object()?;
+        book.set_attribute_value(&attrs[0], &"Oracle Database")?;
+        let props = conn.new_msg_props()?;
+        props.set_payload_object(&book)?;
+        queue.enq_one(&props).await?;
+
+        queue.deq_options()?.set_wait(Duration::ZERO)?;
+        let props = queue.deq_one().await?.unwrap();
+        let (payload, bytes) = props.payload()?;
+        let payload = payload.unwrap();
+        assert!(bytes.is_none());
+        assert_eq!(payload.object_type().unwrap().info()?.name, "UDT_BOOK");
+        let title: String = payload.attribute_value(&attrs[0])?;
+        assert_eq!(title, "Oracle Database");
+        conn.rollback().await?;
+        Ok(())
+    }
+}
@@ -14,6 +14,7 @@
 //-----------------------------------------------------------------------------
 use crate::aq::{DeqOptions, EnqOptions, MsgProps};
 use crate::maybe_async;
+use crate::types::ObjectType;
 use crate::Result;
 use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
@@ -22,12 +23,16 @@ use odpic_sys::*;
 #[odpic_doc]
 pub struct Queue {
     pub(crate) handle: *mut dpiQueue,
+    payload_type: Option<ObjectType>,
 }
 
 #[odpic_doc]
 impl Queue {
-    pub(crate) fn new(handle: *mut dpiQueue) -> Queue {
-        Queue { handle }
+    pub(crate) fn new(handle: *mut dpiQueue, payload_type: Option<ObjectType>) -> Queue {
+        Queue {
+            handle,
+            payload_type,
+        }
     }
 
     #[maybe_async]
@@ -38,7 +43,7 @@ impl Queue {
         }
         .await?;
         Ok((0..(*num as usize))
-            .map(|idx| MsgProps::new(unsafe { (*props).add(idx) }))
+            .map(|idx| MsgProps::new(unsafe { (*props).add(idx) }, self.payload_type.clone()))
             .collect())
     }
 
@@ -52,7 +57,7 @@ impl Queue {
         if prop.is_null() {
             Ok(None)
         } else {
-            Ok(Some(MsgProps::new(*prop)))
+            Ok(Some(MsgProps::new(*prop, self.payload_type.clone())))
         }
     }
 
@@ -106,6 +111,7 @@ impl Clone for Queue {
         unsafe { dpiQueue_addRef(self.handle) };
         Queue {
             handle: self.handle,
+            payload_type: self.payload_type.clone(),
         }
     }
 }
@@ -18,7 +18,7 @@ use crate::conn::common_create_params::DpiCommonCreateParams;
 use crate::conn::conn_create_params::DpiConnCreateParams;
 use crate::context::Context;
 use crate::maybe_async;
-use crate::stmt::{Stmt, Var};
+use crate::stmt::{BindValue, DefineHandler, QueryInfo, Stmt, Var};
 use crate::subscr::subscr_create_params::DpiSubscrCreateParams;
 use crate::subscr::{Subscr, SubscrCreateParams};
 use crate::types::{Json, Lob, NativeType, ObjectType, OracleType, Vector, VectorInfo, Xid};
@@ -27,8 +27,10 @@ use crate::*;
 use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
 use std::ffi::c_void;
+use std::fmt;
 use std::pin::pin;
 use std::ptr;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 mod access_token;
@@ -52,10 +54,11 @@ pub use pool::Pool;
 pub use pool_create_params::PoolCreateParams;
 pub use sharding_key_column::ShardingKeyColumn;
 
-#[derive(Debug)]
 #[odpic_doc]
 pub struct Conn {
     pub(crate) handle: *mut dpiConn,
+    // This is shared by clones.
+    define_handler: Arc<RwLock<Option<Arc<DefineHandler>>>>,
 }
 
 unsafe impl Send for Conn {}
@@ -64,7 +67,10 @@ unsafe impl Sync for Conn {}
 #[odpic_doc]
 impl Conn {
     pub(crate) fn new(handle: *mut dpiConn) -> Conn {
-        Conn { handle }
+        Conn {
+            handle,
+            define_handler: Arc::new(RwLock::new(None)),
+        }
     }
 
     #[maybe_async]
@@ -322,31 +328,38 @@ impl Conn {
     where
         T: AsRef<str>,
     {
-        Ok(aq::Queue::new(get_value!(dpiConn_newJsonQueue(
-            self.handle,
-            name.to_ptr(),
-            name.try_to_len()?
-        ))?))
+        Ok(aq::Queue::new(
+            get_value!(dpiConn_newJsonQueue(
+                self.handle,
+                name.to_ptr(),
+                name.try_to_len()?
+            ))?,
+            None,
+        ))
     }
 
     pub fn new_msg_props(&self) -> Result<aq::MsgProps> {
-        Ok(aq::MsgProps::new(get_value!(dpiConn_newMsgProps(
-            self.handle
-        ))?))
+        Ok(aq::MsgProps::new(
+            get_value!(dpiConn_newMsgProps(self.handle))?,
+            None,
+        ))
     }
 
     pub fn new_queue<T>(&self, name: T, palyload_type: Option<&ObjectType>) -> Result<aq::Queue>
     where
         T: AsRef<str>,
     {
-        Ok(aq::Queue::new(get_value!(dpiConn_newQueue(
-            self.handle,
-            name.to_ptr(),
-            name.try_to_len()?,
-            palyload_type
-                .map(|payload| payload.handle)
-                .unwrap_or(ptr::null_mut())
-        ))?))
+        Ok(aq::Queue::new(
+            get_value!(dpiConn_newQueue(
+                self.handle,
+                name.to_ptr(),
+                name.try_to_len()?,
+                palyload_type
+                    .map(|payload| payload.handle)
+                    .unwrap_or(ptr::null_mut())
+            ))?,
+            palyload_type.cloned(),
+        ))
     }
 
     #[maybe_async]
@@ -384,7 +397,93 @@ impl Conn {
             is_array.to_dpi(),
             obj_type.to_dpi(),
         ))?;
-        Ok(Var::new(handle, native_type, data))
+        Ok(Var::new(
+            handle,
+            self,
+            oracle_type,
+            native_type,
+            max_array_size,
+            data,
+            obj_type.cloned(),
+        ))
+    }
+
+    /// Creates a variable holding `values`
+    ///
+    /// The maximum array size is the number of values and the size of each
+    /// element is the maximum byte length of the values when they are bound
+    /// as bytes. The native type is determined by `T`.
+    ///
+    /// This is a shortcut of [`Conn::new_var`] followed by [`Var::set_values`].
+    pub fn new_var_from_values<T>(
+        &self,
+        oracle_type: OracleType,
+        values: &[T],
+        obj_type: Option<&ObjectType>,
+    ) -> Result<Var>
+    where
+        T: BindValue,
+    {
+        let native_type: NativeType = T::native_type_num().try_to_rust()?;
+        let max_array_size = values.len().max(1).try_into()?;
+        let size = element_size(self, native_type, values, 0)?;
+        let var = self.new_var(
+            oracle_type,
+            native_type,
+            max_array_size,
+            size,
+            true,
+            false,
+            obj_type,
+        )?;
+        var.set_values(values)?;
+        Ok(var)
+    }
+
+    /// Creates a variable for a PL/SQL associative array holding `values`
+    ///
+    /// `max_array_size` is the maximum number of elements, which must be
+    /// large enough for elements returned by PL/SQL when the array is
+    /// an OUT or IN OUT parameter. `size` is the minimum size of each element
+    /// in bytes. The longest value is used instead when it is larger.
+    ///
+    /// Elements returned by PL/SQL are read by [`Var::values`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // procedure upper_names(names in out name_table)
+    /// // where name_table is "table of varchar2(100) index by pls_integer"
+    /// let var = conn.new_array_var_from_values(OracleType::Varchar, &["foo", "bar"], 10, 100, None)?;
+    /// let stmt = conn.prepare_stmt(false, "begin pkg.upper_names(:1); end;", "")?;
+    /// stmt.bind_by_pos(1, &var)?;
+    /// stmt.execute(ExecMode::DEFAULT).await?;
+    /// let names = var.values::<Option<String>>()?;
+    /// ```
+    pub fn new_array_var_from_values<T>(
+        &self,
+        oracle_type: OracleType,
+        values: &[T],
+        max_array_size: u32,
+        size: u32,
+        obj_type: Option<&ObjectType>,
+    ) -> Result<Var>
+    where
+        T: BindValue,
+    {
+        let native_type: NativeType = T::native_type_num().try_to_rust()?;
+        let size = element_size(self, native_type, values, size)?;
+        let var = self.new_var(
+            oracle_type,
+            native_type,
+            max_array_size,
+            size,
+            true,
+            true,
+            obj_type,
+        )?;
+        var.set_array_values(values)?;
+        Ok(var)
     }
 
     pub fn new_vector(&self, info: Option<&VectorInfo>) -> Result<Vector> {
@@ -422,7 +521,8 @@ impl Conn {
             sql.try_to_len()?,
             tag.to_ptr(),
             tag.try_to_len()?,
-        ))?))
+        ))?)
+        .with_conn(self))
     }
 
     #[maybe_async]
@@ -496,6 +596,32 @@ impl Conn {
         ))
     }
 
+    /// Sets a callback defining query columns after [`Stmt::execute`]
+    ///
+    /// Statements prepared by [`Conn::prepare_stmt`] and REF CURSORs got from
+    /// variables created by [`Conn::new_var`] after this call inherit the
+    /// handler. The handler is shared by clones of the connection.
+    /// See [`DefineHandler`].
+    pub fn set_define_handler<T>(&self, handler: T)
+    where
+        T: Fn(&Stmt, u32, &QueryInfo) -> Result<()> + Send + Sync + 'static,
+    {
+        self.set_define_handler_arc(Some(Arc::new(handler)));
+    }
+
+    /// Removes the callback set by [`Conn::set_define_handler`]
+    pub fn remove_define_handler(&self) {
+        self.set_define_handler_arc(None);
+    }
+
+    pub(crate) fn define_handler(&self) -> Option<Arc<DefineHandler>> {
+        self.define_handler.read().unwrap().clone()
+    }
+
+    pub(crate) fn set_define_handler_arc(&self, handler: Option<Arc<DefineHandler>>) {
+        *self.define_handler.write().unwrap() = handler;
+    }
+
     pub fn set_econtext_id<T>(&self, value: T) -> Result<()>
     where
         T: AsRef<str>,
@@ -703,12 +829,47 @@ impl Clone for Conn {
         unsafe { dpiConn_addRef(self.handle) };
         Conn {
             handle: self.handle,
+            define_handler: self.define_handler.clone(),
         }
     }
 }
 
+impl fmt::Debug for Conn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> std::result::Result<(), fmt::Error> {
+        f.debug_struct("Conn")
+            .field("handle", &self.handle)
+            .field(
+                "define_handler",
+                &self.define_handler().map(|_| FmtEllipsis()),
+            )
+            .finish()
+    }
+}
+
 impl Drop for Conn {
     fn drop(&mut self) {
         release_handle!(dpiConn_release(self.handle));
     }
 }
+
+/// Returns the element size of a variable holding `values`
+fn element_size<T>(conn: &Conn, native_type: NativeType, values: &[T], min_size: u32) -> Result<u32>
+where
+    T: BindValue,
+{
+    if native_type != NativeType::Bytes {
+        return Ok(min_size);
+    }
+    let mut size = min_size.max(1);
+    for value in values {
+        let len = value.with_data(Some(conn), |data| {
+            Ok(if data.isNull == 0 {
+                unsafe { data.value.asBytes.length }
+            } else {
+                0
+            })
+        })?;
+        size = size.max(len);
+    }
+    Ok(size)
+}
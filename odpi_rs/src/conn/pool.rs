@@ -21,6 +21,7 @@ use crate::conn::{
 };
 use crate::context::Context;
 use crate::maybe_async;
+use crate::stmt::{DefineHandler, QueryInfo, Stmt};
 use crate::utils::*;
 use crate::Result;
 use odpi_rs_procmacro::odpic_doc;
@@ -29,7 +30,7 @@ use std::ffi::{c_char, c_int, c_void};
 use std::fmt;
 use std::pin::pin;
 use std::ptr;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
 pub type AccessTokenCallback = dyn Fn() -> Option<AccessToken> + Send + Sync + 'static;
@@ -75,6 +76,8 @@ impl AccessTokenCallbackContext {
 pub struct Pool {
     pub(crate) handle: *mut dpiPool,
     callback_context: Option<Arc<AccessTokenCallbackContext>>,
+    // This is shared by clones.
+    define_handler: Arc<RwLock<Option<Arc<DefineHandler>>>>,
 }
 
 #[odpic_doc]
@@ -86,6 +89,7 @@ impl Pool {
         Pool {
             handle,
             callback_context,
+            define_handler: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -121,6 +125,7 @@ impl Pool {
             .await?,
         );
         dpi_create_params.update_out_params()?;
+        conn.set_define_handler_arc(self.define_handler.read().unwrap().clone());
         Ok(conn)
     }
 
@@ -299,6 +304,23 @@ impl Pool {
             value.map_or(Ok(-1), |dur| duration_to_secs(dur, "pool ping interval"))?,
         ))
     }
+
+    /// Sets a callback defining query columns after [`Stmt::execute`]
+    ///
+    /// Connections acquired by [`Pool::acquire_connection`] after this call
+    /// inherit the handler. The handler is shared by clones of the pool.
+    /// See [`DefineHandler`].
+    pub fn set_define_handler<T>(&self, handler: T)
+    where
+        T: Fn(&Stmt, u32, &QueryInfo) -> Result<()> + Send + Sync + 'static,
+    {
+        *self.define_handler.write().unwrap() = Some(Arc::new(handler));
+    }
+
+    /// Removes the callback set by [`Pool::set_define_handler`]
+    pub fn remove_define_handler(&self) {
+        *self.define_handler.write().unwrap() = None;
+    }
 }
 
 impl Clone for Pool {
@@ -307,6 +329,7 @@ impl Clone for Pool {
         Pool {
             handle: self.handle,
             callback_context: self.callback_context.clone(),
+            define_handler: self.define_handler.clone(),
         }
     }
 }
@@ -342,6 +365,65 @@ impl fmt::Debug for Pool {
                 "callback_context",
                 &self.callback_context.as_ref().map(|_| FmtEllipsis()),
             )
+            .field(
+                "define_handler",
+                &self
+                    .define_handler
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .map(|_| FmtEllipsis()),
+            )
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stmt::ExecMode;
+    use crate::test_util;
+    use crate::types::{NativeType, OracleType};
+
+    #[crate::test]
+    async fn acquired_connections_inherit_define_handler() -> Result<()> {
+        let pool = Pool::create(
+            test_util::main_user(),
+            test_util::main_password(),
+            test_util::connect_string(),
+            None,
+            None,
+        )
+        .await?;
+        let positions = Arc::new(Mutex::new(Vec::new()));
+        let handler_positions = positions.clone();
+        // The handler set through a clone is shared by the original pool.
+        pool.clone().set_define_handler(move |stmt, pos, info| {
+            handler_positions.lock().unwrap().push(pos);
+            match info.type_info.oracle_type {
+                Some(OracleType::Number) => {
+                    stmt.define_value(pos, OracleType::Number, NativeType::Bytes, 0, false, None)
+                }
+                _ => Ok(()),
+            }
+        });
+
+        let conn = pool.acquire_connection("", "", None).await?;
+        let stmt = conn.prepare_stmt(false, "select 1.25, 'a' from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<String>(1)?, "1.25");
+        assert_eq!(stmt.query_value::<String>(2)?, "a");
+        assert_eq!(*positions.lock().unwrap(), [1, 2]);
+
+        // Connections acquired after the handler is removed have no handler.
+        pool.clone().remove_define_handler();
+        positions.lock().unwrap().clear();
+        let conn = pool.acquire_connection("", "", None).await?;
+        let stmt = conn.prepare_stmt(false, "select 1 from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert!(positions.lock().unwrap().is_empty());
+        Ok(())
+    }
+}
@@ -59,6 +59,14 @@ pub enum Error {
     },
     #[error("null value found")]
     NullValue,
+    /// An error while getting a query column by `#[derive(FromRow)]`
+    #[error("failed to get column {name} at position {position}: {source}")]
+    #[non_exhaustive]
+    Column {
+        name: String,
+        position: u32,
+        source: Box<Error>,
+    },
     #[error("{message}")]
     #[non_exhaustive]
     Other { message: String },
@@ -75,6 +83,26 @@ impl Error {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Error
+    where
+        T: fmt::Display,
+    {
+        Error::other(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error {
+    fn custom<T>(msg: T) -> Error
+    where
+        T: fmt::Display,
+    {
+        Error::other(msg.to_string())
+    }
+}
+
 impl From<Infallible> for Error {
     fn from(_value: Infallible) -> Error {
         unreachable!()
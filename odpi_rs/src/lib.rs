@@ -22,6 +22,10 @@ use std::str;
 // Re-exports
 pub use odpic_sys;
 
+// Derive macros refer to `::odpi_rs`. This makes it available in tests of this crate.
+#[cfg(test)]
+extern crate self as odpi_rs;
+
 macro_rules! call {
     ($c_func:ident($($arg:expr),+ $(,)?)) => {
         call!($c_func($($arg),+) -> Ok(()))
@@ -306,12 +310,27 @@ mod private {
     impl Sealed for f32 {}
     impl Sealed for f64 {}
     impl Sealed for &str {}
+    impl Sealed for String {}
+    impl Sealed for &[u8] {}
+    impl Sealed for Vec<u8> {}
     impl Sealed for crate::types::Timestamp {}
     impl Sealed for crate::types::IntervalDS {}
     impl Sealed for crate::types::IntervalYM {}
+    impl Sealed for crate::types::Lob {}
+    impl Sealed for crate::types::Json {}
+    impl Sealed for crate::types::Vector {}
+    impl Sealed for crate::types::VectorInfo<'_> {}
     impl Sealed for crate::types::Object {}
     impl Sealed for crate::types::Rowid {}
+    impl Sealed for crate::stmt::Stmt {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for ::chrono::DateTime<::chrono::FixedOffset> {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for ::chrono::DateTime<::chrono::Utc> {}
+    #[cfg(feature = "time")]
+    impl Sealed for ::time::OffsetDateTime {}
     impl<T: Sealed> Sealed for Option<T> {}
+    impl<T: crate::stmt::ToBindValue> Sealed for T {}
 }
 
 #[allow(dead_code)]
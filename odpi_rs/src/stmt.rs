@@ -14,28 +14,106 @@
 //-----------------------------------------------------------------------------
 //! Types for statement executions and queries
 
+use crate::conn::Conn;
 use crate::private;
+use crate::stmt::inline_lobs::InlineLobs;
 use crate::types::{
-    FromSql, FromSqlUnsafe, IntervalDS, IntervalYM, NativeType, NativeValue, Object, ObjectType,
-    OracleType, Rowid, Timestamp,
+    FromSql, FromSqlUnsafe, IntervalDS, IntervalYM, Json, Lob, NativeType, NativeValue, Object,
+    ObjectType, OracleType, Rowid, Timestamp, Vector, VectorInfo,
 };
 use crate::utils::*;
 use crate::*;
 use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
+use std::collections::HashMap;
 use std::ffi::c_void;
+use std::fmt;
 use std::mem;
 use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "arrow")]
+mod arrow;
+mod column_fetcher;
 mod enums;
+mod from_row;
+mod inline_lobs;
 mod query_info;
+mod row_batch;
+mod rows;
 mod stmt_info;
 mod var;
 
+#[cfg(feature = "arrow")]
+pub use arrow::RecordBatches;
+pub use column_fetcher::ColumnFetcher;
 pub use enums::*;
+#[doc(hidden)]
+pub use from_row::ColumnMap;
+pub use from_row::FromRow;
+pub use odpi_rs_procmacro::FromRow;
 pub use query_info::QueryInfo;
+pub use row_batch::RowBatch;
+pub use rows::Rows;
 pub use stmt_info::StmtInfo;
-pub use var::Var;
+pub use var::{ReturnedData, Var};
+
+/// Callback defining query columns after [`Stmt::execute`]
+///
+/// It is called with the statement, the position and the query information
+/// of each column when a query is executed and can call [`Stmt::define_value`]
+/// to choose how the column is fetched. Columns not defined by it are fetched
+/// in the default types.
+///
+/// Handlers are inherited as follows. See [`Conn::set_define_handler`],
+/// [`Stmt::set_define_handler`] and [`Pool::set_define_handler`].
+///
+/// - Connections acquired from a pool inherit the handler of the pool.
+/// - Statements prepared by [`Conn::prepare_stmt`] and REF CURSORs got from
+///   variables inherit the handler of the connection.
+/// - Implicit results and REF CURSORs got from query columns inherit the
+///   handler of the statement.
+/// - Statements prepared by [`Subscr::prepare_stmt`] have no handler.
+///
+/// [`Pool::set_define_handler`]: crate::conn::Pool::set_define_handler
+/// [`Subscr::prepare_stmt`]: crate::subscr::Subscr::prepare_stmt
+///
+/// # Examples
+///
+/// ```ignore
+/// use odpi_rs::types::{NativeType, OracleType};
+///
+/// conn.set_define_handler(|stmt, pos, info| {
+///     let type_info = &info.type_info;
+///     match type_info.oracle_type {
+///         // NUMBER as text to keep its precision
+///         Some(OracleType::Number) => {
+///             stmt.define_value(pos, OracleType::Number, NativeType::Bytes, 0, false, None)
+///         }
+///         // CLOB and BLOB inline instead of as locators
+///         Some(OracleType::Clob) => {
+///             stmt.define_value(pos, OracleType::LongVarchar, NativeType::Bytes, 0, false, None)
+///         }
+///         Some(OracleType::Blob) => {
+///             stmt.define_value(pos, OracleType::LongRaw, NativeType::Bytes, 0, false, None)
+///         }
+///         // DATE as timestamp
+///         Some(OracleType::Date) => {
+///             stmt.define_value(pos, OracleType::Date, NativeType::Timestamp, 0, false, None)
+///         }
+///         // JSON as text
+///         Some(OracleType::Json) => {
+///             stmt.define_value(pos, OracleType::LongVarchar, NativeType::Bytes, 0, false, None)
+///         }
+///         _ => Ok(()),
+///     }
+/// });
+///
+/// let stmt = conn.prepare_stmt(false, "select * from some_table", "")?;
+/// stmt.execute(ExecMode::DEFAULT).await?;
+/// ```
+pub type DefineHandler = dyn Fn(&Stmt, u32, &QueryInfo) -> Result<()> + Send + Sync + 'static;
 
 /// Result of [`Stmt::fetch_rows`]
 #[derive(Clone, Debug)]
@@ -45,17 +123,99 @@ pub struct FetchRowsResult {
 }
 
 /// Value passed to [`Stmt::bind_value_by_name`] and [`Stmt::bind_value_by_pos`]
+///
+/// This trait is sealed. Implement [`ToBindValue`] to bind user-defined types.
 pub trait BindValue: private::Sealed {
     #[doc(hidden)]
     fn native_type_num() -> dpiNativeTypeNum;
+    /// Returns the Oracle type when it isn't determined by the native type.
+    ///
+    /// This is `RAW` for byte arrays, which are bound as `VARCHAR2` otherwise.
     #[doc(hidden)]
-    fn data(&self) -> Result<dpiData> {
-        let mut data: dpiData = unsafe { mem::zeroed() };
-        self.set_to_data(&mut data)?;
-        Ok(data)
+    fn oracle_type_num() -> Option<dpiOracleTypeNum> {
+        None
     }
+    /// Calls `f` with `dpiData` which is valid only while `f` is called.
+    ///
+    /// `conn` is used to create values bound by handles such as vectors.
     #[doc(hidden)]
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()>;
+    fn with_data<R, F>(&self, conn: Option<&Conn>, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut dpiData) -> Result<R>;
+}
+
+/// Conversion from a user-defined type to a value implementing [`BindValue`]
+///
+/// [`BindValue`] is implemented for types implementing this trait.
+/// The converted value may borrow `self`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use odpi_rs::stmt::ToBindValue;
+/// use odpi_rs::Result;
+///
+/// struct EmployeeId(i32);
+///
+/// impl ToBindValue for EmployeeId {
+///     type Value<'a> = i32;
+///
+///     fn to_bind_value(&self) -> Result<i32> {
+///         Ok(self.0)
+///     }
+/// }
+///
+/// struct Name(String);
+///
+/// impl ToBindValue for Name {
+///     type Value<'a> = &'a str;
+///
+///     fn to_bind_value(&self) -> Result<&str> {
+///         Ok(&self.0)
+///     }
+/// }
+///
+/// stmt.bind_value_by_name("id", &EmployeeId(7369))?;
+/// stmt.bind_value_by_name("name", &Some(Name("SMITH".into())))?;
+/// ```
+pub trait ToBindValue {
+    /// The type actually bound
+    type Value<'a>: BindValue
+    where
+        Self: 'a;
+
+    /// Converts `self` to the value actually bound.
+    fn to_bind_value(&self) -> Result<Self::Value<'_>>;
+}
+
+impl<T> BindValue for T
+where
+    T: ToBindValue,
+{
+    fn native_type_num() -> dpiNativeTypeNum {
+        fn native_type_num<'a, T>() -> dpiNativeTypeNum
+        where
+            T: ToBindValue + 'a,
+        {
+            <T::Value<'a> as BindValue>::native_type_num()
+        }
+        native_type_num::<T>()
+    }
+    fn oracle_type_num() -> Option<dpiOracleTypeNum> {
+        fn oracle_type_num<'a, T>() -> Option<dpiOracleTypeNum>
+        where
+            T: ToBindValue + 'a,
+        {
+            <T::Value<'a> as BindValue>::oracle_type_num()
+        }
+        oracle_type_num::<T>()
+    }
+    fn with_data<R, F>(&self, conn: Option<&Conn>, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut dpiData) -> Result<R>,
+    {
+        self.to_bind_value()?.with_data(conn, f)
+    }
 }
 
 impl<T> BindValue for Option<T>
@@ -65,202 +225,230 @@ where
     fn native_type_num() -> dpiNativeTypeNum {
         T::native_type_num()
     }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
+    fn oracle_type_num() -> Option<dpiOracleTypeNum> {
+        T::oracle_type_num()
+    }
+    fn with_data<R, F>(&self, conn: Option<&Conn>, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut dpiData) -> Result<R>,
+    {
         if let Some(value) = self {
-            value.set_to_data(data)
+            value.with_data(conn, f)
         } else {
+            let mut data: dpiData = unsafe { mem::zeroed() };
             data.isNull = 1;
-            Ok(())
+            f(&mut data)
         }
     }
 }
 
-impl BindValue for i8 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_INT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asInt64 = (*self).into();
-        Ok(())
-    }
+macro_rules! impl_bind_value {
+    ($t:ty, $native_type_num:ident, |$value:ident, $data:ident| $set:expr) => {
+        impl_bind_value!($t, $native_type_num, None, |$value, $data| $set);
+    };
+    ($t:ty, $native_type_num:ident, $oracle_type_num:expr, |$value:ident, $data:ident| $set:expr) => {
+        impl BindValue for $t {
+            fn native_type_num() -> dpiNativeTypeNum {
+                $native_type_num
+            }
+            fn oracle_type_num() -> Option<dpiOracleTypeNum> {
+                $oracle_type_num
+            }
+            fn with_data<R, F>(&self, _conn: Option<&Conn>, f: F) -> Result<R>
+            where
+                F: FnOnce(&mut dpiData) -> Result<R>,
+            {
+                let $value = self;
+                let mut $data: dpiData = unsafe { mem::zeroed() };
+                $set;
+                f(&mut $data)
+            }
+        }
+    };
 }
 
-impl BindValue for i16 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_INT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asInt64 = (*self).into();
-        Ok(())
-    }
+impl_bind_value! {
+    i8, DPI_NATIVE_TYPE_INT64, |value, data| data.value.asInt64 = (*value).into()
 }
-
-impl BindValue for i32 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_INT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asInt64 = (*self).into();
-        Ok(())
-    }
+impl_bind_value! {
+    i16, DPI_NATIVE_TYPE_INT64, |value, data| data.value.asInt64 = (*value).into()
 }
-
-impl BindValue for i64 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_INT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asInt64 = *self;
-        Ok(())
-    }
+impl_bind_value! {
+    i32, DPI_NATIVE_TYPE_INT64, |value, data| data.value.asInt64 = (*value).into()
 }
-
-impl BindValue for u8 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_UINT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asUint64 = (*self).into();
-        Ok(())
-    }
+impl_bind_value! {
+    i64, DPI_NATIVE_TYPE_INT64, |value, data| data.value.asInt64 = *value
 }
-
-impl BindValue for u16 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_UINT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asUint64 = (*self).into();
-        Ok(())
-    }
+impl_bind_value! {
+    u8, DPI_NATIVE_TYPE_UINT64, |value, data| data.value.asUint64 = (*value).into()
 }
-
-impl BindValue for u32 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_UINT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asUint64 = (*self).into();
-        Ok(())
-    }
+impl_bind_value! {
+    u16, DPI_NATIVE_TYPE_UINT64, |value, data| data.value.asUint64 = (*value).into()
 }
-
-impl BindValue for u64 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_UINT64
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asUint64 = *self;
-        Ok(())
-    }
+impl_bind_value! {
+    u32, DPI_NATIVE_TYPE_UINT64, |value, data| data.value.asUint64 = (*value).into()
 }
-
-impl BindValue for f32 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_FLOAT
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asFloat = *self;
-        Ok(())
-    }
+impl_bind_value! {
+    u64, DPI_NATIVE_TYPE_UINT64, |value, data| data.value.asUint64 = *value
 }
-
-impl BindValue for f64 {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_DOUBLE
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asDouble = *self;
-        Ok(())
-    }
+impl_bind_value! {
+    f32, DPI_NATIVE_TYPE_FLOAT, |value, data| data.value.asFloat = *value
 }
-
-impl BindValue for &str {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_BYTES
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asBytes.ptr = self.as_ptr() as *mut c_char;
-        data.value.asBytes.length = self.len().try_into()?;
-        Ok(())
-    }
+impl_bind_value! {
+    f64, DPI_NATIVE_TYPE_DOUBLE, |value, data| data.value.asDouble = *value
 }
-
-impl BindValue for Timestamp {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_TIMESTAMP
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asTimestamp = (*self).into();
-        Ok(())
+impl_bind_value! {
+    &str, DPI_NATIVE_TYPE_BYTES, |value, data| {
+        data.value.asBytes.ptr = value.as_ptr() as *mut c_char;
+        data.value.asBytes.length = value.len().try_into()?;
     }
 }
-
-impl BindValue for IntervalYM {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_INTERVAL_YM
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asIntervalYM = (*self).into();
-        Ok(())
+impl_bind_value! {
+    String, DPI_NATIVE_TYPE_BYTES, |value, data| {
+        data.value.asBytes.ptr = value.as_ptr() as *mut c_char;
+        data.value.asBytes.length = value.len().try_into()?;
     }
 }
-
-impl BindValue for IntervalDS {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_INTERVAL_DS
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asIntervalDS = (*self).into();
-        Ok(())
+impl_bind_value! {
+    &[u8], DPI_NATIVE_TYPE_BYTES, Some(DPI_ORACLE_TYPE_RAW), |value, data| {
+        data.value.asBytes.ptr = value.as_ptr() as *mut c_char;
+        data.value.asBytes.length = value.len().try_into()?;
     }
 }
-
-impl BindValue for Object {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_OBJECT
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asObject = self.handle;
-        Ok(())
+impl_bind_value! {
+    Vec<u8>, DPI_NATIVE_TYPE_BYTES, Some(DPI_ORACLE_TYPE_RAW), |value, data| {
+        data.value.asBytes.ptr = value.as_ptr() as *mut c_char;
+        data.value.asBytes.length = value.len().try_into()?;
     }
 }
-
-impl BindValue for Rowid {
-    fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_ROWID
-    }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asRowid = self.handle;
-        Ok(())
-    }
+impl_bind_value! {
+    Timestamp, DPI_NATIVE_TYPE_TIMESTAMP, |value, data| data.value.asTimestamp = (*value).into()
+}
+impl_bind_value! {
+    IntervalYM, DPI_NATIVE_TYPE_INTERVAL_YM, |value, data| data.value.asIntervalYM = (*value).into()
+}
+impl_bind_value! {
+    IntervalDS, DPI_NATIVE_TYPE_INTERVAL_DS, |value, data| data.value.asIntervalDS = (*value).into()
+}
+impl_bind_value! {
+    Object, DPI_NATIVE_TYPE_OBJECT, |value, data| data.value.asObject = value.handle
+}
+impl_bind_value! {
+    Lob, DPI_NATIVE_TYPE_LOB, |value, data| data.value.asLOB = value.handle
+}
+impl_bind_value! {
+    Json, DPI_NATIVE_TYPE_JSON, |value, data| data.value.asJson = value.handle
+}
+impl_bind_value! {
+    Vector, DPI_NATIVE_TYPE_VECTOR, |value, data| data.value.asVector = value.handle
+}
+impl_bind_value! {
+    Stmt, DPI_NATIVE_TYPE_STMT, |value, data| data.value.asStmt = value.handle
+}
+impl_bind_value! {
+    Rowid, DPI_NATIVE_TYPE_ROWID, |value, data| data.value.asRowid = value.handle
+}
+impl_bind_value! {
+    bool, DPI_NATIVE_TYPE_BOOLEAN, |value, data| data.value.asBoolean = (*value).into()
 }
 
-impl BindValue for bool {
+/// Binds a vector created by the connection of the statement or the variable.
+///
+/// This fails when the connection is unknown, for example when the statement
+/// is a REF CURSOR or the value is set to an object. Bind [`Vector`] created
+/// by [`Conn::new_vector`] in that case.
+impl BindValue for VectorInfo<'_> {
     fn native_type_num() -> dpiNativeTypeNum {
-        DPI_NATIVE_TYPE_BOOLEAN
+        DPI_NATIVE_TYPE_VECTOR
     }
-    fn set_to_data(&self, data: &mut dpiData) -> Result<()> {
-        data.value.asBoolean = (*self).into();
-        Ok(())
+    fn with_data<R, F>(&self, conn: Option<&Conn>, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut dpiData) -> Result<R>,
+    {
+        let conn = conn.ok_or_else(|| {
+            Error::other("VectorInfo cannot be bound without a connection; bind Vector instead")
+        })?;
+        conn.new_vector(Some(self))?.with_data(Some(conn), f)
     }
 }
 
-#[derive(Debug)]
 #[odpic_doc]
 pub struct Stmt {
     pub(crate) handle: *mut dpiStmt,
+    conn: Option<Conn>,
+    define_handler: Option<Arc<DefineHandler>>,
+    inline_lob_max_size: Option<u32>,
+    // This is shared by clones.
+    inline_lobs: Arc<Mutex<InlineLobs>>,
+    // buffers of variables defined by Stmt::define keyed by column positions.
+    // This is shared by clones.
+    defined_vars: Arc<Mutex<HashMap<u32, Arc<Mutex<*mut dpiData>>>>>,
+    // true while the fetch buffer is used by a RowBatch or a fetch.
+    // This is shared by clones.
+    fetch_buffer_in_use: Arc<AtomicBool>,
+}
+
+/// Marks the fetch buffer of a statement as in use while this exists
+pub(crate) struct FetchGuard(Arc<AtomicBool>);
+
+impl Drop for FetchGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
 }
 
 #[odpic_doc]
 impl Stmt {
     pub(crate) fn new(handle: *mut dpiStmt) -> Stmt {
-        Stmt { handle }
+        Stmt {
+            handle,
+            conn: None,
+            define_handler: None,
+            fetch_buffer_in_use: Arc::new(AtomicBool::new(false)),
+            inline_lob_max_size: None,
+            inline_lobs: Arc::new(Mutex::new(InlineLobs::default())),
+            defined_vars: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 
     pub(crate) fn with_add_ref(handle: *mut dpiStmt) -> Stmt {
         unsafe { dpiStmt_addRef(handle) };
-        Stmt { handle }
+        Stmt::new(handle)
+    }
+
+    /// Sets the connection used to create bind values such as vectors
+    /// and the define handler set by [`Conn::set_define_handler`]
+    pub(crate) fn with_conn(mut self, conn: &Conn) -> Stmt {
+        self.set_conn(conn);
+        self
+    }
+
+    pub(crate) fn set_conn(&mut self, conn: &Conn) {
+        self.conn = Some(conn.clone());
+        self.define_handler = conn.define_handler();
+    }
+
+    /// Sets the connection and the define settings of `parent`, which
+    /// returned this statement as an implicit result or a REF CURSOR
+    pub(crate) fn set_parent(&mut self, parent: &Stmt) {
+        self.conn = parent.conn.clone();
+        self.define_handler = parent.define_handler.clone();
+        self.inline_lob_max_size = parent.inline_lob_max_size;
+    }
+
+    /// Returns a guard preventing the statement and its clones from
+    /// executing and fetching rows while the fetch buffer is in use
+    pub(crate) fn fetch_guard(&self) -> Result<FetchGuard> {
+        if self
+            .fetch_buffer_in_use
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(Error::other(
+                "the statement is used by a RowBatch or another fetch",
+            ));
+        }
+        Ok(FetchGuard(self.fetch_buffer_in_use.clone()))
     }
 
     pub fn bind_by_name<T>(&self, name: T, var: &Var) -> Result<()>
@@ -279,41 +467,109 @@ impl Stmt {
         call!(dpiStmt_bindByPos(self.handle, pos, var.handle))
     }
 
+    /// # Note
+    /// Values of types not supported by `dpiStmt_bindValueByName`, that is
+    /// [`Lob`], [`Json`], [`Vector`], [`VectorInfo`] and [`Stmt`], and byte
+    /// arrays, which are bound as `RAW`, are bound through variables created
+    /// by the connection of the statement. NULL [`Lob`] is bound as `VARCHAR2`.
     pub fn bind_value_by_name<N, T>(&self, name: N, value: &T) -> Result<()>
     where
         N: AsRef<str>,
         T: BindValue,
     {
-        call!(dpiStmt_bindValueByName(
-            self.handle,
-            name.to_ptr(),
-            name.try_to_len()?,
-            T::native_type_num(),
-            &mut value.data()?,
-        ))
+        if let Some(var) = self.bind_var(value)? {
+            return self.bind_by_name(name, &var);
+        }
+        value.with_data(self.conn.as_ref(), |data| {
+            call!(dpiStmt_bindValueByName(
+                self.handle,
+                name.to_ptr(),
+                name.try_to_len()?,
+                bind_value_native_type_num::<T>(data),
+                data,
+            ))
+        })
     }
 
+    /// # Note
+    /// Some values are bound through variables as described in
+    /// [`Stmt::bind_value_by_name`].
     pub fn bind_value_by_pos<T>(&self, pos: u32, value: &T) -> Result<()>
     where
         T: BindValue,
     {
-        call!(dpiStmt_bindValueByPos(
-            self.handle,
-            pos,
-            T::native_type_num(),
-            &mut value.data()?,
-        ))
+        if let Some(var) = self.bind_var(value)? {
+            return self.bind_by_pos(pos, &var);
+        }
+        value.with_data(self.conn.as_ref(), |data| {
+            call!(dpiStmt_bindValueByPos(
+                self.handle,
+                pos,
+                bind_value_native_type_num::<T>(data),
+                data
+            ))
+        })
+    }
+
+    // Creates a variable holding `value` when dpiStmt_bindValueBy* cannot bind it.
+    // dpiStmt_bindValueBy* binds bytes as VARCHAR2 and rejects native types
+    // other than scalar types, objects and ROWIDs.
+    fn bind_var<T>(&self, value: &T) -> Result<Option<Var>>
+    where
+        T: BindValue,
+    {
+        let oracle_type = match (T::oracle_type_num(), T::native_type_num()) {
+            (Some(oracle_type_num), _) => oracle_type_num.try_to_rust()?,
+            (None, DPI_NATIVE_TYPE_LOB) => {
+                // The LOB type is got from the value. NULL is bound as VARCHAR2.
+                let lob_type = value.with_data(None, |data| {
+                    if data.isNull != 0 {
+                        Ok(None)
+                    } else {
+                        let lob_type = get_value!(dpiLob_getType(data.value.asLOB))?;
+                        Ok(Some(lob_type.try_to_rust()?))
+                    }
+                })?;
+                match lob_type {
+                    Some(lob_type) => lob_type,
+                    None => return Ok(None),
+                }
+            }
+            (None, DPI_NATIVE_TYPE_JSON) => OracleType::Json,
+            (None, DPI_NATIVE_TYPE_VECTOR) => OracleType::Vector,
+            (None, DPI_NATIVE_TYPE_STMT) => OracleType::Stmt,
+            _ => return Ok(None),
+        };
+        let conn = self.conn.as_ref().ok_or_else(|| {
+            Error::other(format!(
+                "{:?} values cannot be bound without the connection of the statement",
+                oracle_type
+            ))
+        })?;
+        Ok(Some(conn.new_var_from_values(
+            oracle_type,
+            std::slice::from_ref(value),
+            None,
+        )?))
     }
 
     pub fn close<T>(&self, tag: T) -> Result<()>
     where
         T: AsRef<str>,
     {
+        let _guard = self.fetch_guard()?;
         call!(dpiStmt_close(self.handle, tag.to_ptr(), tag.try_to_len()?))
     }
 
     pub fn define(&self, pos: u32, var: &Var) -> Result<()> {
-        call!(dpiStmt_define(self.handle, pos, var.handle))
+        let _guard = self.fetch_guard()?;
+        call!(dpiStmt_define(self.handle, pos, var.handle))?;
+        self.inline_lobs.lock().unwrap().remove_column(pos);
+        self.defined_vars
+            .lock()
+            .unwrap()
+            .insert(pos, var.data.clone());
+        Ok(())
     }
 
     pub fn define_value(
@@ -325,6 +581,7 @@ impl Stmt {
         size_is_bytes: bool,
         obj_type: Option<&ObjectType>,
     ) -> Result<()> {
+        let _guard = self.fetch_guard()?;
         call!(dpiStmt_defineValue(
             self.handle,
             pos,
@@ -333,24 +590,68 @@ impl Stmt {
             size,
             size_is_bytes.to_dpi(),
             obj_type.to_dpi()
-        ))
+        ))?;
+        self.inline_lobs.lock().unwrap().remove_column(pos);
+        self.defined_vars.lock().unwrap().remove(&pos);
+        Ok(())
+    }
+
+    /// Returns a column-major fetcher
+    ///
+    /// This must be called after the statement is executed and before rows
+    /// are fetched. A variable is created by the connection of the statement
+    /// and defined for each query column. See [`ColumnFetcher`].
+    pub fn column_fetcher(&self) -> Result<ColumnFetcher<'_>> {
+        ColumnFetcher::new(self)
+    }
+
+    /// Returns record batches of the query results
+    ///
+    /// This must be called after the statement is executed and before rows
+    /// are fetched. Each batch contains at most [`Stmt::fetch_array_size`]
+    /// rows. See [`RecordBatches`].
+    #[cfg(feature = "arrow")]
+    pub fn record_batches(&self) -> Result<RecordBatches<'_>> {
+        RecordBatches::new(self)
     }
 
     pub fn delete_from_cache(&self) -> Result<()> {
         call!(dpiStmt_deleteFromCache(self.handle))
     }
 
+    /// # Note
+    /// When the statement is a query, LOB columns are defined as described in
+    /// [`Stmt::set_inline_lob_max_size`] and then the define handler set by
+    /// [`Conn::set_define_handler`] or [`Stmt::set_define_handler`] is called
+    /// for each column before this returns.
     #[maybe_async]
     pub async fn execute(&self, mode: ExecMode) -> Result<u32> {
-        Ok(*get_value_blocking! {
+        let guard = self.fetch_guard()?;
+        let num_query_columns = *get_value_blocking! {
             let handle = self.handle;
             dpiStmt_execute(*handle, mode.bits())
         }
-        .await?)
+        .await?;
+        // Columns are defined by Stmt::define_value, which takes the guard.
+        drop(guard);
+        self.inline_lobs.lock().unwrap().clear();
+        if self.inline_lob_max_size.is_some() || self.define_handler.is_some() {
+            for pos in 1..=num_query_columns {
+                let info = self.query_info(pos)?;
+                if let Some(max_size) = self.inline_lob_max_size {
+                    inline_lobs::define_inline_lob(self, pos, &info, max_size)?;
+                }
+                if let Some(handler) = &self.define_handler {
+                    handler(self, pos, &info)?;
+                }
+            }
+        }
+        Ok(num_query_columns)
     }
 
     #[maybe_async]
     pub async fn execute_many(&self, mode: ExecMode, num_iters: u32) -> Result<()> {
+        let _guard = self.fetch_guard()?;
         call_blocking! {
             let handle = self.handle;
             dpiStmt_executeMany(*handle, mode.to_dpi(), num_iters)
@@ -360,31 +661,85 @@ impl Stmt {
 
     #[maybe_async]
     pub async fn fetch(&self) -> Result<Option<u32>> {
+        let _guard = self.fetch_guard()?;
         let (found, buffer_row_index) = get_2values_blocking! {
             let handle = self.handle;
             dpiStmt_fetch(*handle)
         }
         .await?;
-        Ok(if *found != 0 {
-            Some(*buffer_row_index)
-        } else {
-            None
-        })
+        if *found == 0 {
+            return Ok(None);
+        }
+        self.replace_long_inline_lobs(*buffer_row_index..(*buffer_row_index + 1))
+            .await?;
+        Ok(Some(*buffer_row_index))
     }
 
     #[maybe_async]
     pub async fn fetch_rows(&self, max_rows: u32) -> Result<FetchRowsResult> {
+        let _guard = self.fetch_guard()?;
+        self.fetch_rows_unguarded(max_rows).await
+    }
+
+    #[maybe_async]
+    async fn fetch_rows_unguarded(&self, max_rows: u32) -> Result<FetchRowsResult> {
         let (buffer_row_index, num_rows_fetched, more_rows) = get_3values_blocking! {
             let handle = self.handle;
             dpiStmt_fetchRows(*handle, max_rows)
         }
         .await?;
+        let indexes = *buffer_row_index..(*buffer_row_index + *num_rows_fetched);
+        self.replace_long_inline_lobs(indexes.clone()).await?;
         Ok(FetchRowsResult {
-            indexes: *buffer_row_index..(*buffer_row_index + *num_rows_fetched),
+            indexes,
             more_rows: more_rows.to_rust(),
         })
     }
 
+    /// Replaces inline LOB values longer than [`Stmt::inline_lob_max_size`]
+    /// in fetched `rows` by temporary LOBs
+    #[maybe_async]
+    async fn replace_long_inline_lobs(&self, rows: Range<u32>) -> Result<()> {
+        if !self.inline_lobs.lock().unwrap().has_columns() {
+            return Ok(());
+        }
+        let stmt = ForceSend(self.clone());
+        task::spawn_blocking(move || inline_lobs::replace_long_values(&stmt, rows)).await
+    }
+
+    /// Returns rows converted by [`FromRow`]
+    ///
+    /// This must be called after the statement is executed. The returned
+    /// value is an [`Iterator`] in sync builds and a [`Stream`] in async
+    /// builds. In async builds, rows in a fetch array are fetched and
+    /// converted in one blocking task.
+    ///
+    /// ```ignore
+    /// stmt.execute(ExecMode::DEFAULT)?;
+    /// for row in stmt.rows::<(i32, Option<String>)>()? {
+    ///     let (empno, ename) = row?;
+    /// }
+    /// ```
+    ///
+    /// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+    pub fn rows<T>(&self) -> Result<Rows<'_, T>>
+    where
+        T: FromRow,
+    {
+        Rows::new(self)
+    }
+
+    /// Fetches rows and returns them as [`RowBatch`]
+    ///
+    /// This is the same as [`Stmt::fetch_rows`] except that fetched column
+    /// values are got from the returned value without `unsafe`.
+    #[maybe_async]
+    pub async fn fetch_row_batch(&mut self, max_rows: u32) -> Result<RowBatch<'_>> {
+        let guard = self.fetch_guard()?;
+        let result = self.fetch_rows_unguarded(max_rows).await?;
+        RowBatch::new(self, result, guard)
+    }
+
     pub fn batch_error_count(&self) -> Result<u32> {
         get_value!(dpiStmt_getBatchErrorCount(self.handle))
     }
@@ -447,7 +802,9 @@ impl Stmt {
         Ok(if handle.is_null() {
             None
         } else {
-            Some(Stmt::new(handle))
+            let mut stmt = Stmt::new(handle);
+            stmt.set_parent(self);
+            Some(stmt)
         })
     }
 
@@ -485,21 +842,46 @@ impl Stmt {
     where
         T: FromSql,
     {
+        if let Some(lob) = self.inline_lobs.lock().unwrap().lob(None, pos) {
+            return <T as FromSql>::from_sql(NativeValue::Lob(Some(lob)));
+        }
         let (native_type_num, data) = get_2values!(dpiStmt_getQueryValue(self.handle, pos))?;
         let native_type = native_type_num.try_to_rust()?;
-        <T as FromSql>::from_sql(NativeValue::from_dpi_data(unsafe { &*data }, native_type)?)
+        <T as FromSql>::from_sql(
+            NativeValue::from_dpi_data(unsafe { &*data }, native_type)?
+                .with_object_type(self.query_object_type(pos, native_type)?.as_ref())
+                .with_parent_stmt(self),
+        )
     }
 
     pub unsafe fn query_value_unsafe<'a, T>(&'a self, pos: u32) -> Result<T>
     where
         T: FromSqlUnsafe<'a>,
     {
+        if let Some(lob) = self.inline_lobs.lock().unwrap().lob(None, pos) {
+            return <T as FromSqlUnsafe>::from_sql_unsafe(NativeValue::Lob(Some(lob)));
+        }
         let (native_type_num, data) = get_2values!(dpiStmt_getQueryValue(self.handle, pos))?;
         let native_type = native_type_num.try_to_rust()?;
-        <T as FromSqlUnsafe>::from_sql_unsafe(NativeValue::from_dpi_data(
-            unsafe { &*data },
-            native_type,
-        )?)
+        <T as FromSqlUnsafe>::from_sql_unsafe(
+            NativeValue::from_dpi_data(unsafe { &*data }, native_type)?
+                .with_object_type(self.query_object_type(pos, native_type)?.as_ref())
+                .with_parent_stmt(self),
+        )
+    }
+
+    // Returns the object type of the column at `pos` when its values are objects.
+    pub(crate) fn query_object_type(
+        &self,
+        pos: u32,
+        native_type: NativeType,
+    ) -> Result<Option<ObjectType>> {
+        if native_type == NativeType::Object {
+            let info = get_value!(dpiStmt_getQueryInfo(self.handle, pos))?;
+            Ok(ObjectType::with_add_ref(info.typeInfo.objectType))
+        } else {
+            Ok(None)
+        }
     }
 
     pub fn row_count(&self) -> Result<u64> {
@@ -517,6 +899,7 @@ impl Stmt {
 
     #[maybe_async]
     pub async fn scroll(&self, mode: FetchMode, offset: i32, row_count_offset: i32) -> Result<()> {
+        let _guard = self.fetch_guard()?;
         call_blocking! {
             let handle = self.handle;
             dpiStmt_scroll(
@@ -530,6 +913,7 @@ impl Stmt {
     }
 
     pub fn set_fetch_array_size(&self, array_size: u32) -> Result<()> {
+        let _guard = self.fetch_guard()?;
         call!(dpiStmt_setFetchArraySize(self.handle, array_size))
     }
 
@@ -547,22 +931,377 @@ impl Stmt {
         ))
     }
 
+    /// Sets a callback defining query columns after [`Stmt::execute`]
+    ///
+    /// This replaces the handler inherited from the connection or the parent
+    /// statement. Implicit results and REF CURSORs got from query columns
+    /// after this call inherit the handler. See [`DefineHandler`].
+    pub fn set_define_handler<T>(&mut self, handler: T)
+    where
+        T: Fn(&Stmt, u32, &QueryInfo) -> Result<()> + Send + Sync + 'static,
+    {
+        self.define_handler = Some(Arc::new(handler));
+    }
+
+    /// Removes the callback set by [`Stmt::set_define_handler`] or inherited
+    /// from the connection or the parent statement
+    pub fn remove_define_handler(&mut self) {
+        self.define_handler = None;
+    }
+
+    /// Returns the value set by [`Stmt::set_inline_lob_max_size`]
+    pub fn inline_lob_max_size(&self) -> Option<u32> {
+        self.inline_lob_max_size
+    }
+
+    /// Sets the maximum size of CLOB, NCLOB and BLOB values fetched inline
+    ///
+    /// When this is `Some(max_size)`, [`Stmt::execute`] defines CLOB, NCLOB
+    /// and BLOB columns as [`OracleType::LongVarchar`], [`OracleType::LongNvarchar`]
+    /// and [`OracleType::LongRaw`] respectively. Their values are fetched with
+    /// rows and are got as `String` or `Vec<u8>` instead of [`Lob`] locators,
+    /// which need round trips to read contents.
+    ///
+    /// Values longer than `max_size` bytes fall back to [`Lob`]s. They are
+    /// copied to temporary LOBs when rows are fetched and are got only as
+    /// [`Lob`]. Columns whose [`DataTypeInfo::client_size_in_bytes`] exceeds
+    /// `max_size` are fetched as locators.
+    ///
+    /// The default is `None`, which fetches all LOB columns as locators.
+    ///
+    /// [`DataTypeInfo::client_size_in_bytes`]: crate::types::DataTypeInfo::client_size_in_bytes
+    pub fn set_inline_lob_max_size(&mut self, max_size: Option<u32>) {
+        self.inline_lob_max_size = max_size;
+    }
+
     pub fn set_prefetch_rows(&self, num_rows: u32) -> Result<()> {
         call!(dpiStmt_setPrefetchRows(self.handle, num_rows))
     }
 }
 
+// NULL LOBs are bound as VARCHAR2 because dpiStmt_bindValueBy* cannot bind
+// the LOB native type and the LOB type of NULL is unknown.
+fn bind_value_native_type_num<T>(data: &dpiData) -> dpiNativeTypeNum
+where
+    T: BindValue,
+{
+    match T::native_type_num() {
+        DPI_NATIVE_TYPE_LOB if data.isNull != 0 => DPI_NATIVE_TYPE_BYTES,
+        native_type_num => native_type_num,
+    }
+}
+
 impl Clone for Stmt {
     fn clone(&self) -> Stmt {
         unsafe { dpiStmt_addRef(self.handle) };
         Stmt {
             handle: self.handle,
+            conn: self.conn.clone(),
+            define_handler: self.define_handler.clone(),
+            fetch_buffer_in_use: self.fetch_buffer_in_use.clone(),
+            inline_lob_max_size: self.inline_lob_max_size,
+            inline_lobs: self.inline_lobs.clone(),
+            defined_vars: self.defined_vars.clone(),
         }
     }
 }
 
+impl fmt::Debug for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> std::result::Result<(), fmt::Error> {
+        f.debug_struct("Stmt")
+            .field("handle", &self.handle)
+            .field("conn", &self.conn)
+            .field(
+                "define_handler",
+                &self.define_handler.as_ref().map(|_| FmtEllipsis()),
+            )
+            .field("inline_lob_max_size", &self.inline_lob_max_size)
+            .finish()
+    }
+}
+
 impl Drop for Stmt {
     fn drop(&mut self) {
         release_handle!(dpiStmt_release(self.handle));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::conn::Conn;
+    use crate::stmt::{BindValue, ExecMode, Stmt};
+    use crate::types::{FromSql, Lob, NativeType, OracleType, VectorInfo};
+    use crate::{maybe_async, test_util, Result, VersionInfo};
+    use std::sync::{Arc, Mutex};
+
+    // Returns the Oracle type and the value of `value` selected from dual.
+    #[maybe_async]
+    async fn select_value<T, U>(conn: &Conn, value: &T) -> Result<(Option<OracleType>, U)>
+    where
+        T: BindValue,
+        U: FromSql,
+    {
+        let stmt = conn.prepare_stmt(false, "select :1 from dual", "")?;
+        stmt.bind_value_by_pos(1, value)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        Ok((
+            stmt.query_info(1)?.type_info.oracle_type,
+            stmt.query_value(1)?,
+        ))
+    }
+
+    #[crate::test]
+    async fn define_handler_set_through_conn_clone() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let positions = Arc::new(Mutex::new(Vec::new()));
+        let handler_positions = positions.clone();
+        // The handler set through a clone is shared by the original connection.
+        conn.clone().set_define_handler(move |_stmt, pos, _info| {
+            handler_positions.lock().unwrap().push(pos);
+            Ok(())
+        });
+        let stmt = conn.prepare_stmt(false, "select 1, 2 from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert_eq!(*positions.lock().unwrap(), [1, 2]);
+
+        conn.clone().remove_define_handler();
+        positions.lock().unwrap().clear();
+        let stmt = conn.prepare_stmt(false, "select 1, 2 from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert!(positions.lock().unwrap().is_empty());
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_strings_and_bytes() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let (oracle_type, value) = select_value::<_, String>(&conn, &"abc").await?;
+        assert_eq!(oracle_type, Some(OracleType::Varchar));
+        assert_eq!(value, "abc");
+        let (oracle_type, value) = select_value::<_, String>(&conn, &"def".to_string()).await?;
+        assert_eq!(oracle_type, Some(OracleType::Varchar));
+        assert_eq!(value, "def");
+
+        let bytes: &[u8] = &[0, 1, 255];
+        let (oracle_type, value) = select_value::<_, Vec<u8>>(&conn, &bytes).await?;
+        assert_eq!(oracle_type, Some(OracleType::Raw));
+        assert_eq!(value, [0, 1, 255]);
+        let (oracle_type, value) = select_value::<_, Vec<u8>>(&conn, &vec![2u8, 3]).await?;
+        assert_eq!(oracle_type, Some(OracleType::Raw));
+        assert_eq!(value, [2, 3]);
+        let (oracle_type, value) =
+            select_value::<_, Option<Vec<u8>>>(&conn, &Option::<Vec<u8>>::None).await?;
+        assert_eq!(oracle_type, Some(OracleType::Raw));
+        assert_eq!(value, None);
+
+        let stmt = conn.prepare_stmt(false, "select :name from dual", "")?;
+        stmt.bind_value_by_name("name", &vec![4u8])?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<Vec<u8>>(1)?, [4]);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_lobs() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let clob = conn.new_temp_lob(OracleType::Clob).await?;
+        clob.set_from_bytes("clob value").await?;
+        let (oracle_type, lob) = select_value::<_, Lob>(&conn, &clob).await?;
+        assert_eq!(oracle_type, Some(OracleType::Clob));
+        let mut buf = [0u8; 20];
+        let len = lob.read_bytes(1, 20, &mut buf).await?;
+        assert_eq!(&buf[..len], b"clob value");
+
+        let blob = conn.new_temp_lob(OracleType::Blob).await?;
+        blob.set_from_bytes([1u8, 2, 3]).await?;
+        let (oracle_type, lob) = select_value::<_, Lob>(&conn, &Some(blob)).await?;
+        assert_eq!(oracle_type, Some(OracleType::Blob));
+        let len = lob.read_bytes(1, 20, &mut buf).await?;
+        assert_eq!(&buf[..len], [1, 2, 3]);
+
+        let (_, value) = select_value::<_, Option<String>>(&conn, &Option::<Lob>::None).await?;
+        assert_eq!(value, None);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_json() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let ver = VersionInfo::new(21, 0, 0, 0, 0);
+        if !test_util::check_version(&conn, &ver, &ver).await? {
+            return Ok(());
+        }
+        let json = conn.new_json()?;
+        json.set_owned_value(&crate::types::json::Value::String("json value".into()))?;
+        let stmt = conn.prepare_stmt(false, "select json_value(:1, '$') from dual", "")?;
+        stmt.bind_value_by_pos(1, &json)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<String>(1)?, "json value");
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_vectors() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let ver = VersionInfo::new(23, 4, 0, 0, 0);
+        if !test_util::check_version(&conn, &ver, &ver).await? {
+            return Ok(());
+        }
+        let sql = "select vector_dimension_count(:1) from dual";
+        let info = VectorInfo::Float32(vec![1.0, 2.0, 3.0].into());
+        let vector = conn.new_vector(Some(&info))?;
+        let stmt = conn.prepare_stmt(false, sql, "")?;
+        stmt.bind_value_by_pos(1, &vector)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<u32>(1)?, 3);
+
+        let info = VectorInfo::Float64(vec![1.0, 2.0].into());
+        let stmt = conn.prepare_stmt(false, sql, "")?;
+        stmt.bind_value_by_pos(1, &info)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<u32>(1)?, 2);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_ref_cursor() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let cursor = conn.prepare_stmt(false, "select 'row' from dual", "")?;
+        cursor.execute(ExecMode::DEFAULT).await?;
+        let var = conn.new_var(
+            OracleType::Varchar,
+            NativeType::Bytes,
+            1,
+            10,
+            true,
+            false,
+            None,
+        )?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "declare
+               c sys_refcursor := :1;
+             begin
+               fetch c into :2;
+               close c;
+             end;",
+            "",
+        )?;
+        stmt.bind_value_by_pos(1, &cursor)?;
+        stmt.bind_by_pos(2, &var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert_eq!(var.value::<String>()?, "row");
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn inline_lobs() -> Result<()> {
+        let conn = test_util::connect().await?;
+        // CLOB values from 3000 to 30000 bytes over three fetch arrays
+        let mut stmt = conn.prepare_stmt(
+            false,
+            "select level, rpad(to_clob('x'), level * 3000, 'x'), \
+                    to_blob(hextoraw('0102')), to_nclob('n') \
+             from dual connect by level <= 10",
+            "",
+        )?;
+        stmt.set_inline_lob_max_size(Some(32767));
+        stmt.set_fetch_array_size(4)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let mut num_rows = 0;
+        while stmt.fetch().await?.is_some() {
+            num_rows += 1;
+            let len = stmt.query_value::<usize>(1)? * 3000;
+            let clob = stmt.query_value::<String>(2)?;
+            assert_eq!(clob.len(), len);
+            assert!(clob.bytes().all(|b| b == b'x'));
+            assert_eq!(stmt.query_value::<Vec<u8>>(3)?, [1, 2]);
+            assert_eq!(stmt.query_value::<String>(4)?, "n");
+        }
+        assert_eq!(num_rows, 10);
+
+        // REF CURSORs from query columns inherit the size.
+        let stmt = conn.prepare_stmt(
+            false,
+            "select cursor(select to_clob('abc') from dual) from dual",
+            "",
+        )?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        let cursor = stmt.query_value::<Stmt>(1)?;
+        assert_eq!(cursor.inline_lob_max_size(), None);
+        let mut stmt = conn.prepare_stmt(
+            false,
+            "select cursor(select to_clob('abc') from dual) from dual",
+            "",
+        )?;
+        stmt.set_inline_lob_max_size(Some(10));
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        let cursor = stmt.query_value::<Stmt>(1)?;
+        assert_eq!(cursor.inline_lob_max_size(), Some(10));
+        let row = cursor.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(cursor.query_value::<String>(1)?, "abc");
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn inline_lobs_over_max_size() -> Result<()> {
+        let conn = test_util::connect().await?;
+        // CLOB values of 600, 1200 and 1800 bytes and a BLOB value of 1500 bytes
+        let sql = "select level, rpad(to_clob('x'), level * 600, 'x'), \
+                          case level when 3 then to_blob(hextoraw(rpad('01', 3000, '01'))) end \
+                   from dual connect by level <= 3";
+
+        // Values longer than the max size fall back to LOBs.
+        let mut stmt = conn.prepare_stmt(false, sql, "")?;
+        stmt.set_inline_lob_max_size(Some(1000));
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<String>(2)?, "x".repeat(600));
+        assert_eq!(stmt.query_value::<Option<Vec<u8>>>(3)?, None);
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert!(stmt.query_value::<String>(2).is_err());
+        let lob = stmt.query_value::<Lob>(2)?;
+        assert_eq!(lob.get_type()?, OracleType::Clob);
+        let mut buf = vec![0u8; 2000];
+        let len = lob.read_bytes(1, 2000, &mut buf).await?;
+        assert_eq!(&buf[..len], "x".repeat(1200).as_bytes());
+
+        // RowBatch returns the same values.
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let batch = stmt.fetch_row_batch(10).await?;
+        let mut indexes = batch.indexes();
+        assert_eq!(indexes.len(), 3);
+        let index = indexes.next().unwrap();
+        assert_eq!(batch.value::<String>(index, 2)?, "x".repeat(600));
+        let index = indexes.next().unwrap();
+        let lob = batch.value::<Lob>(index, 2)?;
+        let size = lob.size().await?;
+        assert_eq!(size, 1200);
+        let index = indexes.next().unwrap();
+        let lob = batch.value::<Lob>(index, 2)?;
+        let size = lob.size().await?;
+        assert_eq!(size, 1800);
+        let lob = batch.value::<Lob>(index, 3)?;
+        assert_eq!(lob.get_type()?, OracleType::Blob);
+        let len = lob.read_bytes(1, 2000, &mut buf).await?;
+        assert_eq!(&buf[..len], [1u8; 1500]);
+        Ok(())
+    }
+}
@@ -0,0 +1,813 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+#[cfg(feature = "is_async")]
+use crate::stmt::FetchRowsResult;
+use crate::stmt::{inline_lobs, ColumnFetcher, QueryInfo, Stmt};
+use crate::types::{NativeType, OracleType, Rowid, Vector, VectorFlags, VectorFormat, VectorInfo};
+use crate::*;
+use ::arrow::array::{
+    ArrayRef, BinaryBuilder, BooleanBuilder, Decimal128Builder, DurationNanosecondBuilder,
+    FixedSizeListArray, Float32Array, Float32Builder, Float64Array, Float64Builder, Int64Builder,
+    Int8Array, IntervalYearMonthBuilder, ListArray, StringBuilder, TimestampMicrosecondBuilder,
+    TimestampMillisecondBuilder, TimestampNanosecondBuilder, TimestampSecondBuilder, UInt8Array,
+};
+use ::arrow::buffer::{NullBuffer, OffsetBuffer};
+use ::arrow::datatypes::{DataType, Field, IntervalUnit, Schema, SchemaRef, TimeUnit};
+use ::arrow::record_batch::RecordBatch;
+use odpic_sys::*;
+#[cfg(feature = "is_async")]
+use std::future::Future;
+#[cfg(feature = "is_async")]
+use std::pin::Pin;
+use std::str;
+use std::sync::Arc;
+#[cfg(feature = "is_async")]
+use std::task::{ready, Context, Poll};
+
+const UTC: &str = "+00:00";
+
+#[cfg(feature = "is_async")]
+type FetchFuture = Pin<Box<dyn Future<Output = Result<(u32, u32, i32)>> + Send>>;
+
+/// [Arrow] record batches of a query created by [`Stmt::record_batches`]
+///
+/// This implements [`Iterator`] when none of async runtime features are
+/// enabled and [`Stream`] otherwise. Each record batch holds rows fetched at
+/// a time. Its maximum size is the fetch array size.
+///
+/// Columns are converted as follows.
+///
+/// | Oracle type | Arrow type |
+/// |---|---|
+/// | `NUMBER(p, 0)` where p <= 18 | `Int64` |
+/// | `NUMBER(p, s)` | `Decimal128(max(p, s), s)` |
+/// | `NUMBER`, `FLOAT`, `BINARY_DOUBLE` | `Float64` |
+/// | `BINARY_FLOAT` | `Float32` |
+/// | `VARCHAR2`, `NVARCHAR2`, `CHAR`, `NCHAR`, `LONG`, `CLOB`, `NCLOB`, `JSON`, `ROWID` | `Utf8` |
+/// | `RAW`, `LONG RAW`, `BLOB` | `Binary` |
+/// | `BOOLEAN` | `Boolean` |
+/// | `DATE` | `Timestamp(Second, None)` |
+/// | `TIMESTAMP(fs)` | `Timestamp(unit, None)` |
+/// | `TIMESTAMP(fs) WITH [LOCAL] TIME ZONE` | `Timestamp(unit, Some("+00:00"))` |
+/// | `INTERVAL DAY TO SECOND` | `Duration(Nanosecond)` |
+/// | `INTERVAL YEAR TO MONTH` | `Interval(YearMonth)` |
+/// | `VECTOR(n, format)` | `FixedSizeList(item, n)` |
+/// | `VECTOR(*, format)`, `VECTOR(n, *)` | `List(item)` |
+///
+/// The unit of timestamps is `Second`, `Millisecond`, `Microsecond` or
+/// `Nanosecond` depending on the fractional seconds precision. Timestamps with
+/// time zone are converted to UTC. Oracle dates before 1582-10-15 are in the
+/// Julian calendar. They are converted to the same points in time, which are
+/// displayed as different dates in the proleptic Gregorian calendar of Arrow.
+/// For example, `1500-01-01` is converted to `1500-01-10`.
+///
+/// The item type of vectors is `Float32`, `Float64`, `Int8` or `UInt8`
+/// (packed bits of `BINARY` vectors) depending on the vector format. It is
+/// `Float64` when the format is flexible. Sparse vectors are converted to
+/// dense ones.
+///
+/// `CLOB`, `NCLOB`, `BLOB` and `JSON` columns are fetched inline as long
+/// strings or long raw values.
+///
+/// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+/// [Arrow]: https://docs.rs/arrow/
+pub struct RecordBatches<'a> {
+    fetcher: ColumnFetcher<'a>,
+    schema: SchemaRef,
+    columns: Vec<Column>,
+    done: bool,
+    #[cfg(feature = "is_async")]
+    future: Option<FetchFuture>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Column {
+    Int64,
+    Decimal(u8, i8),
+    Float32,
+    Float64,
+    Utf8,
+    Rowid,
+    Binary,
+    Boolean,
+    Timestamp(TimeUnit, bool),
+    Duration,
+    IntervalYM,
+    Vector(VectorItem, Option<i32>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum VectorItem {
+    Int8,
+    UInt8,
+    Float32,
+    Float64,
+}
+
+impl Column {
+    /// Returns the column kind and the Oracle type, the native type and
+    /// the size of the variable defined for the column.
+    fn new(info: &QueryInfo) -> Result<(Column, OracleType, NativeType, u32)> {
+        let type_info = &info.type_info;
+        let size = type_info.client_size_in_bytes;
+        // LOB columns are defined as Stmt::set_inline_lob_max_size does.
+        if let Some(oracle_type) = inline_lobs::inline_lob_type(type_info.oracle_type) {
+            let column = if oracle_type == OracleType::LongRaw {
+                Column::Binary
+            } else {
+                Column::Utf8
+            };
+            return Ok((column, oracle_type, NativeType::Bytes, size));
+        }
+        Ok(match type_info.oracle_type {
+            Some(OracleType::Number) => {
+                let precision = type_info.precision;
+                let scale = type_info.scale;
+                if precision > 0 && scale == 0 && precision <= 18 {
+                    (Column::Int64, OracleType::Number, NativeType::Int64, 0)
+                } else if precision > 0 && scale != -127 {
+                    // The precision is less than the scale for numbers
+                    // such as NUMBER(2, 5), whose values are less than 0.001.
+                    let column = Column::Decimal(precision.max(scale.into()).try_into()?, scale);
+                    (column, OracleType::Number, NativeType::Bytes, 0)
+                } else {
+                    (Column::Float64, OracleType::Number, NativeType::Double, 0)
+                }
+            }
+            Some(OracleType::NativeFloat) => (
+                Column::Float32,
+                OracleType::NativeFloat,
+                NativeType::Float,
+                0,
+            ),
+            Some(OracleType::NativeDouble) => (
+                Column::Float64,
+                OracleType::NativeDouble,
+                NativeType::Double,
+                0,
+            ),
+            Some(
+                oracle_type @ (OracleType::Varchar
+                | OracleType::Nvarchar
+                | OracleType::Char
+                | OracleType::Nchar
+                | OracleType::LongVarchar
+                | OracleType::LongNvarchar),
+            ) => (Column::Utf8, oracle_type, NativeType::Bytes, size),
+            Some(OracleType::Json) => (Column::Utf8, OracleType::LongVarchar, NativeType::Bytes, 0),
+            Some(OracleType::Rowid) => (Column::Rowid, OracleType::Rowid, NativeType::Rowid, 0),
+            Some(OracleType::Raw) => (Column::Binary, OracleType::Raw, NativeType::Bytes, size),
+            Some(OracleType::LongRaw) => {
+                (Column::Binary, OracleType::LongRaw, NativeType::Bytes, 0)
+            }
+            Some(OracleType::Boolean) => {
+                (Column::Boolean, OracleType::Boolean, NativeType::Boolean, 0)
+            }
+            Some(OracleType::Date) => (
+                Column::Timestamp(TimeUnit::Second, false),
+                OracleType::Date,
+                NativeType::Timestamp,
+                0,
+            ),
+            Some(
+                oracle_type @ (OracleType::Timestamp
+                | OracleType::TimestampTZ
+                | OracleType::TimestampLTZ),
+            ) => {
+                let unit = match type_info.fs_precision {
+                    0 => TimeUnit::Second,
+                    1..=3 => TimeUnit::Millisecond,
+                    4..=6 => TimeUnit::Microsecond,
+                    _ => TimeUnit::Nanosecond,
+                };
+                let with_tz = oracle_type != OracleType::Timestamp;
+                let column = Column::Timestamp(unit, with_tz);
+                (column, oracle_type, NativeType::Timestamp, 0)
+            }
+            Some(OracleType::IntervalDS) => (
+                Column::Duration,
+                OracleType::IntervalDS,
+                NativeType::IntervalDS,
+                0,
+            ),
+            Some(OracleType::IntervalYM) => (
+                Column::IntervalYM,
+                OracleType::IntervalYM,
+                NativeType::IntervalYM,
+                0,
+            ),
+            Some(OracleType::Vector) => {
+                let item = match type_info.vector_format {
+                    Some(VectorFormat::Int8) => VectorItem::Int8,
+                    Some(VectorFormat::Binary) => VectorItem::UInt8,
+                    Some(VectorFormat::Float32) => VectorItem::Float32,
+                    Some(VectorFormat::Float64) | None => VectorItem::Float64,
+                };
+                let len = if type_info.vector_format.is_some()
+                    && type_info.vector_dimensions != 0
+                    && !type_info.vector_flags.contains(VectorFlags::FLEXIBLE_DIM)
+                {
+                    let dims = type_info.vector_dimensions;
+                    let len = if let VectorItem::UInt8 = item {
+                        dims / 8
+                    } else {
+                        dims
+                    };
+                    Some(len.try_into()?)
+                } else {
+                    None
+                };
+                (
+                    Column::Vector(item, len),
+                    OracleType::Vector,
+                    NativeType::Vector,
+                    0,
+                )
+            }
+            _ => {
+                return Err(Error::other(format!(
+                    "unsupported data type {:?} of column {}",
+                    type_info.oracle_type, info.name
+                )))
+            }
+        })
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Column::Int64 => DataType::Int64,
+            Column::Decimal(precision, scale) => DataType::Decimal128(*precision, *scale),
+            Column::Float32 => DataType::Float32,
+            Column::Float64 => DataType::Float64,
+            Column::Utf8 | Column::Rowid => DataType::Utf8,
+            Column::Binary => DataType::Binary,
+            Column::Boolean => DataType::Boolean,
+            Column::Timestamp(unit, with_tz) => {
+                DataType::Timestamp(*unit, with_tz.then(|| UTC.into()))
+            }
+            Column::Duration => DataType::Duration(TimeUnit::Nanosecond),
+            Column::IntervalYM => DataType::Interval(IntervalUnit::YearMonth),
+            Column::Vector(item, len) => {
+                let field = Arc::new(Field::new_list_field(item.data_type(), false));
+                if let Some(len) = len {
+                    DataType::FixedSizeList(field, *len)
+                } else {
+                    DataType::List(field)
+                }
+            }
+        }
+    }
+
+    fn to_array(self, data: &[dpiData]) -> Result<ArrayRef> {
+        macro_rules! build {
+            ($builder:expr, |$value:ident| $expr:expr) => {{
+                let mut builder = $builder;
+                for d in data {
+                    if d.isNull != 0 {
+                        builder.append_null();
+                    } else {
+                        let $value = &d.value;
+                        builder.append_value(unsafe { $expr });
+                    }
+                }
+                Arc::new(builder.finish()) as ArrayRef
+            }};
+        }
+        let len = data.len();
+        Ok(match self {
+            Column::Int64 => build!(Int64Builder::with_capacity(len), |v| v.asInt64),
+            Column::Decimal(precision, scale) => build!(
+                Decimal128Builder::with_capacity(len)
+                    .with_precision_and_scale(precision, scale)
+                    .map_err(|err| Error::other(err.to_string()))?,
+                |v| parse_decimal(bytes(v), scale)?
+            ),
+            Column::Float32 => build!(Float32Builder::with_capacity(len), |v| v.asFloat),
+            Column::Float64 => build!(Float64Builder::with_capacity(len), |v| v.asDouble),
+            Column::Utf8 => build!(StringBuilder::with_capacity(len, 0), |v| str::from_utf8(
+                bytes(v)
+            )?),
+            Column::Rowid => build!(StringBuilder::with_capacity(len, 0), |v| {
+                Rowid::with_add_ref(v.asRowid).string_value()?.to_string()
+            }),
+            Column::Binary => build!(BinaryBuilder::with_capacity(len, 0), |v| bytes(v)),
+            Column::Boolean => build!(BooleanBuilder::with_capacity(len), |v| v.asBoolean != 0),
+            Column::Timestamp(unit, with_tz) => {
+                let tz = with_tz.then_some(UTC);
+                match unit {
+                    TimeUnit::Second => build!(
+                        TimestampSecondBuilder::with_capacity(len).with_timezone_opt(tz),
+                        |v| timestamp(&v.asTimestamp, unit, with_tz)?
+                    ),
+                    TimeUnit::Millisecond => build!(
+                        TimestampMillisecondBuilder::with_capacity(len).with_timezone_opt(tz),
+                        |v| timestamp(&v.asTimestamp, unit, with_tz)?
+                    ),
+                    TimeUnit::Microsecond => build!(
+                        TimestampMicrosecondBuilder::with_capacity(len).with_timezone_opt(tz),
+                        |v| timestamp(&v.asTimestamp, unit, with_tz)?
+                    ),
+                    TimeUnit::Nanosecond => build!(
+                        TimestampNanosecondBuilder::with_capacity(len).with_timezone_opt(tz),
+                        |v| timestamp(&v.asTimestamp, unit, with_tz)?
+                    ),
+                }
+            }
+            Column::Duration => build!(DurationNanosecondBuilder::with_capacity(len), |v| {
+                duration(&v.asIntervalDS)?
+            }),
+            Column::IntervalYM => build!(IntervalYearMonthBuilder::with_capacity(len), |v| {
+                let it = &v.asIntervalYM;
+                it.years
+                    .checked_mul(12)
+                    .and_then(|months| months.checked_add(it.months))
+                    .ok_or_else(|| Error::other("interval year to month overflow"))?
+            }),
+            Column::Vector(item, len) => vector_array(item, len, data)?,
+        })
+    }
+}
+
+impl VectorItem {
+    fn data_type(&self) -> DataType {
+        match self {
+            VectorItem::Int8 => DataType::Int8,
+            VectorItem::UInt8 => DataType::UInt8,
+            VectorItem::Float32 => DataType::Float32,
+            VectorItem::Float64 => DataType::Float64,
+        }
+    }
+}
+
+/// Returns bytes referred by `value.asBytes`
+///
+/// # Safety
+///
+/// `value.asBytes` must be valid.
+unsafe fn bytes(value: &dpiDataBuffer) -> &[u8] {
+    slice::from_raw_parts(
+        value.asBytes.ptr as *const u8,
+        value.asBytes.length as usize,
+    )
+}
+
+/// Parses the text representation of NUMBER and returns the value scaled by `scale`
+fn parse_decimal(bytes: &[u8], scale: i8) -> Result<i128> {
+    let err = || {
+        Error::other(format!(
+            "cannot convert {} to decimal with scale {}",
+            String::from_utf8_lossy(bytes),
+            scale
+        ))
+    };
+    let (negative, digits) = match bytes.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        _ => (false, bytes),
+    };
+    let mut value: i128 = 0;
+    let mut frac_len: i32 = 0;
+    let mut in_frac = false;
+    for &b in digits {
+        match b {
+            b'0'..=b'9' => {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add((b - b'0').into()))
+                    .ok_or_else(err)?;
+                if in_frac {
+                    frac_len += 1;
+                }
+            }
+            b'.' if !in_frac => in_frac = true,
+            _ => return Err(err()),
+        }
+    }
+    let shift = i32::from(scale) - frac_len;
+    let factor = 10i128.checked_pow(shift.unsigned_abs()).ok_or_else(err)?;
+    if shift >= 0 {
+        value = value.checked_mul(factor).ok_or_else(err)?;
+    } else if value % factor == 0 {
+        value /= factor;
+    } else {
+        return Err(err());
+    }
+    Ok(if negative { -value } else { value })
+}
+
+/// Returns the number of days since 1970-01-01 of an Oracle date
+///
+/// Dates before 1582-10-15 are in the Julian calendar. Years before 1 are
+/// negative and year zero doesn't exist.
+fn days_from_date(year: i64, month: i64, day: i64) -> i64 {
+    // astronomical year numbering, where 1 BC is year zero
+    let year = if year < 0 { year + 1 } else { year };
+    if (year, month, day) < (1582, 10, 15) {
+        days_from_julian(year, month, day)
+    } else {
+        days_from_civil(year, month, day)
+    }
+}
+
+/// Returns the number of days since 1970-01-01 in the proleptic Julian calendar
+fn days_from_julian(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    year.div_euclid(4) * 1461 + year.rem_euclid(4) * 365 + doy - 719470
+}
+
+/// Returns the number of days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn timestamp(ts: &dpiTimestamp, unit: TimeUnit, with_tz: bool) -> Result<i64> {
+    let days = days_from_date(ts.year.into(), ts.month.into(), ts.day.into());
+    let mut secs =
+        days * 86400 + i64::from(ts.hour) * 3600 + i64::from(ts.minute) * 60 + i64::from(ts.second);
+    if with_tz {
+        secs -= i64::from(ts.tzHourOffset) * 3600 + i64::from(ts.tzMinuteOffset) * 60;
+    }
+    let fsecond = i64::from(ts.fsecond);
+    let value = match unit {
+        TimeUnit::Second => Some(secs),
+        TimeUnit::Millisecond => secs
+            .checked_mul(1_000)
+            .and_then(|v| v.checked_add(fsecond / 1_000_000)),
+        TimeUnit::Microsecond => secs
+            .checked_mul(1_000_000)
+            .and_then(|v| v.checked_add(fsecond / 1_000)),
+        TimeUnit::Nanosecond => secs
+            .checked_mul(1_000_000_000)
+            .and_then(|v| v.checked_add(fsecond)),
+    };
+    value.ok_or_else(|| Error::other("timestamp out of range"))
+}
+
+fn duration(it: &dpiIntervalDS) -> Result<i64> {
+    let secs = ((i64::from(it.days) * 24 + i64::from(it.hours)) * 60 + i64::from(it.minutes)) * 60
+        + i64::from(it.seconds);
+    secs.checked_mul(1_000_000_000)
+        .and_then(|v| v.checked_add(it.fseconds.into()))
+        .ok_or_else(|| Error::other("interval day to second out of range"))
+}
+
+/// Dense vector values
+enum Dense {
+    Int8(Vec<i8>),
+    UInt8(Vec<u8>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+}
+
+impl Dense {
+    fn new(info: VectorInfo) -> Dense {
+        fn dense<T: Copy + Default>(ndims: u32, values: &[T], indices: &[u32]) -> Vec<T> {
+            let mut vec = vec![T::default(); ndims as usize];
+            for (value, index) in values.iter().zip(indices) {
+                vec[*index as usize] = *value;
+            }
+            vec
+        }
+        match info {
+            VectorInfo::Binary(values) => Dense::UInt8(values.into_owned()),
+            VectorInfo::Int8(values) => Dense::Int8(values.into_owned()),
+            VectorInfo::Float32(values) => Dense::Float32(values.into_owned()),
+            VectorInfo::Float64(values) => Dense::Float64(values.into_owned()),
+            VectorInfo::SparseBinary {
+                ndims,
+                values,
+                indices,
+            } => Dense::UInt8(dense(ndims / 8, &values, &indices)),
+            VectorInfo::SparseInt8 {
+                ndims,
+                values,
+                indices,
+            } => Dense::Int8(dense(ndims, &values, &indices)),
+            VectorInfo::SparseFloat32 {
+                ndims,
+                values,
+                indices,
+            } => Dense::Float32(dense(ndims, &values, &indices)),
+            VectorInfo::SparseFloat64 {
+                ndims,
+                values,
+                indices,
+            } => Dense::Float64(dense(ndims, &values, &indices)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Dense::Int8(v) => v.len(),
+            Dense::UInt8(v) => v.len(),
+            Dense::Float32(v) => v.len(),
+            Dense::Float64(v) => v.len(),
+        }
+    }
+}
+
+/// Item values of a vector column
+enum Items {
+    Int8(Vec<i8>),
+    UInt8(Vec<u8>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+}
+
+impl Items {
+    fn new(item: VectorItem) -> Items {
+        match item {
+            VectorItem::Int8 => Items::Int8(Vec::new()),
+            VectorItem::UInt8 => Items::UInt8(Vec::new()),
+            VectorItem::Float32 => Items::Float32(Vec::new()),
+            VectorItem::Float64 => Items::Float64(Vec::new()),
+        }
+    }
+
+    fn extend(&mut self, values: Dense) -> Result<()> {
+        match (self, values) {
+            (Items::Int8(items), Dense::Int8(v)) => items.extend(v),
+            (Items::UInt8(items), Dense::UInt8(v)) => items.extend(v),
+            (Items::Float32(items), Dense::Int8(v)) => items.extend(v.into_iter().map(f32::from)),
+            (Items::Float32(items), Dense::Float32(v)) => items.extend(v),
+            (Items::Float64(items), Dense::Int8(v)) => items.extend(v.into_iter().map(f64::from)),
+            (Items::Float64(items), Dense::Float32(v)) => {
+                items.extend(v.into_iter().map(f64::from))
+            }
+            (Items::Float64(items), Dense::Float64(v)) => items.extend(v),
+            _ => return Err(Error::other("unexpected vector format")),
+        }
+        Ok(())
+    }
+
+    fn pad(&mut self, len: usize) {
+        match self {
+            Items::Int8(items) => items.resize(items.len() + len, 0),
+            Items::UInt8(items) => items.resize(items.len() + len, 0),
+            Items::Float32(items) => items.resize(items.len() + len, 0.0),
+            Items::Float64(items) => items.resize(items.len() + len, 0.0),
+        }
+    }
+
+    fn into_array(self) -> ArrayRef {
+        match self {
+            Items::Int8(items) => Arc::new(Int8Array::from(items)),
+            Items::UInt8(items) => Arc::new(UInt8Array::from(items)),
+            Items::Float32(items) => Arc::new(Float32Array::from(items)),
+            Items::Float64(items) => Arc::new(Float64Array::from(items)),
+        }
+    }
+}
+
+fn vector_array(item: VectorItem, len: Option<i32>, data: &[dpiData]) -> Result<ArrayRef> {
+    let mut items = Items::new(item);
+    let mut validity = Vec::with_capacity(data.len());
+    let mut offsets = Vec::with_capacity(data.len());
+    for d in data {
+        let valid = d.isNull == 0;
+        let num_items = if valid {
+            let vector = Vector::with_add_ref(unsafe { d.value.asVector });
+            let values = Dense::new(vector.value()?);
+            let num_items = values.len();
+            items.extend(values)?;
+            num_items
+        } else {
+            0
+        };
+        if let Some(len) = len {
+            let len = len as usize;
+            if num_items != len {
+                if valid {
+                    return Err(Error::other(format!(
+                        "expected {} vector items but got {}",
+                        len, num_items
+                    )));
+                }
+                items.pad(len);
+            }
+        } else {
+            offsets.push(num_items);
+        }
+        validity.push(valid);
+    }
+    let field = Arc::new(Field::new_list_field(item.data_type(), false));
+    let nulls = Some(NullBuffer::from(validity));
+    let values = items.into_array();
+    let array: ArrayRef = if let Some(len) = len {
+        Arc::new(
+            FixedSizeListArray::try_new(field, len, values, nulls)
+                .map_err(|err| Error::other(err.to_string()))?,
+        )
+    } else {
+        Arc::new(
+            ListArray::try_new(field, OffsetBuffer::from_lengths(offsets), values, nulls)
+                .map_err(|err| Error::other(err.to_string()))?,
+        )
+    };
+    Ok(array)
+}
+
+impl<'a> RecordBatches<'a> {
+    pub(crate) fn new(stmt: &'a Stmt) -> Result<RecordBatches<'a>> {
+        let mut columns = Vec::new();
+        let mut fields = Vec::new();
+        let fetcher = ColumnFetcher::with_types(stmt, |info| {
+            let (column, oracle_type, native_type, size) = Column::new(info)?;
+            fields.push(Field::new(
+                info.name.clone(),
+                column.data_type(),
+                info.null_ok,
+            ));
+            columns.push(column);
+            Ok((oracle_type, native_type, size))
+        })?;
+        Ok(RecordBatches {
+            fetcher,
+            schema: Arc::new(Schema::new(fields)),
+            columns,
+            done: false,
+            #[cfg(feature = "is_async")]
+            future: None,
+        })
+    }
+
+    /// Returns the schema of record batches
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn record_batch(&mut self) -> Result<Option<RecordBatch>> {
+        if !self.fetcher.more_rows() {
+            self.done = true;
+        }
+        let mut arrays = Vec::with_capacity(self.columns.len());
+        for (idx, column) in self.columns.iter().enumerate() {
+            arrays.push(column.to_array(self.fetcher.column_data(idx))?);
+        }
+        if arrays.iter().all(|array| array.is_empty()) {
+            self.done = true;
+            return Ok(None);
+        }
+        RecordBatch::try_new(self.schema.clone(), arrays)
+            .map(Some)
+            .map_err(|err| Error::other(err.to_string()))
+    }
+}
+
+#[sync_impl]
+impl Iterator for RecordBatches<'_> {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Result<RecordBatch>> {
+        if self.done {
+            return None;
+        }
+        let result = self.fetcher.fetch().and_then(|_| self.record_batch());
+        if result.is_err() {
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+#[async_impl(AFIT)]
+impl futures_core::Stream for RecordBatches<'_> {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<RecordBatch>>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        if this.future.is_none() {
+            let guard = match this.fetcher.stmt().fetch_guard() {
+                Ok(guard) => guard,
+                Err(err) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            };
+            let handle = ForceSend(this.fetcher.stmt().handle);
+            let max_rows = this.fetcher.fetch_array_size();
+            this.future = Some(Box::pin(task::spawn_blocking(move || {
+                let _guard = guard;
+                use odpic_sys::blocking::dpiStmt_fetchRows;
+                get_3values!(dpiStmt_fetchRows(*handle, max_rows))
+            })));
+        }
+        let result = ready!(this.future.as_mut().unwrap().as_mut().poll(cx));
+        this.future = None;
+        let result = result.and_then(|(buffer_row_index, num_rows_fetched, more_rows)| {
+            this.fetcher.set_fetched(FetchRowsResult {
+                indexes: buffer_row_index..(buffer_row_index + num_rows_fetched),
+                more_rows: more_rows != 0,
+            });
+            this.record_batch()
+        });
+        if result.is_err() {
+            this.done = true;
+        }
+        Poll::Ready(result.transpose())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        days_from_civil, days_from_date, days_from_julian, parse_decimal, timestamp, TimeUnit,
+    };
+    #[cfg(not(feature = "is_async"))]
+    use crate::stmt::ExecMode;
+    #[cfg(not(feature = "is_async"))]
+    use crate::{test_util, Result};
+    #[cfg(not(feature = "is_async"))]
+    use ::arrow::array::{Array, BinaryArray, StringArray};
+    use odpic_sys::dpiTimestamp;
+
+    #[test]
+    fn decimal() {
+        assert_eq!(parse_decimal(b"123.45", 2).unwrap(), 12345);
+        assert_eq!(parse_decimal(b"-0.5", 3).unwrap(), -500);
+        assert_eq!(parse_decimal(b"12300", -2).unwrap(), 123);
+        assert!(parse_decimal(b"1.234", 2).is_err());
+        assert!(parse_decimal(b"1e5", 0).is_err());
+    }
+
+    #[test]
+    fn timestamp_to_epoch() {
+        let ts = dpiTimestamp {
+            year: 2000,
+            month: 3,
+            day: 1,
+            hour: 9,
+            minute: 30,
+            second: 15,
+            fsecond: 123_456_789,
+            tzHourOffset: 9,
+            tzMinuteOffset: 0,
+        };
+        assert_eq!(
+            timestamp(&ts, TimeUnit::Second, false).unwrap(),
+            951_903_015
+        );
+        assert_eq!(timestamp(&ts, TimeUnit::Second, true).unwrap(), 951_870_615);
+        assert_eq!(
+            timestamp(&ts, TimeUnit::Microsecond, false).unwrap(),
+            951_903_015_123_456
+        );
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(-4712, 1, 1), -2_440_588 + 38);
+        // Julian day number zero
+        assert_eq!(days_from_julian(-4712, 1, 1), -2_440_588);
+        assert_eq!(days_from_date(-4713, 1, 1), -2_440_588);
+        assert_eq!(days_from_date(-1, 12, 31) + 1, days_from_date(1, 1, 1));
+        assert_eq!(
+            days_from_date(1582, 10, 4) + 1,
+            days_from_date(1582, 10, 15)
+        );
+        assert_eq!(days_from_date(1500, 1, 1), days_from_civil(1500, 1, 10));
+    }
+
+    // RecordBatches is an Iterator only in sync builds.
+    #[cfg(not(feature = "is_async"))]
+    #[crate::test]
+    async fn lob_columns() -> Result<()> {
+        let conn = test_util::connect().await?;
+        // LOB values longer than 32767 bytes are fetched inline.
+        let stmt = conn.prepare_stmt(
+            false,
+            "select rpad(to_clob('x'), 40000, 'x'), to_blob(hextoraw('0102')) from dual",
+            "",
+        )?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let batches = stmt.record_batches()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(batches.len(), 1);
+        let clob = batches[0].column(0);
+        let clob = clob.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(clob.value(0), "x".repeat(40000));
+        let blob = batches[0].column(1);
+        let blob = blob.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(blob.value(0), [1, 2]);
+        Ok(())
+    }
+}
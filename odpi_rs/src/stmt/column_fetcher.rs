@@ -0,0 +1,224 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::maybe_async;
+use crate::stmt::{FetchRowsResult, QueryInfo, Stmt, Var};
+use crate::types::{FromSql, NativeType, NativeValue, OracleType};
+use crate::*;
+use std::ops::Range;
+
+/// Column-major fetcher created by [`Stmt::column_fetcher`]
+///
+/// This defines a variable, whose maximum array size is the fetch array
+/// size, for each query column and copies fetched values from the variables
+/// to per-column vectors without building rows.
+///
+/// # Examples
+///
+/// ```ignore
+/// stmt.set_fetch_array_size(1000)?;
+/// stmt.execute(ExecMode::DEFAULT)?;
+/// let mut fetcher = stmt.column_fetcher()?;
+/// let mut ids = Vec::<Option<i64>>::new();
+/// let mut names = Vec::<Option<String>>::new();
+/// while fetcher.fetch()? > 0 {
+///     fetcher.extend_column(1, &mut ids)?;
+///     fetcher.extend_column(2, &mut names)?;
+/// }
+/// ```
+pub struct ColumnFetcher<'a> {
+    stmt: &'a Stmt,
+    vars: Vec<Var>,
+    fetch_array_size: u32,
+    indexes: Range<u32>,
+    more_rows: bool,
+}
+
+impl<'a> ColumnFetcher<'a> {
+    pub(crate) fn new(stmt: &'a Stmt) -> Result<ColumnFetcher<'a>> {
+        ColumnFetcher::with_types(stmt, |info| {
+            let type_info = &info.type_info;
+            match (type_info.oracle_type, type_info.default_native_type) {
+                (Some(oracle_type), Some(native_type)) => {
+                    Ok((oracle_type, native_type, type_info.client_size_in_bytes))
+                }
+                _ => Err(Error::other(format!(
+                    "unsupported data type of column {}",
+                    info.name
+                ))),
+            }
+        })
+    }
+
+    /// Creates a fetcher whose variable types are chosen by `f`
+    ///
+    /// `f` returns the Oracle type, the native type and the size in bytes.
+    pub(crate) fn with_types<F>(stmt: &'a Stmt, mut f: F) -> Result<ColumnFetcher<'a>>
+    where
+        F: FnMut(&QueryInfo) -> Result<(OracleType, NativeType, u32)>,
+    {
+        let conn = stmt.conn.as_ref().ok_or_else(|| {
+            Error::other("columns cannot be fetched without the connection of the statement")
+        })?;
+        let fetch_array_size = stmt.fetch_array_size()?;
+        let num_columns = stmt.num_query_columns()?;
+        let mut vars = Vec::with_capacity(num_columns as usize);
+        for pos in 1..=num_columns {
+            let info = stmt.query_info(pos)?;
+            let (oracle_type, native_type, size) = f(&info)?;
+            let var = conn.new_var(
+                oracle_type,
+                native_type,
+                fetch_array_size,
+                size,
+                true,
+                false,
+                info.type_info.object_type.as_ref(),
+            )?;
+            stmt.define(pos, &var)?;
+            vars.push(var);
+        }
+        Ok(ColumnFetcher {
+            stmt,
+            vars,
+            fetch_array_size,
+            indexes: 0..0,
+            more_rows: true,
+        })
+    }
+
+    #[cfg(all(feature = "arrow", feature = "is_async"))]
+    pub(crate) fn stmt(&self) -> &'a Stmt {
+        self.stmt
+    }
+
+    #[cfg(all(feature = "arrow", feature = "is_async"))]
+    pub(crate) fn fetch_array_size(&self) -> u32 {
+        self.fetch_array_size
+    }
+
+    pub(crate) fn set_fetched(&mut self, result: FetchRowsResult) {
+        self.indexes = result.indexes;
+        self.more_rows = result.more_rows;
+    }
+
+    /// Returns `dpiData` of the fetched rows in the `idx`-th variable
+    #[cfg(feature = "arrow")]
+    pub(crate) fn column_data(&self, idx: usize) -> &[odpic_sys::dpiData] {
+        let data = *self.vars[idx].data.lock().unwrap();
+        unsafe {
+            std::slice::from_raw_parts(data.add(self.indexes.start as usize), self.indexes.len())
+        }
+    }
+
+    /// Returns the variables defined for the query columns
+    pub fn vars(&self) -> &[Var] {
+        &self.vars
+    }
+
+    /// Fetches rows up to the fetch array size and returns the number of them
+    ///
+    /// Zero is returned when no more rows exist. Values of the fetched rows
+    /// are valid until the next call.
+    #[maybe_async]
+    pub async fn fetch(&mut self) -> Result<usize> {
+        let result = self.stmt.fetch_rows(self.fetch_array_size).await?;
+        self.set_fetched(result);
+        Ok(self.indexes.len())
+    }
+
+    /// Returns `true` if more rows may be fetched
+    pub fn more_rows(&self) -> bool {
+        self.more_rows
+    }
+
+    /// Returns values of the column at `pos` in the fetched rows
+    ///
+    /// `pos` is one-based.
+    pub fn column<T>(&self, pos: u32) -> Result<Vec<T>>
+    where
+        T: FromSql,
+    {
+        let mut values = Vec::with_capacity(self.indexes.len());
+        self.extend_column(pos, &mut values)?;
+        Ok(values)
+    }
+
+    /// Appends values of the column at `pos` in the fetched rows to `values`
+    ///
+    /// `pos` is one-based.
+    pub fn extend_column<T>(&self, pos: u32, values: &mut Vec<T>) -> Result<()>
+    where
+        T: FromSql,
+    {
+        let var = pos
+            .checked_sub(1)
+            .and_then(|idx| self.vars.get(idx as usize))
+            .ok_or_else(|| Error::other(format!("column position {} out of range", pos)))?;
+        let data = *var.data.lock().unwrap();
+        values.reserve(self.indexes.len());
+        for index in self.indexes.clone() {
+            values.push(<T as FromSql>::from_sql(
+                NativeValue::from_dpi_data(unsafe { &*data.add(index as usize) }, var.native_type)?
+                    .with_object_type(var.objtype.as_ref())
+                    .with_parent_stmt(self.stmt),
+            )?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stmt::ExecMode;
+    use crate::{test_util, Result};
+
+    #[crate::test]
+    async fn fetch_in_chunks() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "select level, decode(mod(level, 3), 0, null, 'row ' || level) \
+             from dual connect by level <= 10",
+            "",
+        )?;
+        stmt.set_fetch_array_size(4)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let mut fetcher = stmt.column_fetcher()?;
+        assert_eq!(fetcher.vars().len(), 2);
+        let mut chunks = Vec::new();
+        let mut ids = Vec::<i64>::new();
+        let mut names = Vec::<Option<String>>::new();
+        loop {
+            let num_rows = fetcher.fetch().await?;
+            if num_rows == 0 {
+                break;
+            }
+            chunks.push(num_rows);
+            assert_eq!(fetcher.column::<i64>(1)?.len(), num_rows);
+            fetcher.extend_column(1, &mut ids)?;
+            fetcher.extend_column(2, &mut names)?;
+        }
+        assert_eq!(chunks, [4, 4, 2]);
+        assert!(!fetcher.more_rows());
+        assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+        let expected = (1..=10)
+            .map(|id| (id % 3 != 0).then(|| format!("row {}", id)))
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+        assert!(fetcher.column::<i64>(0).is_err());
+        assert!(fetcher.column::<i64>(3).is_err());
+        Ok(())
+    }
+}
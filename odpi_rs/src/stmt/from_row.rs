@@ -0,0 +1,256 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::stmt::{RowBatch, Stmt};
+use crate::types::FromSql;
+use crate::{Error, Result};
+
+/// Conversion from a fetched row to a Rust value
+///
+/// This is implemented for tuples, whose elements are got from the query
+/// columns by position, and by `#[derive(FromRow)]` for structs, whose fields
+/// are got from the query columns by name.
+///
+/// [`FromRow::columns`] is called once after the statement is executed and its
+/// result is passed to [`FromRow::from_row`] for each fetched row.
+/// [`Stmt::rows`] does it while fetching rows.
+///
+/// ```ignore
+/// use odpi_rs::stmt::{ExecMode, FromRow};
+///
+/// #[derive(FromRow)]
+/// struct Emp {
+///     empno: i32,
+///     ename: Option<String>,
+///     #[odpi(rename = "MGR")]
+///     manager: Option<i32>,
+/// }
+///
+/// stmt.execute(ExecMode::DEFAULT).await?;
+/// let columns = Emp::columns(&stmt)?;
+/// loop {
+///     let batch = stmt.fetch_row_batch(100).await?;
+///     for index in batch.indexes() {
+///         let emp = Emp::from_row(&batch, index, &columns)?;
+///     }
+///     if !batch.more_rows() {
+///         break;
+///     }
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Information about the query columns such as column positions
+    type Columns;
+
+    /// Gets information about the query columns from the executed statement.
+    fn columns(stmt: &Stmt) -> Result<Self::Columns>;
+
+    /// Converts the row at `index` in `batch` to `Self`.
+    fn from_row(batch: &RowBatch<'_>, index: u32, columns: &Self::Columns) -> Result<Self>;
+}
+
+/// Column positions looked up by name
+///
+/// This is used by code generated by `#[derive(FromRow)]`.
+#[doc(hidden)]
+pub struct ColumnMap {
+    names: Vec<String>,
+    positions: Vec<u32>,
+}
+
+impl ColumnMap {
+    pub fn new(stmt: &Stmt) -> Result<ColumnMap> {
+        let num_columns = stmt.num_query_columns()?;
+        let names = (1..=num_columns)
+            .map(|pos| Ok(stmt.query_info(pos)?.name))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ColumnMap {
+            names,
+            positions: Vec::new(),
+        })
+    }
+
+    /// Finds the column case-insensitively and adds its position.
+    ///
+    /// This fails when no column or more than one column has the name.
+    pub fn add(&mut self, name: &str) -> Result<()> {
+        let name = name.to_uppercase();
+        let mut positions = self
+            .names
+            .iter()
+            .enumerate()
+            .filter(|(_, column_name)| column_name.to_uppercase() == name)
+            .map(|(index, _)| index + 1);
+        let pos = positions
+            .next()
+            .ok_or_else(|| Error::other(format!("column {} not found in the query", name)))?;
+        if let Some(other_pos) = positions.next() {
+            return Err(Error::other(format!(
+                "column {} is ambiguous: found at positions {} and {}",
+                name, pos, other_pos
+            )));
+        }
+        self.positions.push(pos.try_into()?);
+        Ok(())
+    }
+
+    /// Gets the value of the column added by the `index`th call of [`ColumnMap::add`]
+    /// in the row at `row` in `batch`.
+    ///
+    /// Errors are wrapped in [`Error::Column`] with the column name and position.
+    pub fn value<T>(&self, batch: &RowBatch<'_>, row: u32, index: usize) -> Result<T>
+    where
+        T: FromSql,
+    {
+        let pos = self.positions[index];
+        batch.value(row, pos).map_err(|err| Error::Column {
+            name: self.names[pos as usize - 1].clone(),
+            position: pos,
+            source: Box::new(err),
+        })
+    }
+}
+
+macro_rules! from_row_tuple_impl {
+    ($num:literal: $($t:ident $pos:literal),+) => {
+        impl<$($t),+> FromRow for ($($t,)+)
+        where
+            $($t: FromSql,)+
+        {
+            type Columns = ();
+
+            fn columns(stmt: &Stmt) -> Result<()> {
+                let num_columns = stmt.num_query_columns()?;
+                if num_columns < $num {
+                    return Err(Error::other(format!(
+                        concat!("expected at least ", $num, " columns but the query has {}"),
+                        num_columns
+                    )));
+                }
+                Ok(())
+            }
+
+            fn from_row(batch: &RowBatch<'_>, index: u32, _columns: &()) -> Result<Self> {
+                Ok(($(batch.value::<$t>(index, $pos)?,)+))
+            }
+        }
+    };
+}
+
+from_row_tuple_impl! { 1: T1 1 }
+from_row_tuple_impl! { 2: T1 1, T2 2 }
+from_row_tuple_impl! { 3: T1 1, T2 2, T3 3 }
+from_row_tuple_impl! { 4: T1 1, T2 2, T3 3, T4 4 }
+from_row_tuple_impl! { 5: T1 1, T2 2, T3 3, T4 4, T5 5 }
+from_row_tuple_impl! { 6: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6 }
+from_row_tuple_impl! { 7: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7 }
+from_row_tuple_impl! { 8: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8 }
+from_row_tuple_impl! { 9: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9 }
+from_row_tuple_impl! { 10: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10 }
+from_row_tuple_impl! { 11: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11 }
+from_row_tuple_impl! { 12: T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11, T12 12 }
+
+#[cfg(test)]
+mod tests {
+    use super::ColumnMap;
+    use crate::stmt::{ExecMode, FromRow};
+    use crate::{test_util, Error, Result};
+
+    fn column_map(names: &[&str]) -> ColumnMap {
+        ColumnMap {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            positions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn add_columns() {
+        let mut columns = column_map(&["ID", "name", "Value"]);
+        columns.add("value").unwrap();
+        columns.add("NAME").unwrap();
+        columns.add("id").unwrap();
+        assert_eq!(columns.positions, [3, 2, 1]);
+    }
+
+    #[test]
+    fn add_missing_column() {
+        let mut columns = column_map(&["ID"]);
+        let err = columns.add("name").unwrap_err();
+        assert_eq!(err.to_string(), "column NAME not found in the query");
+    }
+
+    #[test]
+    fn add_ambiguous_column() {
+        let mut columns = column_map(&["ID", "NAME", "id"]);
+        let err = columns.add("Id").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column ID is ambiguous: found at positions 1 and 3"
+        );
+        columns.add("name").unwrap();
+        assert_eq!(columns.positions, [2]);
+    }
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct Row {
+        id: i32,
+        #[odpi(rename = "TEXT")]
+        name: Option<String>,
+    }
+
+    #[crate::test]
+    async fn derive_from_row() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let mut stmt = conn.prepare_stmt(false, "select 'foo' text, 1 id from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let columns = Row::columns(&stmt)?;
+        let batch = stmt.fetch_row_batch(1).await?;
+        assert_eq!(batch.len(), 1);
+        let row = Row::from_row(&batch, batch.indexes().start, &columns)?;
+        assert_eq!(
+            row,
+            Row {
+                id: 1,
+                name: Some("foo".into())
+            }
+        );
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn column_errors() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let mut stmt = conn.prepare_stmt(false, "select null text, null id from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let columns = Row::columns(&stmt)?;
+        let batch = stmt.fetch_row_batch(1).await?;
+        match Row::from_row(&batch, batch.indexes().start, &columns) {
+            Err(Error::Column {
+                name,
+                position,
+                source,
+            }) => {
+                assert_eq!(name, "ID");
+                assert_eq!(position, 2);
+                assert!(matches!(*source, Error::NullValue));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let stmt = conn.prepare_stmt(false, "select 1 id, 2 id, 'a' text from dual", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert!(Row::columns(&stmt).is_err());
+        Ok(())
+    }
+}
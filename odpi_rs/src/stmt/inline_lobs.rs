@@ -0,0 +1,161 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::stmt::{QueryInfo, Stmt};
+use crate::types::{Lob, NativeType, OracleType};
+use crate::utils::*;
+use crate::*;
+use odpic_sys::blocking::{dpiConn_newTempLob, dpiLob_writeBytes};
+use odpic_sys::*;
+use std::collections::HashMap;
+use std::ffi::c_char;
+use std::ops::Range;
+
+/// CLOB, NCLOB and BLOB columns fetched inline and temporary LOBs holding
+/// their values longer than [`Stmt::inline_lob_max_size`]
+///
+/// This is shared by clones of a statement as the fetch buffer is.
+#[derive(Default)]
+pub(crate) struct InlineLobs {
+    // positions and LOB types of the columns
+    columns: Vec<(u32, OracleType)>,
+    // temporary LOBs keyed by buffer row indexes and column positions
+    lobs: HashMap<(u32, u32), Lob>,
+    // buffer row index of the last fetched row
+    last_index: u32,
+}
+
+impl InlineLobs {
+    /// Forgets the columns and the values of the previous execution
+    pub(crate) fn clear(&mut self) {
+        self.columns.clear();
+        self.lobs.clear();
+    }
+
+    /// Forgets the column at `pos`, which is defined by other types
+    pub(crate) fn remove_column(&mut self, pos: u32) {
+        self.columns.retain(|column| column.0 != pos);
+    }
+
+    pub(crate) fn has_columns(&self) -> bool {
+        !self.columns.is_empty()
+    }
+
+    /// Returns the LOB replacing the value of the column at `pos` in the
+    /// row at `index` or in the last fetched row when `index` is `None`
+    pub(crate) fn lob(&self, index: Option<u32>, pos: u32) -> Option<Lob> {
+        self.lobs
+            .get(&(index.unwrap_or(self.last_index), pos))
+            .cloned()
+    }
+}
+
+/// Returns the long string or long raw type fetching values of a CLOB,
+/// NCLOB or BLOB column inline
+///
+/// This is shared by [`Stmt::set_inline_lob_max_size`] and the `arrow`
+/// feature. `None` is returned for other types.
+pub(crate) fn inline_lob_type(oracle_type: Option<OracleType>) -> Option<OracleType> {
+    match oracle_type {
+        Some(OracleType::Clob) => Some(OracleType::LongVarchar),
+        Some(OracleType::Nclob) => Some(OracleType::LongNvarchar),
+        Some(OracleType::Blob) => Some(OracleType::LongRaw),
+        _ => None,
+    }
+}
+
+/// Defines a CLOB, NCLOB or BLOB column as a long string or long raw column
+///
+/// The column is defined when the size in `info` is unknown or at most
+/// `max_size`. Other columns are left as they are.
+pub(crate) fn define_inline_lob(
+    stmt: &Stmt,
+    pos: u32,
+    info: &QueryInfo,
+    max_size: u32,
+) -> Result<()> {
+    let (Some(lob_type), Some(oracle_type)) = (
+        info.type_info.oracle_type,
+        inline_lob_type(info.type_info.oracle_type),
+    ) else {
+        return Ok(());
+    };
+    let size = match info.type_info.client_size_in_bytes {
+        0 => max_size,
+        size if size <= max_size => size,
+        _ => return Ok(()),
+    };
+    stmt.define_value(pos, oracle_type, NativeType::Bytes, size, true, None)?;
+    stmt.inline_lobs
+        .lock()
+        .unwrap()
+        .columns
+        .push((pos, lob_type));
+    Ok(())
+}
+
+/// Replaces values longer than the max size in `rows` of inline LOB
+/// columns by temporary LOBs
+///
+/// This must be called after `rows` are fetched. It calls blocking functions.
+pub(crate) fn replace_long_values(stmt: &Stmt, rows: Range<u32>) -> Result<()> {
+    let columns = {
+        let mut inline_lobs = stmt.inline_lobs.lock().unwrap();
+        inline_lobs.lobs.clear();
+        inline_lobs.last_index = rows.end.saturating_sub(1);
+        inline_lobs.columns.clone()
+    };
+    let max_size = match stmt.inline_lob_max_size {
+        Some(max_size) if !columns.is_empty() && !rows.is_empty() => max_size,
+        _ => return Ok(()),
+    };
+    let mut lobs = HashMap::new();
+    for (pos, lob_type) in columns {
+        let (_, data): (dpiNativeTypeNum, *mut dpiData) =
+            get_2values!(dpiStmt_getQueryValue(stmt.handle, pos))?;
+        // dpiStmt_getQueryValue returns the element of the last fetched
+        // row in the dpiData array of the query variable.
+        let data = unsafe { data.sub(rows.end as usize - 1) };
+        for index in rows.clone() {
+            let data = unsafe { &*data.add(index as usize) };
+            if data.isNull != 0 {
+                continue;
+            }
+            let bytes = unsafe { data.value.asBytes };
+            if bytes.length > max_size {
+                lobs.insert((index, pos), new_temp_lob(stmt, lob_type, &bytes)?);
+            }
+        }
+    }
+    stmt.inline_lobs.lock().unwrap().lobs = lobs;
+    Ok(())
+}
+
+fn new_temp_lob(stmt: &Stmt, lob_type: OracleType, bytes: &dpiBytes) -> Result<Lob> {
+    let conn = stmt
+        .conn
+        .as_ref()
+        .ok_or_else(|| Error::other("no connection to create a temporary LOB"))?;
+    let lob = Lob::new(get_value!(dpiConn_newTempLob(
+        conn.handle,
+        lob_type.to_dpi()
+    ))?);
+    call!(dpiLob_writeBytes(
+        lob.handle,
+        1,
+        bytes.ptr as *const c_char,
+        bytes.length as u64
+    ))?;
+    Ok(lob)
+}
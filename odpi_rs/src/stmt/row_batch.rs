@@ -0,0 +1,275 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::stmt::{FetchGuard, FetchRowsResult, Stmt};
+use crate::types::{FromSql, FromSqlUnsafe, NativeType, NativeValue, ObjectType};
+use crate::utils::*;
+use crate::*;
+use odpic_sys::*;
+use std::ops::Range;
+
+/// Rows fetched by [`Stmt::fetch_row_batch`]
+///
+/// This borrows the statement mutably so that no rows are fetched while
+/// this exists. Column values are got for each index in [`RowBatch::indexes`]
+/// without copying data by [`RowBatch::value_ref`].
+///
+/// Columns defined by variables with [`Stmt::define`] can't be borrowed by
+/// [`RowBatch::value_ref`] because the variables may change their buffers.
+/// Use [`RowBatch::value`] for them.
+///
+/// Clones of the statement aren't borrowed. They return an error when
+/// they execute, fetch rows, define columns, change the fetch array size
+/// or close the statement while this exists.
+///
+/// # Examples
+///
+/// ```ignore
+/// loop {
+///     let batch = stmt.fetch_row_batch(100)?;
+///     for index in batch.indexes() {
+///         let name: Option<&str> = batch.value_ref(index, 1)?;
+///     }
+///     if !batch.more_rows() {
+///         break;
+///     }
+/// }
+/// ```
+pub struct RowBatch<'a> {
+    stmt: &'a mut Stmt,
+    result: FetchRowsResult,
+    columns: Vec<Column>,
+    _guard: FetchGuard,
+}
+
+struct Column {
+    native_type: NativeType,
+    objtype: Option<ObjectType>,
+    // the first element of the dpiData array of the query variable
+    data: *const dpiData,
+    // true when the query variable is defined by Stmt::define
+    user_var: bool,
+}
+
+impl<'a> RowBatch<'a> {
+    pub(crate) fn new(
+        stmt: &'a mut Stmt,
+        result: FetchRowsResult,
+        guard: FetchGuard,
+    ) -> Result<RowBatch<'a>> {
+        let mut columns = Vec::new();
+        if !result.indexes.is_empty() {
+            let last_index = result.indexes.end as usize - 1;
+            for pos in 1..=stmt.num_query_columns()? {
+                // dpiStmt_getQueryValue returns the element of the last
+                // fetched row in the dpiData array of the query variable.
+                let (native_type_num, data) =
+                    get_2values!(dpiStmt_getQueryValue(stmt.handle, pos))?;
+                let data: *mut dpiData = unsafe { data.sub(last_index) };
+                let native_type = native_type_num.try_to_rust()?;
+                let user_var = match stmt.defined_vars.lock().unwrap().get(&pos) {
+                    Some(var_data) => *var_data.lock().unwrap() == data,
+                    None => false,
+                };
+                columns.push(Column {
+                    native_type,
+                    objtype: stmt.query_object_type(pos, native_type)?,
+                    data,
+                    user_var,
+                });
+            }
+        }
+        Ok(RowBatch {
+            stmt,
+            result,
+            columns,
+            _guard: guard,
+        })
+    }
+
+    /// Returns buffer row indexes of the fetched rows
+    pub fn indexes(&self) -> Range<u32> {
+        self.result.indexes.clone()
+    }
+
+    /// Returns `true` if more rows may be fetched
+    pub fn more_rows(&self) -> bool {
+        self.result.more_rows
+    }
+
+    /// Returns the number of fetched rows
+    pub fn len(&self) -> usize {
+        self.result.indexes.len()
+    }
+
+    /// Returns `true` if no rows are fetched
+    pub fn is_empty(&self) -> bool {
+        self.result.indexes.is_empty()
+    }
+
+    /// Returns the value of the column at `pos` in the row at `index`
+    ///
+    /// `index` is one of [`RowBatch::indexes`] and `pos` is one-based.
+    pub fn value<T>(&self, index: u32, pos: u32) -> Result<T>
+    where
+        T: FromSql,
+    {
+        <T as FromSql>::from_sql(self.native_value(index, pos)?)
+    }
+
+    /// Returns the value of the column at `pos` in the row at `index`
+    /// borrowing the fetch buffer
+    ///
+    /// This is a safe version of [`Stmt::query_value_unsafe`]. The returned
+    /// value such as `&str` and `&[u8]` is valid while this exists.
+    ///
+    /// An error is returned for columns defined by variables with
+    /// [`Stmt::define`]. Use [`RowBatch::value`] for them.
+    pub fn value_ref<'b, T>(&'b self, index: u32, pos: u32) -> Result<T>
+    where
+        T: FromSqlUnsafe<'b>,
+    {
+        if self.column(pos)?.user_var {
+            return Err(Error::other(format!(
+                "column {} is defined by a variable, whose buffer may be changed while borrowed",
+                pos
+            )));
+        }
+        // The fetch buffer isn't overwritten while the statement is borrowed
+        // and the guard prevents its clones from executing and fetching.
+        unsafe { <T as FromSqlUnsafe>::from_sql_unsafe(self.native_value(index, pos)?) }
+    }
+
+    fn native_value(&self, index: u32, pos: u32) -> Result<NativeValue<'_>> {
+        if !self.result.indexes.contains(&index) {
+            return Err(Error::other(format!(
+                "row index {} out of range {:?}",
+                index, self.result.indexes
+            )));
+        }
+        let column = self.column(pos)?;
+        if let Some(lob) = self.stmt.inline_lobs.lock().unwrap().lob(Some(index), pos) {
+            return Ok(NativeValue::Lob(Some(lob)));
+        }
+        Ok(NativeValue::from_dpi_data(
+            unsafe { &*column.data.add(index as usize) },
+            column.native_type,
+        )?
+        .with_object_type(column.objtype.as_ref())
+        .with_parent_stmt(self.stmt))
+    }
+
+    fn column(&self, pos: u32) -> Result<&Column> {
+        pos.checked_sub(1)
+            .and_then(|idx| self.columns.get(idx as usize))
+            .ok_or_else(|| Error::other(format!("column position {} out of range", pos)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stmt::ExecMode;
+    use crate::types::{NativeType, OracleType};
+    use crate::{test_util, Result};
+
+    #[crate::test]
+    async fn clones_cannot_fetch_while_batch_exists() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let mut stmt =
+            conn.prepare_stmt(false, "select 'a' from dual connect by level <= 3", "")?;
+        let clone = stmt.clone();
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let batch = stmt.fetch_row_batch(1).await?;
+        let fetch_result = clone.fetch_rows(1).await;
+        assert!(fetch_result.is_err());
+        let execute_result = clone.execute(ExecMode::DEFAULT).await;
+        assert!(execute_result.is_err());
+        let value: &str = batch.value_ref(batch.indexes().start, 1)?;
+        assert_eq!(value, "a");
+        drop(batch);
+        let result = clone.fetch_rows(1).await?;
+        assert_eq!(result.indexes.len(), 1);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn clones_cannot_change_fetch_buffer_while_batch_exists() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let mut stmt =
+            conn.prepare_stmt(false, "select 'a' from dual connect by level <= 3", "")?;
+        let clone = stmt.clone();
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let batch = stmt.fetch_row_batch(1).await?;
+        let var = conn.new_var(
+            OracleType::Varchar,
+            NativeType::Bytes,
+            1,
+            10,
+            false,
+            false,
+            None,
+        )?;
+        assert!(clone
+            .define_value(1, OracleType::Varchar, NativeType::Bytes, 10, false, None)
+            .is_err());
+        assert!(clone.define(1, &var).is_err());
+        assert!(clone.set_fetch_array_size(10).is_err());
+        let execute_result = clone.execute_many(ExecMode::DEFAULT, 1).await;
+        assert!(execute_result.is_err());
+        assert!(clone.close("").is_err());
+        let value: &str = batch.value_ref(batch.indexes().start, 1)?;
+        assert_eq!(value, "a");
+        drop(batch);
+        clone.define_value(1, OracleType::Varchar, NativeType::Bytes, 10, false, None)?;
+        clone.set_fetch_array_size(10)?;
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn columns_defined_by_vars_cannot_be_borrowed() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let mut stmt =
+            conn.prepare_stmt(false, "select 'a', 'b' from dual connect by level <= 3", "")?;
+        stmt.set_fetch_array_size(3)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let var = conn.new_var(
+            OracleType::Varchar,
+            NativeType::Bytes,
+            3,
+            10,
+            false,
+            false,
+            None,
+        )?;
+        stmt.define(1, &var)?;
+        let batch = stmt.fetch_row_batch(3).await?;
+        let index = batch.indexes().start;
+        assert!(batch.value_ref::<&str>(index, 1).is_err());
+        assert_eq!(batch.value::<String>(index, 1)?, "a");
+        let value: &str = batch.value_ref(index, 2)?;
+        assert_eq!(value, "b");
+        // The variable can change its buffer while the batch exists.
+        var.set_values(&["c", "c", "c"])?;
+        assert_eq!(batch.value::<String>(index, 1)?, "c");
+        drop(batch);
+
+        // Redefined columns can be borrowed.
+        stmt.define_value(1, OracleType::Varchar, NativeType::Bytes, 10, false, None)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let batch = stmt.fetch_row_batch(3).await?;
+        let value: &str = batch.value_ref(batch.indexes().start, 1)?;
+        assert_eq!(value, "a");
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::stmt::{inline_lobs, FetchGuard, FetchRowsResult, FromRow, RowBatch, Stmt};
+use crate::*;
+use odpic_sys::blocking::dpiStmt_fetchRows;
+use std::collections::VecDeque;
+#[cfg(feature = "is_async")]
+use std::future::Future;
+#[cfg(feature = "is_async")]
+use std::pin::Pin;
+#[cfg(feature = "is_async")]
+use std::task::{ready, Context, Poll};
+
+#[cfg(feature = "is_async")]
+type FetchFuture<T, C> =
+    Pin<Box<dyn Future<Output = Result<(C, VecDeque<Result<T>>, bool)>> + Send>>;
+
+/// Rows converted by [`FromRow`]
+///
+/// This is created by [`Stmt::rows`]. It implements [`Iterator`] when
+/// none of async runtime features are enabled and [`Stream`] otherwise.
+///
+/// Rows are fetched in the unit of the fetch array size, which is set by
+/// [`Stmt::set_fetch_array_size`]. Rows in the fetch array are converted
+/// all at once. In async builds, this is done in one blocking task.
+/// When a row fails to be converted, the rows converted before it are
+/// returned first and then the error is returned. No rows are returned
+/// after the error.
+///
+/// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+pub struct Rows<'a, T>
+where
+    T: FromRow,
+{
+    stmt: &'a Stmt,
+    columns: Option<T::Columns>,
+    rows: VecDeque<Result<T>>,
+    done: bool,
+    #[cfg(feature = "is_async")]
+    future: Option<FetchFuture<T, T::Columns>>,
+}
+
+impl<'a, T> Rows<'a, T>
+where
+    T: FromRow,
+{
+    pub(crate) fn new(stmt: &'a Stmt) -> Result<Rows<'a, T>> {
+        Ok(Rows {
+            stmt,
+            columns: Some(T::columns(stmt)?),
+            rows: VecDeque::new(),
+            done: false,
+            #[cfg(feature = "is_async")]
+            future: None,
+        })
+    }
+}
+
+/// Fetches rows and converts them to `T`
+///
+/// `dpiStmt_fetchRows` is called with the fetch array size as `maxRows`. It
+/// returns rows left in the fetch array or fetches rows from the database
+/// when no rows are left. Inline LOB values longer than the max size are
+/// replaced by temporary LOBs and then each row is converted by
+/// [`FromRow::from_row`] through [`RowBatch`]. Conversion stops at the
+/// first error, which is the last element of the returned rows.
+///
+/// The second element of the returned value is true when no more rows exist
+/// or a row fails to be converted.
+fn fetch_rows<T>(
+    stmt: &mut Stmt,
+    columns: &T::Columns,
+    guard: FetchGuard,
+) -> Result<(VecDeque<Result<T>>, bool)>
+where
+    T: FromRow,
+{
+    let fetch_array_size = stmt.fetch_array_size()?;
+    let (buffer_row_index, num_rows_fetched, more_rows): (u32, u32, i32) =
+        get_3values!(dpiStmt_fetchRows(stmt.handle, fetch_array_size))?;
+    let result = FetchRowsResult {
+        indexes: buffer_row_index..(buffer_row_index + num_rows_fetched),
+        more_rows: more_rows != 0,
+    };
+    inline_lobs::replace_long_values(stmt, result.indexes.clone())?;
+    let batch = RowBatch::new(stmt, result, guard)?;
+    let mut rows = VecDeque::with_capacity(batch.len());
+    for index in batch.indexes() {
+        let row = T::from_row(&batch, index, columns);
+        let is_err = row.is_err();
+        rows.push_back(row);
+        if is_err {
+            return Ok((rows, true));
+        }
+    }
+    Ok((rows, !batch.more_rows()))
+}
+
+#[sync_impl]
+impl<T> Iterator for Rows<'_, T>
+where
+    T: FromRow,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(row) = self.rows.pop_front() {
+                return Some(row);
+            }
+            if self.done {
+                return None;
+            }
+            let columns = self.columns.as_ref()?;
+            // RowBatch borrows a clone because `self.stmt` is borrowed immutably.
+            let result = self
+                .stmt
+                .fetch_guard()
+                .and_then(|guard| fetch_rows(&mut self.stmt.clone(), columns, guard));
+            match result {
+                Ok((rows, done)) => {
+                    self.rows = rows;
+                    self.done = done;
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+// Fields are never pinned.
+#[async_impl(AFIT)]
+impl<T> Unpin for Rows<'_, T> where T: FromRow {}
+
+#[async_impl(AFIT)]
+impl<T> futures_core::Stream for Rows<'_, T>
+where
+    T: FromRow + Send + 'static,
+    T::Columns: Send + 'static,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(row) = this.rows.pop_front() {
+                return Poll::Ready(Some(row));
+            }
+            if let Some(future) = this.future.as_mut() {
+                let result = ready!(future.as_mut().poll(cx));
+                this.future = None;
+                match result {
+                    Ok((columns, rows, done)) => {
+                        this.columns = Some(columns);
+                        this.rows = rows;
+                        this.done = done;
+                    }
+                    Err(err) => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+                continue;
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            let guard = match this.stmt.fetch_guard() {
+                Ok(guard) => guard,
+                Err(err) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            };
+            let Some(columns) = this.columns.take() else {
+                return Poll::Ready(None);
+            };
+            let mut stmt = ForceSend(this.stmt.clone());
+            this.future = Some(Box::pin(task::spawn_blocking(move || {
+                let (rows, done) = fetch_rows::<T>(&mut stmt, &columns, guard)?;
+                Ok((columns, rows, done))
+            })));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rows;
+    use crate::stmt::{ExecMode, FromRow};
+    use crate::{test_util, Result};
+
+    // Collects rows by Iterator.
+    #[cfg(not(feature = "is_async"))]
+    fn collect<T>(rows: Rows<'_, T>) -> Result<Vec<T>>
+    where
+        T: FromRow,
+    {
+        rows.collect()
+    }
+
+    // Collects rows by Stream.
+    #[cfg(feature = "is_async")]
+    async fn collect<T>(mut rows: Rows<'_, T>) -> Result<Vec<T>>
+    where
+        T: FromRow + Send + 'static,
+        T::Columns: Send + 'static,
+    {
+        use futures_core::Stream;
+        use std::future::poll_fn;
+        use std::pin::Pin;
+
+        let mut vec = Vec::new();
+        while let Some(row) = poll_fn(|cx| Pin::new(&mut rows).poll_next(cx)).await {
+            vec.push(row?);
+        }
+        Ok(vec)
+    }
+
+    // Collects all items including errors by Iterator.
+    #[cfg(not(feature = "is_async"))]
+    fn collect_results<T>(rows: Rows<'_, T>) -> Vec<Result<T>>
+    where
+        T: FromRow,
+    {
+        rows.collect()
+    }
+
+    // Collects all items including errors by Stream.
+    #[cfg(feature = "is_async")]
+    async fn collect_results<T>(mut rows: Rows<'_, T>) -> Vec<Result<T>>
+    where
+        T: FromRow + Send + 'static,
+        T::Columns: Send + 'static,
+    {
+        use futures_core::Stream;
+        use std::future::poll_fn;
+        use std::pin::Pin;
+
+        let mut vec = Vec::new();
+        while let Some(row) = poll_fn(|cx| Pin::new(&mut rows).poll_next(cx)).await {
+            vec.push(row);
+        }
+        vec
+    }
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct Row {
+        id: i64,
+        name: String,
+    }
+
+    #[crate::test]
+    async fn rows_in_fetch_arrays() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "select level id, 'row ' || level name from dual connect by level <= 10",
+            "",
+        )?;
+        // 10 rows are fetched by 3, 3, 3 and 1.
+        stmt.set_fetch_array_size(3)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let rows = collect(stmt.rows::<(i64, String)>()?).await?;
+        let expected = (1..=10)
+            .map(|id| (id, format!("row {}", id)))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, expected);
+
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let rows = collect(stmt.rows::<Row>()?).await?;
+        let expected = (1..=10)
+            .map(|id| Row {
+                id,
+                name: format!("row {}", id),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, expected);
+
+        // no rows
+        let stmt = conn.prepare_stmt(false, "select 1, 'a' from dual where 1 = 0", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let rows = collect(stmt.rows::<(i64, String)>()?).await?;
+        assert!(rows.is_empty());
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn conversion_errors() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "select decode(level, 2, null, level) from dual connect by level <= 3",
+            "",
+        )?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let result = collect(stmt.rows::<(i64,)>()?).await;
+        assert!(result.is_err());
+
+        // The row converted before the error is returned first and no rows
+        // are returned after the error.
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let results = collect_results(stmt.rows::<(i64,)>()?).await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().ok(), Some(&(1,)));
+        assert!(results[1].is_err());
+
+        // The number of columns is checked before fetching rows.
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert!(stmt.rows::<(i64, i64)>().is_err());
+        Ok(())
+    }
+
+    // Stmt isn't Send, so rows containing it cannot be streamed.
+    #[cfg(not(feature = "is_async"))]
+    #[crate::test]
+    async fn ref_cursors() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "select level, cursor(select level * 10 from dual connect by level <= 2) \
+             from dual connect by level <= 2",
+            "",
+        )?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let rows = collect(stmt.rows::<(i64, crate::stmt::Stmt)>()?).await?;
+        assert_eq!(rows.len(), 2);
+        for (_, cursor) in rows {
+            let values = collect(cursor.rows::<(i64,)>()?).await?;
+            assert_eq!(values, [(10,), (20,)]);
+        }
+        Ok(())
+    }
+}
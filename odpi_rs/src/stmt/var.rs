@@ -12,9 +12,11 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
-use crate::stmt::Stmt;
+use crate::conn::Conn;
+use crate::stmt::{BindValue, Stmt};
 use crate::types::{
-    FromSql, FromSqlUnsafe, Json, Lob, NativeType, NativeValue, Object, Rowid, Vector,
+    FromSql, FromSqlUnsafe, Json, Lob, NativeType, NativeValue, Object, ObjectType, OracleType,
+    Rowid, Vector,
 };
 use crate::utils::*;
 use crate::*;
@@ -27,29 +29,51 @@ use std::sync::Mutex;
 #[odpic_doc]
 pub struct Var {
     pub(crate) handle: *mut dpiVar,
+    conn: Conn,
+    oracle_type: OracleType,
     pub(crate) native_type: NativeType,
+    pub(crate) max_array_size: u32,
     pub(crate) data: Arc<Mutex<*mut dpiData>>,
+    pub(crate) objtype: Option<ObjectType>,
 }
 
 #[odpic_doc]
 impl Var {
-    pub(crate) fn new(handle: *mut dpiVar, native_type: NativeType, data: *mut dpiData) -> Var {
+    pub(crate) fn new(
+        handle: *mut dpiVar,
+        conn: &Conn,
+        oracle_type: OracleType,
+        native_type: NativeType,
+        max_array_size: u32,
+        data: *mut dpiData,
+        objtype: Option<ObjectType>,
+    ) -> Var {
         Var {
             handle,
+            conn: conn.clone(),
+            oracle_type,
             native_type,
+            max_array_size,
             data: Arc::new(Mutex::new(data)),
+            objtype,
         }
     }
 
+    /// Returns the maximum number of elements passed to [`Conn::new_var`][crate::conn::Conn::new_var]
+    pub fn max_array_size(&self) -> u32 {
+        self.max_array_size
+    }
+
     pub fn value<T>(&self) -> Result<T>
     where
         T: FromSql,
     {
         let data = self.data.lock().unwrap();
-        <T as FromSql>::from_sql(NativeValue::from_dpi_data(
-            unsafe { &**data },
-            self.native_type,
-        )?)
+        <T as FromSql>::from_sql(
+            NativeValue::from_dpi_data(unsafe { &**data }, self.native_type)?
+                .with_object_type(self.objtype.as_ref())
+                .with_conn(&self.conn),
+        )
     }
 
     pub unsafe fn value_unsafe<'a, T>(&'a self) -> Result<T>
@@ -57,10 +81,161 @@ impl Var {
         T: FromSqlUnsafe<'a>,
     {
         let data = self.data.lock().unwrap();
-        <T as FromSqlUnsafe>::from_sql_unsafe(NativeValue::from_dpi_data(
-            unsafe { &**data },
-            self.native_type,
-        )?)
+        <T as FromSqlUnsafe>::from_sql_unsafe(
+            NativeValue::from_dpi_data(unsafe { &**data }, self.native_type)?
+                .with_object_type(self.objtype.as_ref())
+                .with_conn(&self.conn),
+        )
+    }
+
+    /// Returns all elements
+    ///
+    /// The number of elements is [`Var::num_elements_in_array`], which is
+    /// the maximum array size unless the variable is a PL/SQL array.
+    pub fn values<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromSql,
+    {
+        let num_elements = self.num_elements_in_array()?;
+        let data = *self.data.lock().unwrap();
+        (0..num_elements as usize)
+            .map(|index| {
+                <T as FromSql>::from_sql(
+                    NativeValue::from_dpi_data(unsafe { &*data.add(index) }, self.native_type)?
+                        .with_object_type(self.objtype.as_ref())
+                        .with_conn(&self.conn),
+                )
+            })
+            .collect()
+    }
+
+    /// Sets `values` to elements from the first one
+    ///
+    /// This is typically used to set bind values of all iterations
+    /// before [`Stmt::execute_many`]. Use
+    /// [`Conn::new_var_from_values`][crate::conn::Conn::new_var_from_values] to create a variable sized for the values.
+    ///
+    /// This fails when the native type of `T` differs from that of
+    /// the variable or when the number of values exceeds [`Var::max_array_size`].
+    /// Bytes cannot be set to `CLOB`, `NCLOB` and `BLOB` variables because
+    /// writing them needs round trips. Use [`Var::set_from_bytes`] instead.
+    pub fn set_values<T>(&self, values: &[T]) -> Result<()>
+    where
+        T: BindValue,
+    {
+        let native_type: NativeType = T::native_type_num().try_to_rust()?;
+        if native_type != self.native_type {
+            return Err(Error::other(format!(
+                "native type mismatch: variable {:?}, value {:?}",
+                self.native_type, native_type
+            )));
+        }
+        if values.len() > self.max_array_size as usize {
+            return Err(Error::other(format!(
+                "{} values exceed the maximum array size {}",
+                values.len(),
+                self.max_array_size
+            )));
+        }
+        let data = *self.data.lock().unwrap();
+        for (pos, value) in values.iter().enumerate() {
+            value.with_data(Some(&self.conn), |src| unsafe {
+                self.set_data(pos, data.add(pos), src)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Sets `values` to a PL/SQL associative array
+    ///
+    /// This sets `values` by [`Var::set_values`] and then the number of
+    /// elements in the array by [`Var::set_num_elements_in_array`].
+    /// The variable must be created with `is_array` set to `true`.
+    pub fn set_array_values<T>(&self, values: &[T]) -> Result<()>
+    where
+        T: BindValue,
+    {
+        self.set_values(values)?;
+        self.set_num_elements_in_array(values.len().try_into()?)
+    }
+
+    unsafe fn set_data(&self, pos: usize, dest: *mut dpiData, src: &dpiData) -> Result<()> {
+        if src.isNull != 0 {
+            dpiData_setNull(dest);
+            return Ok(());
+        }
+        let pos = pos as u32;
+        let value = &src.value;
+        match self.native_type {
+            NativeType::Int64 => dpiData_setInt64(dest, value.asInt64),
+            NativeType::Uint64 => dpiData_setUint64(dest, value.asUint64),
+            NativeType::Float => dpiData_setFloat(dest, value.asFloat),
+            NativeType::Double => dpiData_setDouble(dest, value.asDouble),
+            NativeType::Boolean => dpiData_setBool(dest, value.asBoolean),
+            NativeType::Timestamp => {
+                let ts = &value.asTimestamp;
+                dpiData_setTimestamp(
+                    dest,
+                    ts.year,
+                    ts.month,
+                    ts.day,
+                    ts.hour,
+                    ts.minute,
+                    ts.second,
+                    ts.fsecond,
+                    ts.tzHourOffset,
+                    ts.tzMinuteOffset,
+                )
+            }
+            NativeType::IntervalDS => {
+                let it = &value.asIntervalDS;
+                dpiData_setIntervalDS(dest, it.days, it.hours, it.minutes, it.seconds, it.fseconds)
+            }
+            NativeType::IntervalYM => {
+                let it = &value.asIntervalYM;
+                dpiData_setIntervalYM(dest, it.years, it.months)
+            }
+            // The following types must be set via dpiVar_setFrom* functions
+            // because the variable owns buffers or references.
+            NativeType::Bytes => {
+                // dpiVar_setFromBytes blocks only when it writes to LOBs.
+                if matches!(
+                    self.oracle_type,
+                    OracleType::Clob | OracleType::Nclob | OracleType::Blob
+                ) {
+                    return Err(Error::other(format!(
+                        "bytes cannot be set to {:?} variables without round trips. Use Var::set_from_bytes instead",
+                        self.oracle_type
+                    )));
+                }
+                use odpic_sys::blocking::dpiVar_setFromBytes;
+                return call!(dpiVar_setFromBytes(
+                    self.handle,
+                    pos,
+                    value.asBytes.ptr,
+                    value.asBytes.length
+                ));
+            }
+            NativeType::Lob => return call!(dpiVar_setFromLob(self.handle, pos, value.asLOB)),
+            NativeType::Object => {
+                return call!(dpiVar_setFromObject(self.handle, pos, value.asObject))
+            }
+            NativeType::Stmt => return call!(dpiVar_setFromStmt(self.handle, pos, value.asStmt)),
+            NativeType::Rowid => {
+                return call!(dpiVar_setFromRowid(self.handle, pos, value.asRowid))
+            }
+            NativeType::Json => return call!(dpiVar_setFromJson(self.handle, pos, value.asJson)),
+            NativeType::Vector => {
+                return call!(dpiVar_setFromVector(self.handle, pos, value.asVector))
+            }
+            NativeType::JsonObject | NativeType::JsonArray | NativeType::Null => {
+                return Err(Error::other(format!(
+                    "unexpected native type {:?}",
+                    self.native_type
+                )));
+            }
+        }
+        Ok(())
     }
 
     pub fn copy_data(&self, pos: u32, source: &Var, source_pos: u32) -> Result<()> {
@@ -71,10 +246,55 @@ impl Var {
         get_value!(dpiVar_getNumElementsInArray(self.handle))
     }
 
-    // // return pointer to array of dpiData structures for transferring data
-    // // this is needed for DML returning where the number of elements is modified
-    // DPI_EXPORT int dpiVar_getReturnedData(dpiVar *var, uint32_t pos,
-    //         uint32_t *numElements, dpiData **data);
+    /// Returns data returned by a DML returning statement in the `pos`-th iteration
+    ///
+    /// `pos` is zero-based and corresponds to the iteration index passed to
+    /// [`Stmt::execute_many`]. Use zero after [`Stmt::execute`].
+    ///
+    /// The returned value borrows this variable and becomes stale when
+    /// the statement is executed again.
+    pub fn returned_data(&self, pos: u32) -> Result<ReturnedData<'_>> {
+        let (num_elements, data) = get_2values!(dpiVar_getReturnedData(self.handle, pos))?;
+        Ok(ReturnedData {
+            var: self,
+            num_elements,
+            data,
+        })
+    }
+
+    /// Returns values returned by a DML returning statement in the `pos`-th iteration
+    ///
+    /// This is a shortcut of `var.returned_data(pos)?.values()`.
+    ///
+    /// # Examples
+    ///
+    /// Collect generated identity keys of rows inserted by [`Stmt::execute_many`].
+    ///
+    /// ```ignore
+    /// let stmt = conn.prepare_stmt(
+    ///     false,
+    ///     "insert into employees (name) values (:1) returning id into :2",
+    ///     "",
+    /// )?;
+    /// let name_var = conn.new_var(OracleType::Varchar, NativeType::Bytes, names.len() as u32, 100, false, false, None)?;
+    /// let id_var = conn.new_var(OracleType::Number, NativeType::Int64, names.len() as u32, 0, false, false, None)?;
+    /// for (pos, name) in names.iter().enumerate() {
+    ///     name_var.set_from_bytes(pos as u32, name).await?;
+    /// }
+    /// stmt.bind_by_pos(1, &name_var)?;
+    /// stmt.bind_by_pos(2, &id_var)?;
+    /// stmt.execute_many(ExecMode::DEFAULT, names.len() as u32).await?;
+    /// let mut ids = Vec::new();
+    /// for pos in 0..names.len() as u32 {
+    ///     ids.extend(id_var.returned_values::<i64>(pos)?);
+    /// }
+    /// ```
+    pub fn returned_values<T>(&self, pos: u32) -> Result<Vec<T>>
+    where
+        T: FromSql,
+    {
+        self.returned_data(pos)?.values()
+    }
 
     pub fn size_in_bytes(&self) -> Result<u32> {
         get_value!(dpiVar_getSizeInBytes(self.handle))
@@ -128,13 +348,67 @@ impl Var {
     }
 }
 
+/// Data returned by a DML returning statement in an iteration
+///
+/// This is created by [`Var::returned_data`]. A DML statement may affect
+/// zero, one or more rows in an iteration, so this holds as many
+/// elements as the number of rows.
+#[derive(Debug)]
+pub struct ReturnedData<'a> {
+    var: &'a Var,
+    num_elements: u32,
+    data: *mut dpiData,
+}
+
+impl ReturnedData<'_> {
+    /// Returns the number of elements
+    pub fn len(&self) -> usize {
+        self.num_elements as usize
+    }
+
+    /// Returns `true` if no rows are returned
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the `index`-th element
+    pub fn value<T>(&self, index: usize) -> Result<T>
+    where
+        T: FromSql,
+    {
+        if index >= self.len() {
+            return Err(Error::other(format!(
+                "index {index} out of range for returned data of length {}",
+                self.len()
+            )));
+        }
+        <T as FromSql>::from_sql(
+            NativeValue::from_dpi_data(unsafe { &*self.data.add(index) }, self.var.native_type)?
+                .with_object_type(self.var.objtype.as_ref())
+                .with_conn(&self.var.conn),
+        )
+    }
+
+    /// Returns all elements
+    pub fn values<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromSql,
+    {
+        (0..self.len()).map(|index| self.value(index)).collect()
+    }
+}
+
 impl Clone for Var {
     fn clone(&self) -> Var {
         unsafe { dpiVar_addRef(self.handle) };
         Var {
             handle: self.handle,
+            conn: self.conn.clone(),
+            oracle_type: self.oracle_type,
             native_type: self.native_type,
+            max_array_size: self.max_array_size,
             data: self.data.clone(),
+            objtype: self.objtype.clone(),
         }
     }
 }
@@ -144,3 +418,225 @@ impl Drop for Var {
         release_handle!(dpiVar_release(self.handle));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::conn::Conn;
+    use crate::stmt::{ExecMode, Var};
+    use crate::types::{NativeType, OracleType};
+    use crate::{test_util, Result};
+
+    fn new_var(conn: &Conn, oracle_type: OracleType, native_type: NativeType) -> Result<Var> {
+        conn.new_var(oracle_type, native_type, 3, 100, false, false, None)
+    }
+
+    #[crate::test]
+    async fn dml_returning() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(false, "delete from TestTempTable", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+
+        // one row in each iteration of execute_many
+        let stmt = conn.prepare_stmt(
+            false,
+            "insert into TestTempTable values (:1, :2) returning IntCol * 10, StringCol into :3, :4",
+            "",
+        )?;
+        let int_var = new_var(&conn, OracleType::Number, NativeType::Int64)?;
+        let str_var = new_var(&conn, OracleType::Varchar, NativeType::Bytes)?;
+        let ret_int_var = new_var(&conn, OracleType::Number, NativeType::Int64)?;
+        let ret_str_var = new_var(&conn, OracleType::Varchar, NativeType::Bytes)?;
+        int_var.set_values(&[1i64, 2, 3])?;
+        str_var.set_values(&["one", "two", "three"])?;
+        stmt.bind_by_pos(1, &int_var)?;
+        stmt.bind_by_pos(2, &str_var)?;
+        stmt.bind_by_pos(3, &ret_int_var)?;
+        stmt.bind_by_pos(4, &ret_str_var)?;
+        stmt.execute_many(ExecMode::DEFAULT, 3).await?;
+        for (pos, (int_val, str_val)) in [(10, "one"), (20, "two"), (30, "three")]
+            .into_iter()
+            .enumerate()
+        {
+            let data = ret_int_var.returned_data(pos as u32)?;
+            assert_eq!(data.len(), 1);
+            assert_eq!(data.value::<i64>(0)?, int_val);
+            assert!(data.value::<i64>(1).is_err());
+            assert_eq!(
+                ret_str_var.returned_values::<String>(pos as u32)?,
+                [str_val]
+            );
+        }
+
+        // multiple rows in one execution
+        let stmt = conn.prepare_stmt(
+            false,
+            "update TestTempTable set StringCol = StringCol || '!' where IntCol >= 2 \
+             returning IntCol, StringCol into :1, :2",
+            "",
+        )?;
+        stmt.bind_by_pos(1, &ret_int_var)?;
+        stmt.bind_by_pos(2, &ret_str_var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let mut rows = ret_int_var
+            .returned_values::<i64>(0)?
+            .into_iter()
+            .zip(ret_str_var.returned_values::<String>(0)?)
+            .collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(rows, [(2, "two!".to_string()), (3, "three!".to_string())]);
+
+        // no rows
+        let stmt = conn.prepare_stmt(
+            false,
+            "delete from TestTempTable where IntCol > 10 returning IntCol into :1",
+            "",
+        )?;
+        stmt.bind_by_pos(1, &ret_int_var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert!(ret_int_var.returned_data(0)?.is_empty());
+        assert!(ret_int_var.returned_values::<i64>(0)?.is_empty());
+
+        conn.rollback().await?;
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn set_values() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let var = new_var(&conn, OracleType::Number, NativeType::Int64)?;
+        var.set_values(&[1i64, 2, 3])?;
+        assert_eq!(var.values::<i64>()?, [1, 2, 3]);
+        var.set_values(&[Some(4i64), None])?;
+        assert_eq!(var.values::<Option<i64>>()?, [Some(4), None, Some(3)]);
+        assert_eq!(var.value::<i64>()?, 4);
+
+        let var = new_var(&conn, OracleType::Varchar, NativeType::Bytes)?;
+        var.set_values(&["a", "bc"])?;
+        var.set_values(&[Some("def".to_string())])?;
+        assert_eq!(
+            var.values::<Option<String>>()?,
+            [Some("def".into()), Some("bc".into()), None]
+        );
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn set_values_errors() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let var = new_var(&conn, OracleType::Number, NativeType::Int64)?;
+        // native type mismatch
+        assert!(var.set_values(&[1.5f64]).is_err());
+        assert!(var.set_values(&["1"]).is_err());
+        // more than max_array_size
+        assert!(var.set_values(&[1i64, 2, 3, 4]).is_err());
+        // bytes to LOB
+        let var = new_var(&conn, OracleType::Clob, NativeType::Bytes)?;
+        assert!(var.set_values(&["clob"]).is_err());
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn new_var_from_values() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(false, "delete from TestTempTable", "")?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+
+        let strings = ["a", "", "long string value"];
+        let str_var = conn.new_var_from_values(OracleType::Varchar, &strings, None)?;
+        assert_eq!(str_var.max_array_size(), 3);
+        assert!(str_var.size_in_bytes()? >= 17);
+        assert_eq!(
+            str_var.values::<Option<String>>()?,
+            [Some("a".into()), Some("".into()), Some(strings[2].into())]
+        );
+        let int_var = conn.new_var_from_values(OracleType::Number, &[1i64, 2, 3], None)?;
+        assert_eq!(int_var.max_array_size(), 3);
+
+        let stmt = conn.prepare_stmt(false, "insert into TestTempTable values (:1, :2)", "")?;
+        stmt.bind_by_pos(1, &int_var)?;
+        stmt.bind_by_pos(2, &str_var)?;
+        stmt.execute_many(ExecMode::DEFAULT, 3).await?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "select count(*), max(length(StringCol)) from TestTempTable",
+            "",
+        )?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<i64>(1)?, 3);
+        assert_eq!(stmt.query_value::<i64>(2)?, 17);
+
+        // an empty slice makes a variable with one element.
+        let var = conn.new_var_from_values::<f64>(OracleType::NativeDouble, &[], None)?;
+        assert_eq!(var.max_array_size(), 1);
+
+        conn.rollback().await?;
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn plsql_arrays() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let ret_var = new_var(&conn, OracleType::Number, NativeType::Int64)?;
+
+        // IN
+        let var =
+            conn.new_array_var_from_values(OracleType::Varchar, &["a", "bcd"], 10, 0, None)?;
+        assert_eq!(var.num_elements_in_array()?, 2);
+        let stmt = conn.prepare_stmt(
+            false,
+            "begin :1 := pkg_TestStringArrays.TestInArrays(5, :2); end;",
+            "",
+        )?;
+        stmt.bind_by_pos(1, &ret_var)?;
+        stmt.bind_by_pos(2, &var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert_eq!(ret_var.value::<i64>()?, 9);
+
+        // IN OUT. Returned elements are longer than the elements set.
+        let var =
+            conn.new_array_var_from_values(OracleType::Varchar, &["a", "bcd"], 10, 100, None)?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "begin pkg_TestStringArrays.TestInOutArrays(2, :1); end;",
+            "",
+        )?;
+        stmt.bind_by_pos(1, &var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert_eq!(
+            var.values::<String>()?,
+            [
+                "Converted element # 1 originally had length 1",
+                "Converted element # 2 originally had length 3"
+            ]
+        );
+
+        // OUT
+        let var = conn.new_array_var_from_values::<i64>(OracleType::Number, &[], 10, 0, None)?;
+        assert_eq!(var.num_elements_in_array()?, 0);
+        let stmt = conn.prepare_stmt(
+            false,
+            "begin pkg_TestNumberArrays.TestOutArrays(3, :1); end;",
+            "",
+        )?;
+        stmt.bind_by_pos(1, &var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert_eq!(var.values::<i64>()?, [100, 200, 300]);
+
+        // set_array_values changes the number of elements.
+        var.set_array_values(&[1i64, 2])?;
+        assert_eq!(var.num_elements_in_array()?, 2);
+        let stmt = conn.prepare_stmt(
+            false,
+            "begin :1 := pkg_TestNumberArrays.TestInArrays(0, :2); end;",
+            "",
+        )?;
+        stmt.bind_by_pos(1, &ret_var)?;
+        stmt.bind_by_pos(2, &var)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        assert_eq!(ret_var.value::<i64>()?, 3);
+        assert!(var.set_array_values(&[0i64; 11]).is_err());
+        Ok(())
+    }
+}
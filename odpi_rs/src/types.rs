@@ -15,8 +15,13 @@
 //! Oracle data types and type information
 
 mod annotation;
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal;
+#[cfg(feature = "chrono")]
+mod chrono;
 mod data_type_info;
 mod enums;
+mod from_object;
 mod from_sql;
 mod interval_ds;
 mod interval_ym;
@@ -25,9 +30,17 @@ mod lob;
 mod native_value;
 mod object;
 mod object_attr;
+mod object_attr_info;
 mod object_type;
+mod object_type_info;
+mod ora_number;
 mod rowid;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+#[cfg(feature = "time")]
+mod time;
 mod timestamp;
+mod to_object;
 mod vector;
 mod vector_info;
 mod xid;
@@ -35,19 +48,31 @@ mod xid;
 pub use annotation::Annotation;
 pub use data_type_info::DataTypeInfo;
 pub use enums::*;
+pub use from_object::FromObject;
+pub use from_object::FromObjectValue;
 pub use from_sql::FromSql;
 pub use from_sql::FromSqlUnsafe;
 pub use interval_ds::IntervalDS;
 pub use interval_ym::IntervalYM;
 #[doc(inline)]
 pub use json::Json;
-pub use lob::Lob;
+pub use lob::{ClobLines, ClobReader, ClobWriter, Lob, LobStream};
 pub use native_value::NativeValue;
+pub use object::Elements;
 pub use object::Object;
 pub use object_attr::ObjectAttr;
+pub use object_attr_info::ObjectAttrInfo;
+#[doc(hidden)]
+pub use object_type::ObjectAttrMap;
 pub use object_type::ObjectType;
+pub use object_type_info::ObjectTypeInfo;
+pub use ora_number::OraNumber;
 pub use rowid::Rowid;
 pub use timestamp::Timestamp;
+pub use to_object::ToObject;
+pub use to_object::ToObjectValue;
 pub use vector::Vector;
 pub use vector_info::VectorInfo;
 pub use xid::Xid;
+
+pub use odpi_rs_procmacro::{FromObject, ToObject};
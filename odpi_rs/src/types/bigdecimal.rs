@@ -0,0 +1,124 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Conversions between Oracle NUMBER and [bigdecimal] types
+//!
+//! Values are converted via text by [`OraNumber`] so that no precision goes
+//! through floating point numbers.
+//! NUMBER columns must be fetched as [`NativeType::Bytes`][crate::types::NativeType::Bytes]
+//! as described in [`OraNumber`]. Values with more than 40 significant digits
+//! are rounded when they are bound.
+use crate::stmt::ToBindValue;
+use crate::types::{DataTypeInfo, FromObjectValue, FromSql, NativeValue, OraNumber, ToObjectValue};
+use crate::Result;
+use ::bigdecimal::BigDecimal;
+use std::str::FromStr;
+
+impl FromSql for BigDecimal {
+    fn from_sql(value: NativeValue<'_>) -> Result<BigDecimal> {
+        OraNumber::from_sql(value)?.to_decimal("BigDecimal", BigDecimal::from_str)
+    }
+}
+
+impl ToBindValue for BigDecimal {
+    type Value<'a> = String;
+
+    fn to_bind_value(&self) -> Result<String> {
+        OraNumber::decimal_bind_text(self)
+    }
+}
+
+impl FromObjectValue for BigDecimal {
+    type Value = OraNumber;
+
+    fn from_object_value(value: OraNumber, _type_info: &DataTypeInfo) -> Result<BigDecimal> {
+        value.to_decimal("BigDecimal", BigDecimal::from_str)
+    }
+}
+
+impl ToObjectValue for BigDecimal {
+    type Value<'a> = String;
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<String> {
+        OraNumber::decimal_bind_text(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stmt::ExecMode;
+    use crate::test_util;
+    use crate::types::{NativeType, OracleType};
+
+    fn from_text(s: &str) -> Result<BigDecimal> {
+        BigDecimal::from_sql(NativeValue::Bytes(Some(s.as_bytes())))
+    }
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn fetch() -> Result<()> {
+        assert_eq!(from_text("0")?, dec("0"));
+        assert_eq!(from_text("-123.45")?, dec("-123.45"));
+        assert_eq!(from_text("1E+125")?, dec("1e125"));
+        assert_eq!(from_text("1E-130")?, dec("1e-130"));
+        assert_eq!(
+            from_text("1234567890123456789012345678901234567890")?,
+            dec("1234567890123456789012345678901234567890")
+        );
+        assert!(from_text("abc").is_err());
+        assert!(matches!(
+            BigDecimal::from_sql(NativeValue::Bytes(None)),
+            Err(crate::Error::NullValue)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn bind_text() -> Result<()> {
+        assert_eq!(dec("0").to_bind_value()?, "0");
+        assert_eq!(dec("1.500").to_bind_value()?, "15E-1");
+        assert_eq!(dec("-123.45").to_bind_value()?, "-12345E-2");
+        // negative scale
+        assert_eq!(BigDecimal::new(123.into(), -3).to_bind_value()?, "123000");
+        assert_eq!(dec("1e-130").to_bind_value()?, "1E-130");
+        // rounded to 40 significant digits
+        assert_eq!(
+            dec("12345678901234567890123456789012345678905").to_bind_value()?,
+            "12345678901234567890123456789012345678910"
+        );
+        // out of the range of Oracle NUMBER
+        assert!(dec("1e126").to_bind_value().is_err());
+        assert_eq!(dec("1e-131").to_bind_value()?, "0");
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn round_trip() -> Result<()> {
+        let conn = test_util::connect().await?;
+        for value in [dec("-123.45"), dec("1e125"), dec("1e-130")] {
+            let stmt = conn.prepare_stmt(false, "select to_number(:1) from dual", "")?;
+            stmt.bind_value_by_pos(1, &value)?;
+            stmt.execute(ExecMode::DEFAULT).await?;
+            stmt.define_value(1, OracleType::Number, NativeType::Bytes, 0, false, None)?;
+            let row = stmt.fetch().await?;
+            assert!(row.is_some());
+            assert_eq!(stmt.query_value::<BigDecimal>(1)?, value);
+        }
+        Ok(())
+    }
+}
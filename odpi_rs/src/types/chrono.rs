@@ -0,0 +1,478 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Conversions between Oracle date-time types and [chrono] types
+//!
+//! | Oracle type | odpi_rs type | chrono type |
+//! |---|---|---|
+//! | `DATE`, `TIMESTAMP` | [`Timestamp`] | [`NaiveDate`], [`NaiveDateTime`] |
+//! | `TIMESTAMP WITH TIME ZONE` | [`Timestamp`] | [`DateTime<FixedOffset>`], [`DateTime<Utc>`] |
+//! | `INTERVAL DAY TO SECOND` | [`IntervalDS`] | [`TimeDelta`] |
+//!
+//! The date and time fields of [`Timestamp`] are the local date and time at the
+//! offset given by `tz_hour_offset` and `tz_minute_offset`.
+//!
+//! [`DateTime<FixedOffset>`] and [`DateTime<Utc>`] are bound as
+//! `TIMESTAMP WITH TIME ZONE` through variables so that the offset isn't lost.
+//!
+//! Fractional seconds are truncated to [`DataTypeInfo::fs_precision`] when
+//! values are set to object attributes and collection elements, whose data
+//! types are known on the client side. Fetched values already have the
+//! precision of the columns and bind values are rounded to the precision
+//! of the targets by Oracle.
+//!
+//! Oracle has no year zero. Its year -1 is 1 BC, which is year 0 in chrono,
+//! so years before 1 differ by one. Conversions to [`Timestamp`] fail when the
+//! year is out of the range of Oracle dates, 4712 BC (-4711 in chrono) to
+//! 9999, or when the time zone offset has seconds. Conversions from
+//! [`Timestamp`] fail when the year is zero.
+//!
+//! Oracle dates before 1582-10-15 are in the Julian calendar while chrono uses
+//! the proleptic Gregorian calendar. The year, month and day are converted as
+//! they are, so such dates denote different points in time on both sides.
+//! For example, `1500-01-01` in Oracle is `1500-01-10` in the proleptic
+//! Gregorian calendar.
+use crate::conn::Conn;
+use crate::stmt::{BindValue, ToBindValue};
+use crate::types::{
+    DataTypeInfo, FromObjectValue, FromSql, IntervalDS, NativeValue, Timestamp, ToObjectValue,
+};
+use crate::{Error, Result};
+use ::chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Utc,
+};
+use odpic_sys::*;
+
+// Converts a chrono year, where 1 BC is year zero, to an Oracle year
+fn oracle_year(value: &NaiveDateTime) -> Result<i16> {
+    if !(-4711..=9999).contains(&value.year()) {
+        return Err(Error::other(format!(
+            "year of {} is out of the range of Oracle dates",
+            value
+        )));
+    }
+    let year = value.year() as i16;
+    Ok(if year <= 0 { year - 1 } else { year })
+}
+
+// Converts an Oracle year, where 1 BC is year -1, to a chrono year
+fn chrono_year(value: &Timestamp) -> Result<i32> {
+    match value.year {
+        // Oracle rejects year zero with ORA-01841.
+        0 => Err(Error::other(format!("year zero in {:?}", value))),
+        year if year < 0 => Ok(i32::from(year) + 1),
+        year => Ok(year.into()),
+    }
+}
+
+fn timestamp_from_naive(value: &NaiveDateTime, offset_secs: i32) -> Result<Timestamp> {
+    let year = oracle_year(value)?;
+    if offset_secs % 60 != 0 {
+        return Err(Error::other(format!(
+            "time zone offset {} seconds isn't a whole number of minutes",
+            offset_secs
+        )));
+    }
+    Ok(Timestamp {
+        year,
+        month: value.month() as u8,
+        day: value.day() as u8,
+        hour: value.hour() as u8,
+        minute: value.minute() as u8,
+        second: value.second() as u8,
+        // chrono represents a leap second as nanosecond >= 1_000_000_000.
+        fsecond: value.nanosecond().min(999_999_999),
+        tz_hour_offset: (offset_secs / 3600) as i8,
+        tz_minute_offset: (offset_secs % 3600 / 60) as i8,
+    })
+}
+
+fn date_from_timestamp(value: &Timestamp) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(chrono_year(value)?, value.month.into(), value.day.into())
+        .ok_or_else(|| Error::other(format!("invalid date {:?}", value)))
+}
+
+fn naive_from_timestamp(value: &Timestamp) -> Result<NaiveDateTime> {
+    let date = date_from_timestamp(value)?;
+    let time = NaiveTime::from_hms_nano_opt(
+        value.hour.into(),
+        value.minute.into(),
+        value.second.into(),
+        value.fsecond,
+    );
+    match time {
+        Some(time) => Ok(NaiveDateTime::new(date, time)),
+        None => Err(Error::other(format!("invalid timestamp {:?}", value))),
+    }
+}
+
+fn offset_from_timestamp(value: &Timestamp) -> Result<FixedOffset> {
+    let secs = i32::from(value.tz_hour_offset) * 3600 + i32::from(value.tz_minute_offset) * 60;
+    FixedOffset::east_opt(secs)
+        .ok_or_else(|| Error::other(format!("invalid time zone offset in {:?}", value)))
+}
+
+impl TryFrom<NaiveDate> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: NaiveDate) -> Result<Timestamp> {
+        timestamp_from_naive(&value.and_time(NaiveTime::MIN), 0)
+    }
+}
+
+impl TryFrom<NaiveDateTime> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: NaiveDateTime) -> Result<Timestamp> {
+        timestamp_from_naive(&value, 0)
+    }
+}
+
+impl TryFrom<DateTime<FixedOffset>> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: DateTime<FixedOffset>) -> Result<Timestamp> {
+        timestamp_from_naive(&value.naive_local(), value.offset().local_minus_utc())
+    }
+}
+
+impl TryFrom<DateTime<Utc>> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: DateTime<Utc>) -> Result<Timestamp> {
+        timestamp_from_naive(&value.naive_utc(), 0)
+    }
+}
+
+/// The time part and the time zone offset are discarded.
+impl TryFrom<Timestamp> for NaiveDate {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<NaiveDate> {
+        date_from_timestamp(&value)
+    }
+}
+
+/// The time zone offset is discarded.
+impl TryFrom<Timestamp> for NaiveDateTime {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<NaiveDateTime> {
+        naive_from_timestamp(&value)
+    }
+}
+
+impl TryFrom<Timestamp> for DateTime<FixedOffset> {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<DateTime<FixedOffset>> {
+        let offset = offset_from_timestamp(&value)?;
+        naive_from_timestamp(&value)?
+            .and_local_timezone(offset)
+            .single()
+            .ok_or_else(|| Error::other(format!("invalid timestamp {:?}", value)))
+    }
+}
+
+impl TryFrom<Timestamp> for DateTime<Utc> {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<DateTime<Utc>> {
+        Ok(DateTime::<FixedOffset>::try_from(value)?.with_timezone(&Utc))
+    }
+}
+
+impl From<IntervalDS> for TimeDelta {
+    fn from(value: IntervalDS) -> TimeDelta {
+        let secs = i64::from(value.days) * 86400
+            + i64::from(value.hours) * 3600
+            + i64::from(value.minutes) * 60
+            + i64::from(value.seconds);
+        TimeDelta::seconds(secs) + TimeDelta::nanoseconds(value.fseconds.into())
+    }
+}
+
+impl TryFrom<TimeDelta> for IntervalDS {
+    type Error = Error;
+
+    fn try_from(value: TimeDelta) -> Result<IntervalDS> {
+        // Both are truncated toward zero so that all fields have the same sign.
+        let secs = value.num_seconds();
+        Ok(IntervalDS {
+            days: (secs / 86400).try_into()?,
+            hours: (secs % 86400 / 3600) as i32,
+            minutes: (secs % 3600 / 60) as i32,
+            seconds: (secs % 60) as i32,
+            fseconds: value.subsec_nanos(),
+        })
+    }
+}
+
+macro_rules! chrono_timestamp_impl {
+    ($($t:ty)*) => {$(
+        impl FromSql for $t {
+            fn from_sql(value: NativeValue<'_>) -> Result<$t> {
+                Timestamp::from_sql(value)?.try_into()
+            }
+        }
+
+        impl FromObjectValue for $t {
+            type Value = Timestamp;
+
+            fn from_object_value(value: Timestamp, _type_info: &DataTypeInfo) -> Result<$t> {
+                value.try_into()
+            }
+        }
+
+        impl ToObjectValue for $t {
+            type Value<'a> = Timestamp;
+
+            fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<Timestamp> {
+                Ok(Timestamp::try_from(*self)?.with_fs_precision(type_info.fs_precision))
+            }
+        }
+    )*};
+}
+
+chrono_timestamp_impl! { NaiveDate NaiveDateTime DateTime<FixedOffset> DateTime<Utc> }
+
+impl ToBindValue for NaiveDate {
+    type Value<'a> = Timestamp;
+
+    fn to_bind_value(&self) -> Result<Timestamp> {
+        (*self).try_into()
+    }
+}
+
+impl ToBindValue for NaiveDateTime {
+    type Value<'a> = Timestamp;
+
+    fn to_bind_value(&self) -> Result<Timestamp> {
+        (*self).try_into()
+    }
+}
+
+// dpiStmt_bindValueBy* binds timestamps as TIMESTAMP, which has no offset.
+macro_rules! chrono_timestamp_tz_bind_value_impl {
+    ($($t:ty)*) => {$(
+        impl BindValue for $t {
+            fn native_type_num() -> dpiNativeTypeNum {
+                DPI_NATIVE_TYPE_TIMESTAMP
+            }
+            fn oracle_type_num() -> Option<dpiOracleTypeNum> {
+                Some(DPI_ORACLE_TYPE_TIMESTAMP_TZ)
+            }
+            fn with_data<R, F>(&self, conn: Option<&Conn>, f: F) -> Result<R>
+            where
+                F: FnOnce(&mut dpiData) -> Result<R>,
+            {
+                Timestamp::try_from(*self)?.with_data(conn, f)
+            }
+        }
+    )*};
+}
+
+chrono_timestamp_tz_bind_value_impl! { DateTime<FixedOffset> DateTime<Utc> }
+
+impl FromSql for TimeDelta {
+    fn from_sql(value: NativeValue<'_>) -> Result<TimeDelta> {
+        Ok(IntervalDS::from_sql(value)?.into())
+    }
+}
+
+impl ToBindValue for TimeDelta {
+    type Value<'a> = IntervalDS;
+
+    fn to_bind_value(&self) -> Result<IntervalDS> {
+        (*self).try_into()
+    }
+}
+
+impl FromObjectValue for TimeDelta {
+    type Value = IntervalDS;
+
+    fn from_object_value(value: IntervalDS, _type_info: &DataTypeInfo) -> Result<TimeDelta> {
+        Ok(value.into())
+    }
+}
+
+impl ToObjectValue for TimeDelta {
+    type Value<'a> = IntervalDS;
+
+    fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<IntervalDS> {
+        Ok(IntervalDS::try_from(*self)?.with_fs_precision(type_info.fs_precision))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stmt::ExecMode;
+    use crate::test_util;
+
+    fn timestamp(year: i16, fsecond: u32, tz_hour_offset: i8, tz_minute_offset: i8) -> Timestamp {
+        Timestamp {
+            year,
+            month: 1,
+            day: 2,
+            hour: 3,
+            minute: 4,
+            second: 5,
+            fsecond,
+            tz_hour_offset,
+            tz_minute_offset,
+        }
+    }
+
+    fn interval(days: i32, hours: i32, minutes: i32, seconds: i32, fseconds: i32) -> IntervalDS {
+        IntervalDS {
+            days,
+            hours,
+            minutes,
+            seconds,
+            fseconds,
+        }
+    }
+
+    #[test]
+    fn year_range() -> Result<()> {
+        // (chrono year, Oracle year)
+        for (year, oracle_year) in [(-4711, -4712), (-1, -2), (0, -1), (1, 1), (9999, 9999)] {
+            let value = NaiveDate::from_ymd_opt(year, 1, 2).unwrap();
+            let ts = Timestamp::try_from(value)?;
+            assert_eq!(ts.year, oracle_year);
+            assert_eq!(NaiveDate::try_from(ts)?, value);
+            let value = value.and_hms_opt(3, 4, 5).unwrap();
+            let ts = Timestamp::try_from(value)?;
+            assert_eq!(ts, timestamp(oracle_year, 0, 0, 0));
+            assert_eq!(NaiveDateTime::try_from(ts)?, value);
+        }
+        for year in [-4712, 10000] {
+            let value = NaiveDate::from_ymd_opt(year, 1, 2).unwrap();
+            assert!(Timestamp::try_from(value).is_err());
+        }
+        assert!(NaiveDate::try_from(timestamp(0, 0, 0, 0)).is_err());
+        assert!(NaiveDateTime::try_from(timestamp(0, 0, 0, 0)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn offset_sign() -> Result<()> {
+        let naive = NaiveDate::from_ymd_opt(2025, 1, 2)
+            .unwrap()
+            .and_hms_opt(3, 4, 5)
+            .unwrap();
+        for (secs, hour, minute) in [
+            (0, 0, 0),
+            (5 * 3600 + 30 * 60, 5, 30),
+            (-(3 * 3600 + 30 * 60), -3, -30),
+            (-45 * 60, 0, -45),
+        ] {
+            let offset = FixedOffset::east_opt(secs).unwrap();
+            let value = naive.and_local_timezone(offset).unwrap();
+            let ts = Timestamp::try_from(value)?;
+            assert_eq!(ts, timestamp(2025, 0, hour, minute));
+            assert_eq!(DateTime::<FixedOffset>::try_from(ts)?, value);
+            assert_eq!(DateTime::<Utc>::try_from(ts)?, value.with_timezone(&Utc));
+        }
+        let offset = FixedOffset::east_opt(3600 + 1).unwrap();
+        assert!(Timestamp::try_from(naive.and_local_timezone(offset).unwrap()).is_err());
+
+        // DateTime<Utc> is converted to the UTC date and time.
+        let value = DateTime::<Utc>::try_from(timestamp(2025, 0, -3, -30))?;
+        let ts = Timestamp {
+            hour: 6,
+            minute: 34,
+            ..timestamp(2025, 0, 0, 0)
+        };
+        assert_eq!(Timestamp::try_from(value)?, ts);
+        Ok(())
+    }
+
+    #[test]
+    fn nanosecond_truncation() -> Result<()> {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+        let value = date.and_hms_nano_opt(3, 4, 5, 123_456_789).unwrap();
+        let ts = Timestamp::try_from(value)?;
+        assert_eq!(ts, timestamp(2025, 123_456_789, 0, 0));
+        assert_eq!(ts.with_fs_precision(3).fsecond, 123_000_000);
+        assert_eq!(ts.with_fs_precision(0).fsecond, 0);
+        assert_eq!(NaiveDateTime::try_from(ts)?, value);
+
+        // leap second
+        let value = date.and_hms_nano_opt(3, 4, 59, 1_500_000_000).unwrap();
+        assert_eq!(Timestamp::try_from(value)?.fsecond, 999_999_999);
+        Ok(())
+    }
+
+    #[test]
+    fn interval_round_trip() -> Result<()> {
+        let cases = [
+            (TimeDelta::zero(), interval(0, 0, 0, 0, 0)),
+            (
+                TimeDelta::new(86400 + 2 * 3600 + 3 * 60 + 4, 5).unwrap(),
+                interval(1, 2, 3, 4, 5),
+            ),
+            (
+                -TimeDelta::new(86400 + 2 * 3600 + 3 * 60 + 4, 5).unwrap(),
+                interval(-1, -2, -3, -4, -5),
+            ),
+            (TimeDelta::nanoseconds(-1), interval(0, 0, 0, 0, -1)),
+        ];
+        for (delta, interval) in cases {
+            assert_eq!(IntervalDS::try_from(delta)?, interval);
+            assert_eq!(TimeDelta::from(interval), delta);
+        }
+        assert!(IntervalDS::try_from(TimeDelta::MAX).is_err());
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_offset() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let offset = FixedOffset::west_opt(3 * 3600 + 30 * 60).unwrap();
+        let value = NaiveDate::from_ymd_opt(2025, 1, 2)
+            .unwrap()
+            .and_hms_opt(3, 4, 5)
+            .unwrap()
+            .and_local_timezone(offset)
+            .unwrap();
+        let stmt = conn.prepare_stmt(false, "select :ts, to_char(:ts, 'TZH:TZM') from dual", "")?;
+        stmt.bind_value_by_name("ts", &value)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        assert_eq!(stmt.query_value::<DateTime<FixedOffset>>(1)?, value);
+        assert_eq!(stmt.query_value::<String>(2)?, "-03:30");
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_bc_date() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt =
+            conn.prepare_stmt(false, "select :1, to_char(:1, 'SYYYY-MM-DD') from dual", "")?;
+        // year 0 in chrono is 1 BC.
+        for (year, text) in [(0, "-0001-01-02"), (-4711, "-4712-01-02")] {
+            let value = NaiveDate::from_ymd_opt(year, 1, 2).unwrap();
+            stmt.bind_value_by_pos(1, &value)?;
+            stmt.execute(ExecMode::DEFAULT).await?;
+            let row = stmt.fetch().await?;
+            assert!(row.is_some());
+            assert_eq!(stmt.query_value::<NaiveDate>(1)?, value);
+            assert_eq!(stmt.query_value::<String>(2)?, text);
+        }
+        Ok(())
+    }
+}
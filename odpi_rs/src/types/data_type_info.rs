@@ -81,6 +81,18 @@ impl DataTypeInfo {
     }
 }
 
+impl DataTypeInfo {
+    // Returns the object type or an error when the type isn't an object type.
+    pub(crate) fn object_type_or_err(&self) -> Result<&ObjectType> {
+        self.object_type.as_ref().ok_or_else(|| {
+            Error::other(format!(
+                "expected an object type but got {:?}",
+                self.oracle_type
+            ))
+        })
+    }
+}
+
 impl TryToRust<DataTypeInfo> for dpiDataTypeInfo {
     fn try_to_rust(&self) -> Result<DataTypeInfo> {
         DataTypeInfo::from_dpi(self)
@@ -0,0 +1,122 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::types::{
+    DataTypeInfo, FromSql, IntervalDS, IntervalYM, Lob, Object, ObjectType, Timestamp,
+};
+use crate::{Error, Result};
+
+/// Conversion from an Oracle object to a Rust value
+///
+/// This is usually implemented by `#[derive(FromObject)]`.
+/// Each field of the struct is got from the object attribute whose name is
+/// the uppercased field name. The attribute name can be changed by
+/// `#[odpi(rename = "ATTR_NAME")]` on the field. Field types must implement
+/// [`FromObjectValue`].
+///
+/// `#[odpi(type = "SCHEMA.TYPE_NAME")]` on the struct sets [`FromObject::TYPE_NAME`],
+/// which is used by [`Object::to_value`] to look up the object type.
+///
+/// ```ignore
+/// use odpi_rs::types::{FromObject, Object};
+///
+/// #[derive(FromObject)]
+/// #[odpi(type = "SCOTT.POINT")]
+/// struct Point {
+///     x: i32,
+///     #[odpi(rename = "Y_COORD")]
+///     y: Option<f64>,
+/// }
+///
+/// let point: Point = obj.to_value(&conn).await?;
+/// ```
+pub trait FromObject: Sized {
+    /// The object type name specified by `#[odpi(type = "SCHEMA.TYPE_NAME")]`
+    const TYPE_NAME: Option<&'static str> = None;
+
+    fn from_object(obj: &Object, objtype: &ObjectType) -> Result<Self>;
+}
+
+/// Conversion from a value of an object attribute or a collection element to a Rust value
+///
+/// Types implementing [`FromObject`] are converted from nested objects and
+/// `Vec<T>` is converted from a collection except `Vec<u8>`, which is
+/// converted from `RAW`. For this reason, `u8` isn't implemented.
+pub trait FromObjectValue: Sized {
+    /// The type of the value got from the attribute or the element
+    type Value: FromSql;
+
+    /// Converts `value` got from the attribute or the element whose type is `type_info`.
+    fn from_object_value(value: Self::Value, type_info: &DataTypeInfo) -> Result<Self>;
+}
+
+impl<T> FromObjectValue for T
+where
+    T: FromObject,
+{
+    type Value = Object;
+
+    fn from_object_value(value: Object, type_info: &DataTypeInfo) -> Result<T> {
+        T::from_object(&value, type_info.object_type_or_err()?)
+    }
+}
+
+impl<T> FromObjectValue for Option<T>
+where
+    T: FromObjectValue,
+{
+    type Value = Option<T::Value>;
+
+    fn from_object_value(value: Option<T::Value>, type_info: &DataTypeInfo) -> Result<Option<T>> {
+        value
+            .map(|value| T::from_object_value(value, type_info))
+            .transpose()
+    }
+}
+
+impl<T> FromObjectValue for Vec<T>
+where
+    T: FromObjectValue,
+{
+    type Value = Object;
+
+    fn from_object_value(value: Object, type_info: &DataTypeInfo) -> Result<Vec<T>> {
+        let objtype = type_info.object_type_or_err()?;
+        let element_type_info = objtype
+            .info()?
+            .element_type_info
+            .ok_or_else(|| Error::other(format!("{:?} isn't a collection type", objtype)))?;
+        value
+            .elements::<T::Value>()
+            .map(|element| {
+                element.and_then(|(_, value)| T::from_object_value(value, &element_type_info))
+            })
+            .collect()
+    }
+}
+
+macro_rules! from_object_value_impl {
+    ($($t:ty)*) => {$(
+        impl FromObjectValue for $t {
+            type Value = $t;
+
+            fn from_object_value(value: $t, _type_info: &DataTypeInfo) -> Result<$t> {
+                Ok(value)
+            }
+        }
+    )*};
+}
+
+from_object_value_impl! { i8 i16 i32 i64 u16 u32 u64 f32 f64 bool }
+from_object_value_impl! { String Vec<u8> Object Timestamp IntervalDS IntervalYM Lob }
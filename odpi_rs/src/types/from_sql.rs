@@ -12,10 +12,31 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
-use crate::types::{NativeValue, Rowid};
+use crate::stmt::Stmt;
+use crate::types::{
+    IntervalDS, IntervalYM, Json, Lob, NativeValue, Object, Rowid, Timestamp, Vector,
+};
 use crate::{Error, Result};
 use std::str::{self, FromStr};
 
+/// Conversion from a fetched value to a Rust value
+///
+/// Numeric types are converted as follows:
+///
+/// - Integer types are converted from integers by `TryFrom`. Out-of-range
+///   values are errors.
+/// - Integer types are converted from `f32` and `f64` values via text,
+///   that is `to_string()` followed by `from_str()`, so that values with a
+///   fractional part, NaN, infinities and out-of-range values are errors
+///   instead of being truncated or saturated.
+/// - Floating-point types are converted from numeric values by `as`,
+///   which may lose precision.
+/// - Numeric types are converted from text, such as NUMBER columns
+///   fetched as bytes, by `from_str()`.
+///
+/// `String` is converted from text, numeric values, booleans and ROWIDs.
+/// `Vec<u8>` is converted from text and RAW values as they are.
+/// NULL values are [`Error::NullValue`] except when the type is `Option<T>`.
 pub trait FromSql: Sized {
     fn from_sql(value: NativeValue<'_>) -> Result<Self>;
 }
@@ -59,6 +80,10 @@ macro_rules! from_sql_num_impl {
                     NativeValue::Int64(None) => Err(Error::NullValue),
                     NativeValue::Uint64(Some(value)) => Ok(from_sql_num_impl!(value => $kind $t)),
                     NativeValue::Uint64(None) => Err(Error::NullValue),
+                    NativeValue::Float(Some(value)) => Ok(from_sql_num_impl!(value => $kind float $t)),
+                    NativeValue::Float(None) => Err(Error::NullValue),
+                    NativeValue::Double(Some(value)) => Ok(from_sql_num_impl!(value => $kind float $t)),
+                    NativeValue::Double(None) => Err(Error::NullValue),
                     _ => Err(Error::other(format!(
                         concat!("failed to convert to ", stringify!($t), " from {:?}"),
                         value
@@ -67,6 +92,10 @@ macro_rules! from_sql_num_impl {
             }
         }
     )*};
+    // Floating-point numbers are converted to integers via text so that
+    // fractional or out-of-range values are rejected instead of truncated.
+    ($value:expr => int float $t:ty) => { <$t>::from_str(&$value.to_string())? };
+    ($value:expr => float float $t:ty) => { $value as $t };
     ($value:expr => int $t:ty) => { $value.try_into()? };
     ($value:expr => float $t:ty) => { $value as $t };
 }
@@ -74,6 +103,77 @@ macro_rules! from_sql_num_impl {
 from_sql_num_impl! { int: isize i8 i16 i32 i64 i128 usize u8 u16 u32 u64 u128 }
 from_sql_num_impl! { float: f32 f64 }
 
+impl FromSql for String {
+    fn from_sql(value: NativeValue<'_>) -> Result<String> {
+        match value {
+            NativeValue::Bytes(Some(bytes)) => Ok(str::from_utf8(bytes)?.to_owned()),
+            NativeValue::Bytes(None) => Err(Error::NullValue),
+            NativeValue::Int64(Some(value)) => Ok(value.to_string()),
+            NativeValue::Int64(None) => Err(Error::NullValue),
+            NativeValue::Uint64(Some(value)) => Ok(value.to_string()),
+            NativeValue::Uint64(None) => Err(Error::NullValue),
+            NativeValue::Float(Some(value)) => Ok(value.to_string()),
+            NativeValue::Float(None) => Err(Error::NullValue),
+            NativeValue::Double(Some(value)) => Ok(value.to_string()),
+            NativeValue::Double(None) => Err(Error::NullValue),
+            NativeValue::Boolean(Some(value)) => Ok(value.to_string()),
+            NativeValue::Boolean(None) => Err(Error::NullValue),
+            NativeValue::Rowid(Some(rowid)) => Ok(rowid.string_value()?.to_owned()),
+            NativeValue::Rowid(None) => Err(Error::NullValue),
+            _ => Err(Error::other(format!(
+                "failed to convert to String from {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromSql for Vec<u8> {
+    fn from_sql(value: NativeValue<'_>) -> Result<Vec<u8>> {
+        match value {
+            NativeValue::Bytes(Some(bytes)) => Ok(bytes.to_vec()),
+            NativeValue::Bytes(None) => Err(Error::NullValue),
+            _ => Err(Error::other(format!(
+                "failed to convert to Vec<u8> from {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+macro_rules! from_sql_owned_impl {
+    ($($t:ident)*) => {$(
+        impl FromSql for $t {
+            fn from_sql(value: NativeValue<'_>) -> Result<$t> {
+                match value {
+                    NativeValue::$t(Some(value)) => Ok(value),
+                    NativeValue::$t(None) => Err(Error::NullValue),
+                    _ => Err(Error::other(format!(
+                        concat!("failed to convert to ", stringify!($t), " from {:?}"),
+                        value
+                    ))),
+                }
+            }
+        }
+    )*};
+}
+
+// Oracle DATE values are also fetched as NativeValue::Timestamp.
+from_sql_owned_impl! { Timestamp IntervalDS IntervalYM Lob Json Vector Stmt }
+
+impl FromSql for Object {
+    fn from_sql(value: NativeValue<'_>) -> Result<Object> {
+        match value {
+            NativeValue::Object(Some(obj)) => Ok(obj),
+            NativeValue::Object(None) => Err(Error::NullValue),
+            _ => Err(Error::other(format!(
+                "failed to convert to Object from {:?}",
+                value
+            ))),
+        }
+    }
+}
+
 impl FromSql for Rowid {
     fn from_sql(value: NativeValue<'_>) -> Result<Rowid> {
         match value {
@@ -135,3 +235,145 @@ impl<'a> FromSqlUnsafe<'a> for &'a [u8] {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stmt::{ExecMode, Stmt};
+    use crate::types::{FromSql, Json, NativeValue, Vector};
+    use crate::{test_util, Error, Result};
+
+    fn convert<T: FromSql>(value: NativeValue<'_>) -> Result<T> {
+        T::from_sql(value)
+    }
+
+    #[test]
+    fn float_to_integer() {
+        assert_eq!(convert::<i32>(NativeValue::Double(Some(12.0))).unwrap(), 12);
+        assert_eq!(convert::<i64>(NativeValue::Float(Some(-3.0))).unwrap(), -3);
+        assert_eq!(
+            convert::<u64>(NativeValue::Double(Some(1e19))).unwrap(),
+            10_000_000_000_000_000_000
+        );
+        assert_eq!(
+            convert::<i128>(NativeValue::Double(Some(1e20))).unwrap(),
+            10i128.pow(20)
+        );
+        // fractional values aren't truncated.
+        assert!(convert::<i32>(NativeValue::Double(Some(1.5))).is_err());
+        assert!(convert::<i32>(NativeValue::Float(Some(0.1))).is_err());
+        // out-of-range values aren't saturated.
+        assert!(convert::<i8>(NativeValue::Double(Some(128.0))).is_err());
+        assert!(convert::<u32>(NativeValue::Double(Some(-1.0))).is_err());
+        assert!(convert::<i64>(NativeValue::Double(Some(1e19))).is_err());
+        assert!(convert::<i32>(NativeValue::Double(Some(f64::NAN))).is_err());
+        assert!(convert::<i32>(NativeValue::Double(Some(f64::INFINITY))).is_err());
+        assert!(matches!(
+            convert::<i32>(NativeValue::Double(None)),
+            Err(Error::NullValue)
+        ));
+    }
+
+    #[test]
+    fn numbers_from_text() {
+        assert_eq!(
+            convert::<i64>(NativeValue::Bytes(Some(b"-42"))).unwrap(),
+            -42
+        );
+        assert_eq!(
+            convert::<f64>(NativeValue::Bytes(Some(b"1.25"))).unwrap(),
+            1.25
+        );
+        assert!(convert::<i64>(NativeValue::Bytes(Some(b"1.25"))).is_err());
+        assert!(convert::<u8>(NativeValue::Int64(Some(256))).is_err());
+        assert_eq!(convert::<f32>(NativeValue::Int64(Some(3))).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(
+            convert::<String>(NativeValue::Bytes(Some(b"abc"))).unwrap(),
+            "abc"
+        );
+        assert_eq!(
+            convert::<String>(NativeValue::Int64(Some(-1))).unwrap(),
+            "-1"
+        );
+        assert_eq!(
+            convert::<String>(NativeValue::Uint64(Some(2))).unwrap(),
+            "2"
+        );
+        assert_eq!(
+            convert::<String>(NativeValue::Double(Some(0.5))).unwrap(),
+            "0.5"
+        );
+        assert_eq!(
+            convert::<String>(NativeValue::Boolean(Some(true))).unwrap(),
+            "true"
+        );
+        assert!(convert::<String>(NativeValue::Bytes(Some(b"\xff"))).is_err());
+        assert!(convert::<String>(NativeValue::Timestamp(None)).is_err());
+        assert!(matches!(
+            convert::<String>(NativeValue::Bytes(None)),
+            Err(Error::NullValue)
+        ));
+        assert_eq!(
+            convert::<Option<String>>(NativeValue::Bytes(None)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(
+            convert::<Vec<u8>>(NativeValue::Bytes(Some(b"\x00\xff"))).unwrap(),
+            [0, 255]
+        );
+        assert!(convert::<Vec<u8>>(NativeValue::Int64(Some(1))).is_err());
+        assert!(matches!(
+            convert::<Vec<u8>>(NativeValue::Bytes(None)),
+            Err(Error::NullValue)
+        ));
+    }
+
+    #[test]
+    fn handles() {
+        assert!(matches!(
+            convert::<Json>(NativeValue::Json(None)),
+            Err(Error::NullValue)
+        ));
+        assert!(matches!(
+            convert::<Vector>(NativeValue::Vector(None)),
+            Err(Error::NullValue)
+        ));
+        assert!(matches!(
+            convert::<Stmt>(NativeValue::Stmt(None)),
+            Err(Error::NullValue)
+        ));
+        assert!(convert::<Option<Json>>(NativeValue::Json(None))
+            .unwrap()
+            .is_none());
+        assert!(convert::<Json>(NativeValue::Bytes(Some(b"{}"))).is_err());
+        assert!(convert::<Vector>(NativeValue::Double(Some(1.0))).is_err());
+        assert!(convert::<Stmt>(NativeValue::Int64(Some(1))).is_err());
+    }
+
+    #[crate::test]
+    async fn ref_cursor() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt = conn.prepare_stmt(
+            false,
+            "select cursor(select 'a' from dual union all select 'b' from dual) from dual",
+            "",
+        )?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        let cursor: Stmt = stmt.query_value(1)?;
+        let mut values = vec![];
+        while cursor.fetch().await?.is_some() {
+            values.push(cursor.query_value::<String>(1)?);
+        }
+        assert_eq!(values, ["a", "b"]);
+        Ok(())
+    }
+}
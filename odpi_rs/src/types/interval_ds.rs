@@ -12,9 +12,10 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
+use crate::types::timestamp::fs_unit;
 use odpic_sys::*;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct IntervalDS {
     pub days: i32,
     pub hours: i32,
@@ -23,6 +24,18 @@ pub struct IntervalDS {
     pub fseconds: i32,
 }
 
+impl IntervalDS {
+    /// Returns the interval whose fractional seconds are truncated to
+    /// `fs_precision` digits, such as [`DataTypeInfo::fs_precision`][crate::types::DataTypeInfo::fs_precision].
+    pub fn with_fs_precision(self, fs_precision: u8) -> IntervalDS {
+        let unit = fs_unit(fs_precision) as i32;
+        IntervalDS {
+            fseconds: self.fseconds - self.fseconds % unit,
+            ..self
+        }
+    }
+}
+
 impl From<IntervalDS> for dpiIntervalDS {
     fn from(value: IntervalDS) -> dpiIntervalDS {
         dpiIntervalDS {
@@ -14,7 +14,7 @@
 //-----------------------------------------------------------------------------
 use odpic_sys::*;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct IntervalYM {
     pub years: i32,
     pub months: i32,
@@ -20,12 +20,19 @@ use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
 
 pub mod array;
+mod extended;
 mod json_node;
 mod native_value;
+mod node_tree;
 pub mod object;
+mod path;
+#[cfg(feature = "serde")]
+mod serde;
+mod text;
 
 pub use json_node::*;
 pub use native_value::NativeValue;
+pub use path::JsonPath;
 
 #[derive(Debug)]
 #[odpic_doc]
@@ -78,6 +85,63 @@ impl Json {
         ))
     }
 
+    /// Deserializes the JSON value to `T` by [serde].
+    ///
+    /// Oracle scalar types not in the JSON data model are passed to `T` as
+    /// extended JSON objects described in [`Json::set_from_serde`], except
+    /// that `RAW` values are passed as bytes when `T` expects bytes or a
+    /// sequence such as `Vec<u8>`.
+    /// Non-integer numbers are passed as `f64`, or as exact text when
+    /// `T` expects a string.
+    ///
+    /// [serde]: ::serde
+    #[cfg(feature = "serde")]
+    #[maybe_async]
+    pub async fn to_serde<T>(&self) -> Result<T>
+    where
+        T: ::serde::de::DeserializeOwned,
+    {
+        let node = unsafe { self.unsafe_value(JsonOptions::NUMBER_AS_STRING).await? };
+        T::deserialize(self::serde::NodeDeserializer::new(node))
+    }
+
+    /// Sets the JSON value serialized from `value` by [serde].
+    ///
+    /// The value is converted to JSON nodes directly without text
+    /// representation. Integers and floating-point numbers are set as
+    /// numbers, strings as strings and byte arrays as `RAW`.
+    ///
+    /// Objects with a single field whose key is one of extended JSON keys
+    /// listed in [`Value::to_json_string`] are converted to Oracle scalar
+    /// types. [`Value`], [`Timestamp`], [`IntervalDS`] and [`IntervalYM`]
+    /// are serialized in this form.
+    ///
+    /// [serde]: ::serde
+    /// [`Timestamp`]: crate::types::Timestamp
+    /// [`IntervalDS`]: crate::types::IntervalDS
+    /// [`IntervalYM`]: crate::types::IntervalYM
+    #[cfg(feature = "serde")]
+    pub fn set_from_serde<T>(&self, value: &T) -> Result<()>
+    where
+        T: ::serde::Serialize + ?Sized,
+    {
+        let mut tree = node_tree::NodeTree::new();
+        let top = value.serialize(self::serde::NodeSerializer::new(&mut tree))?;
+        tree.set_to(self.handle, top)
+    }
+
+    /// Sets the JSON value to `value`.
+    ///
+    /// Unlike [`Json::set_value`], `dpiJsonNode`, `dpiJsonArray` and
+    /// `dpiJsonObject` structures are laid out internally and kept alive
+    /// during the call. A value got by [`Json::value`] can be changed and
+    /// set back by this method.
+    pub fn set_owned_value(&self, value: &Value) -> Result<()> {
+        let mut tree = node_tree::NodeTree::new();
+        let top = tree.value(value)?;
+        tree.set_to(self.handle, top)
+    }
+
     pub fn set_value<'a, T>(&self, top_node: T) -> Result<()>
     where
         T: Into<dpiJsonNode>,
@@ -103,6 +167,8 @@ impl Drop for Json {
 
 #[cfg(test)]
 mod tests {
+    use crate::types::json::{DateTime, Map, Number, Value};
+    use crate::types::{IntervalDS, IntervalYM, JsonOptions, Timestamp};
     use crate::{test_util, Result};
 
     #[crate::test]
@@ -111,4 +177,65 @@ mod tests {
         let _json = conn.new_json()?;
         Ok(())
     }
+
+    #[crate::test]
+    async fn set_owned_value() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let json = conn.new_json()?;
+        let ts = Timestamp {
+            year: 2024,
+            month: 1,
+            day: 23,
+            hour: 4,
+            minute: 56,
+            second: 7,
+            fsecond: 0,
+            tz_hour_offset: 0,
+            tz_minute_offset: 0,
+        };
+        let mut object = Map::new();
+        object.insert("null".to_string(), Value::Null);
+        object.insert("bool".to_string(), Value::Bool(true));
+        object.insert(
+            "number".to_string(),
+            Value::Number(Number::String("123.45".to_string())),
+        );
+        object.insert("float".to_string(), Value::Number(Number::Float(1.5)));
+        object.insert("string".to_string(), Value::String("abc".to_string()));
+        object.insert("raw".to_string(), Value::Raw(vec![1, 2, 3]));
+        object.insert("date".to_string(), Value::Date(DateTime::Timestamp(ts)));
+        object.insert(
+            "timestamp".to_string(),
+            Value::Timestamp(DateTime::Timestamp(Timestamp {
+                fsecond: 123456000,
+                ..ts
+            })),
+        );
+        object.insert(
+            "interval_ds".to_string(),
+            Value::IntervalDS(IntervalDS {
+                days: 1,
+                hours: 2,
+                minutes: 3,
+                seconds: 4,
+                fseconds: 500000000,
+            }),
+        );
+        object.insert(
+            "interval_ym".to_string(),
+            Value::IntervalYM(IntervalYM {
+                years: 1,
+                months: 2,
+            }),
+        );
+        object.insert(
+            "array".to_string(),
+            Value::Array(vec![Value::Array(vec![]), Value::Object(Map::new())]),
+        );
+        let value = Value::Object(object);
+        json.set_owned_value(&value)?;
+        let fetched = json.value(JsonOptions::NUMBER_AS_STRING).await?;
+        assert_eq!(fetched, value);
+        Ok(())
+    }
 }
@@ -0,0 +1,472 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Extended JSON objects representing Oracle scalar types
+use crate::types::json::{DateTime, Number, Value};
+use crate::types::{IntervalDS, IntervalYM, OraNumber, Timestamp};
+use crate::{Error, Result};
+use std::fmt::Write;
+
+pub(crate) const DATE: &str = "$oracleDate";
+pub(crate) const TIMESTAMP: &str = "$oracleTimestamp";
+pub(crate) const TIMESTAMP_TZ: &str = "$oracleTimestampTZ";
+pub(crate) const INTERVAL_DS: &str = "$intervalDaySecond";
+pub(crate) const INTERVAL_YM: &str = "$intervalYearMonth";
+pub(crate) const RAW: &str = "$rawhex";
+pub(crate) const JSON_ID: &str = "$rawid";
+pub(crate) const VECTOR: &str = "$vectorImage";
+pub(crate) const NUMBER_DECIMAL: &str = "$numberDecimal";
+pub(crate) const NUMBER_FLOAT: &str = "$numberFloat";
+pub(crate) const NUMBER_DOUBLE: &str = "$numberDouble";
+
+const KEYS: [&str; 11] = [
+    DATE,
+    TIMESTAMP,
+    TIMESTAMP_TZ,
+    INTERVAL_DS,
+    INTERVAL_YM,
+    RAW,
+    JSON_ID,
+    VECTOR,
+    NUMBER_DECIMAL,
+    NUMBER_FLOAT,
+    NUMBER_DOUBLE,
+];
+
+/// Value of the single field in an extended JSON object
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ExtendedValue {
+    String(String),
+    Number(f64),
+}
+
+/// Returns the static key if `key` is the key of an extended JSON object.
+pub(crate) fn key(key: &str) -> Option<&'static str> {
+    KEYS.iter().find(|k| **k == key).copied()
+}
+
+/// Returns the value of an extended JSON object if `value` is a string or a number.
+pub(crate) fn from_value(value: &Value) -> Option<ExtendedValue> {
+    match value {
+        Value::String(value) => Some(ExtendedValue::String(value.clone())),
+        Value::Number(Number::Double(value)) => Some(ExtendedValue::Number(*value)),
+        Value::Number(Number::String(value)) => value.parse().ok().map(ExtendedValue::Number),
+        _ => None,
+    }
+}
+
+/// Encodes Oracle scalar types other than numbers to the key and the value
+/// of an extended JSON object.
+pub(crate) fn encode(value: &Value) -> Option<(&'static str, ExtendedValue)> {
+    fn date_time(key: &'static str, value: &DateTime) -> (&'static str, ExtendedValue) {
+        match value {
+            DateTime::Timestamp(ts) => (key, ExtendedValue::String(format_timestamp(ts))),
+            DateTime::Double(value) => (key, ExtendedValue::Number(*value)),
+        }
+    }
+    Some(match value {
+        Value::Date(value) => date_time(DATE, value),
+        Value::Timestamp(value) => date_time(TIMESTAMP, value),
+        Value::TimestampTZ(value) => (
+            TIMESTAMP_TZ,
+            ExtendedValue::String(format_timestamp_tz(value)),
+        ),
+        Value::IntervalDS(value) => (
+            INTERVAL_DS,
+            ExtendedValue::String(format_interval_ds(value)),
+        ),
+        Value::IntervalYM(value) => (
+            INTERVAL_YM,
+            ExtendedValue::String(format_interval_ym(value)),
+        ),
+        Value::Raw(value) => (RAW, ExtendedValue::String(to_hex(value))),
+        Value::JsonId(value) => (JSON_ID, ExtendedValue::String(to_hex(value))),
+        Value::Vector(value) => (VECTOR, ExtendedValue::String(to_hex(value))),
+        _ => return None,
+    })
+}
+
+/// Decodes an extended JSON object whose key is one of [`key`].
+pub(crate) fn decode(key: &str, value: ExtendedValue) -> Result<Value> {
+    let invalid = |value: &ExtendedValue| {
+        Error::other(format!(
+            "invalid value {:?} of extended JSON key {}",
+            value, key
+        ))
+    };
+    Ok(match (key, &value) {
+        (DATE, ExtendedValue::String(s)) => Value::Date(DateTime::Timestamp(parse_timestamp(s)?)),
+        (DATE, ExtendedValue::Number(n)) => Value::Date(DateTime::Double(*n)),
+        (TIMESTAMP, ExtendedValue::String(s)) => {
+            Value::Timestamp(DateTime::Timestamp(parse_timestamp(s)?))
+        }
+        (TIMESTAMP_TZ, ExtendedValue::String(s)) => Value::TimestampTZ(parse_timestamp(s)?),
+        (TIMESTAMP | TIMESTAMP_TZ, ExtendedValue::Number(n)) => {
+            Value::Timestamp(DateTime::Double(*n))
+        }
+        (INTERVAL_DS, ExtendedValue::String(s)) => Value::IntervalDS(parse_interval_ds(s)?),
+        (INTERVAL_YM, ExtendedValue::String(s)) => Value::IntervalYM(parse_interval_ym(s)?),
+        (RAW, ExtendedValue::String(s)) => Value::Raw(from_hex(s)?),
+        (JSON_ID, ExtendedValue::String(s)) => Value::JsonId(from_hex(s)?),
+        (VECTOR, ExtendedValue::String(s)) => Value::Vector(from_hex(s)?),
+        (NUMBER_DECIMAL, ExtendedValue::String(s)) => {
+            s.parse::<OraNumber>()?;
+            Value::Number(Number::String(s.clone()))
+        }
+        (NUMBER_FLOAT, ExtendedValue::Number(n)) => Value::Number(Number::Float(*n as f32)),
+        (NUMBER_DOUBLE, ExtendedValue::Number(n)) => Value::Number(Number::Double(*n)),
+        // "NaN", "Infinity" or "-Infinity"
+        (NUMBER_DOUBLE, ExtendedValue::String(s)) => Value::Number(Number::Double(s.parse()?)),
+        _ => return Err(invalid(&value)),
+    })
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        write!(s, "{:02x}", b).unwrap();
+    }
+    s
+}
+
+pub(crate) fn from_hex(s: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::other(format!("invalid hex string {:?}", s));
+    if s.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// Formats `ts` such as `2024-01-23T04:56:07.123` and `2024-01-23T04:56:07+09:00`.
+///
+/// The time zone offset is omitted when it is zero.
+pub(crate) fn format_timestamp(ts: &Timestamp) -> String {
+    let mut s = format!(
+        "{}{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        if ts.year < 0 { "-" } else { "" },
+        ts.year.unsigned_abs(),
+        ts.month,
+        ts.day,
+        ts.hour,
+        ts.minute,
+        ts.second
+    );
+    if ts.fsecond != 0 {
+        let fsecond = format!("{:09}", ts.fsecond);
+        s.push('.');
+        s.push_str(fsecond.trim_end_matches('0'));
+    }
+    if ts.tz_hour_offset != 0 || ts.tz_minute_offset != 0 {
+        let sign = if ts.tz_hour_offset < 0 || ts.tz_minute_offset < 0 {
+            '-'
+        } else {
+            '+'
+        };
+        write!(
+            s,
+            "{}{:02}:{:02}",
+            sign,
+            ts.tz_hour_offset.unsigned_abs(),
+            ts.tz_minute_offset.unsigned_abs()
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Formats `ts` with the time zone offset such as `2024-01-23T04:56:07+00:00`.
+pub(crate) fn format_timestamp_tz(ts: &Timestamp) -> String {
+    let mut s = format_timestamp(ts);
+    if ts.tz_hour_offset == 0 && ts.tz_minute_offset == 0 {
+        s.push_str("+00:00");
+    }
+    s
+}
+
+/// Simple parser for fixed-format text
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Parser<'a> {
+        Parser { s, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reads ASCII digits.
+    fn digits(&mut self) -> Option<&'a str> {
+        let len = self
+            .rest()
+            .bytes()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if len == 0 {
+            None
+        } else {
+            let digits = &self.rest()[..len];
+            self.pos += len;
+            Some(digits)
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Option<T> {
+        self.digits()?.parse().ok()
+    }
+
+    /// Reads fractional digits after a decimal point as nanoseconds.
+    fn nanos(&mut self) -> Option<u32> {
+        let digits = self.digits()?;
+        if digits.len() > 9 {
+            return None;
+        }
+        Some(digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32))
+    }
+}
+
+/// Parses text formatted by [`format_timestamp`]. The time part is optional.
+pub(crate) fn parse_timestamp(s: &str) -> Result<Timestamp> {
+    fn parse(s: &str) -> Option<Timestamp> {
+        let mut p = Parser::new(s);
+        let negative_year = p.eat('-');
+        let year: i16 = p.number()?;
+        let mut ts = Timestamp {
+            year: if negative_year { -year } else { year },
+            month: 0,
+            day: 0,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            fsecond: 0,
+            tz_hour_offset: 0,
+            tz_minute_offset: 0,
+        };
+        p.eat('-').then_some(())?;
+        ts.month = p.number()?;
+        p.eat('-').then_some(())?;
+        ts.day = p.number()?;
+        if p.eat('T') || p.eat(' ') {
+            ts.hour = p.number()?;
+            p.eat(':').then_some(())?;
+            ts.minute = p.number()?;
+            p.eat(':').then_some(())?;
+            ts.second = p.number()?;
+            if p.eat('.') {
+                ts.fsecond = p.nanos()?;
+            }
+            if !p.eat('Z') {
+                let sign = if p.eat('+') {
+                    1
+                } else if p.eat('-') {
+                    -1
+                } else {
+                    0
+                };
+                if sign != 0 {
+                    ts.tz_hour_offset = sign * p.number::<i8>()?;
+                    p.eat(':').then_some(())?;
+                    ts.tz_minute_offset = sign * p.number::<i8>()?;
+                }
+            }
+        }
+        p.is_end().then_some(ts)
+    }
+    parse(s).ok_or_else(|| Error::other(format!("invalid timestamp {:?}", s)))
+}
+
+/// Formats `value` in ISO 8601 duration format such as `P1DT2H3M4.5S` and `-P1DT0H0M0S`.
+pub(crate) fn format_interval_ds(value: &IntervalDS) -> String {
+    let negative = value.days < 0
+        || value.hours < 0
+        || value.minutes < 0
+        || value.seconds < 0
+        || value.fseconds < 0;
+    let mut s = format!(
+        "{}P{}DT{}H{}M{}",
+        if negative { "-" } else { "" },
+        value.days.unsigned_abs(),
+        value.hours.unsigned_abs(),
+        value.minutes.unsigned_abs(),
+        value.seconds.unsigned_abs()
+    );
+    if value.fseconds != 0 {
+        let fseconds = format!("{:09}", value.fseconds.unsigned_abs());
+        s.push('.');
+        s.push_str(fseconds.trim_end_matches('0'));
+    }
+    s.push('S');
+    s
+}
+
+/// Parses ISO 8601 duration format with days, hours, minutes and seconds.
+pub(crate) fn parse_interval_ds(s: &str) -> Result<IntervalDS> {
+    fn parse(s: &str) -> Option<IntervalDS> {
+        let mut p = Parser::new(s);
+        let sign = if p.eat('-') { -1 } else { 1 };
+        p.eat('P').then_some(())?;
+        let mut value = IntervalDS {
+            days: 0,
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+            fseconds: 0,
+        };
+        let mut has_value = false;
+        if let Some(days) = p.number::<i32>() {
+            p.eat('D').then_some(())?;
+            value.days = sign * days;
+            has_value = true;
+        }
+        if p.eat('T') {
+            has_value = false;
+            let mut num = p.number::<i32>();
+            if num.is_some() && p.eat('H') {
+                value.hours = sign * num?;
+                has_value = true;
+                num = p.number();
+            }
+            if num.is_some() && p.eat('M') {
+                value.minutes = sign * num?;
+                has_value = true;
+                num = p.number();
+            }
+            if let Some(seconds) = num {
+                value.seconds = sign * seconds;
+                if p.eat('.') {
+                    value.fseconds = sign * p.nanos()? as i32;
+                }
+                p.eat('S').then_some(())?;
+                has_value = true;
+            }
+        }
+        (has_value && p.is_end()).then_some(value)
+    }
+    parse(s).ok_or_else(|| Error::other(format!("invalid interval day to second {:?}", s)))
+}
+
+/// Formats `value` in ISO 8601 duration format such as `P1Y2M` and `-P0Y3M`.
+pub(crate) fn format_interval_ym(value: &IntervalYM) -> String {
+    let negative = value.years < 0 || value.months < 0;
+    format!(
+        "{}P{}Y{}M",
+        if negative { "-" } else { "" },
+        value.years.unsigned_abs(),
+        value.months.unsigned_abs()
+    )
+}
+
+/// Parses ISO 8601 duration format with years and months.
+pub(crate) fn parse_interval_ym(s: &str) -> Result<IntervalYM> {
+    fn parse(s: &str) -> Option<IntervalYM> {
+        let mut p = Parser::new(s);
+        let sign = if p.eat('-') { -1 } else { 1 };
+        p.eat('P').then_some(())?;
+        let mut value = IntervalYM {
+            years: 0,
+            months: 0,
+        };
+        let mut has_value = false;
+        let mut num = p.number::<i32>();
+        if num.is_some() && p.eat('Y') {
+            value.years = sign * num?;
+            has_value = true;
+            num = p.number();
+        }
+        if num.is_some() && p.eat('M') {
+            value.months = sign * num?;
+            has_value = true;
+        } else if num.is_some() {
+            return None;
+        }
+        (has_value && p.is_end()).then_some(value)
+    }
+    parse(s).ok_or_else(|| Error::other(format!("invalid interval year to month {:?}", s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp() -> Result<()> {
+        for s in [
+            "2024-01-23T04:56:07",
+            "2024-01-23T04:56:07.1",
+            "2024-01-23T04:56:07.123456789+09:00",
+            "-0044-03-15T00:00:00-00:30",
+        ] {
+            assert_eq!(format_timestamp(&parse_timestamp(s)?), s);
+        }
+        let ts = parse_timestamp("2024-01-23")?;
+        assert_eq!(format_timestamp(&ts), "2024-01-23T00:00:00");
+        let ts = parse_timestamp("2024-01-23 04:56:07Z")?;
+        assert_eq!(format_timestamp(&ts), "2024-01-23T04:56:07");
+        assert_eq!(format_timestamp_tz(&ts), "2024-01-23T04:56:07+00:00");
+        let ts = parse_timestamp("2024-01-23T04:56:07-09:30")?;
+        assert_eq!(format_timestamp_tz(&ts), "2024-01-23T04:56:07-09:30");
+        assert!(parse_timestamp("2024-01-23T04:56").is_err());
+        assert!(parse_timestamp("2024-01-23T04:56:07.1234567890").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn interval() -> Result<()> {
+        for s in ["P1DT2H3M4.5S", "-P0DT0H0M0.000001S", "P0DT0H0M0S"] {
+            assert_eq!(format_interval_ds(&parse_interval_ds(s)?), s);
+        }
+        assert_eq!(
+            format_interval_ds(&parse_interval_ds("PT90M")?),
+            "P0DT0H90M0S"
+        );
+        assert!(parse_interval_ds("P1DT").is_err());
+        for s in ["P1Y2M", "-P0Y3M"] {
+            assert_eq!(format_interval_ym(&parse_interval_ym(s)?), s);
+        }
+        assert!(parse_interval_ym("P").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn hex() -> Result<()> {
+        assert_eq!(to_hex(&[0x01, 0xab, 0xff]), "01abff");
+        assert_eq!(from_hex("01ABff")?, [0x01, 0xab, 0xff]);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+        Ok(())
+    }
+}
@@ -56,7 +56,7 @@ impl ToDpi<*mut dpiJsonNode> for Node<'_> {
 
 pub type Map<K, V> = std::collections::HashMap<K, V>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
 pub enum Number {
     String(String),
@@ -64,14 +64,14 @@ pub enum Number {
     Float(f32),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
 pub enum DateTime {
     Timestamp(Timestamp),
     Double(f64),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
 pub enum Value {
     Null,
@@ -84,6 +84,7 @@ pub enum Value {
     JsonId(Vec<u8>),
     Date(DateTime),
     Timestamp(DateTime),
+    TimestampTZ(Timestamp),
     IntervalDS(IntervalDS),
     IntervalYM(IntervalYM),
     #[doc(hidden)]
@@ -107,10 +108,10 @@ impl Value {
                 (OracleType::JsonId, NativeType::Bytes) => Ok(Value::JsonId(
                     (value.asBytes.ptr, value.asBytes.length).try_to_rust()?,
                 )),
-                (OracleType::Number, NativeType::Float) => {
+                (OracleType::Number | OracleType::NativeFloat, NativeType::Float) => {
                     Ok(Value::Number(Number::Float(value.asFloat)))
                 }
-                (OracleType::Number, NativeType::Double) => {
+                (OracleType::Number | OracleType::NativeDouble, NativeType::Double) => {
                     Ok(Value::Number(Number::Double(value.asDouble)))
                 }
                 (OracleType::Number, NativeType::Bytes) => {
@@ -130,6 +131,9 @@ impl Value {
                 (OracleType::Timestamp, NativeType::Timestamp) => Ok(Value::Timestamp(
                     DateTime::Timestamp(value.asTimestamp.into()),
                 )),
+                (OracleType::TimestampTZ, NativeType::Timestamp) => {
+                    Ok(Value::TimestampTZ(value.asTimestamp.into()))
+                }
                 (OracleType::IntervalDS, NativeType::IntervalDS) => {
                     Ok(Value::IntervalDS(value.asIntervalDS.into()))
                 }
@@ -141,6 +145,18 @@ impl Value {
                 (OracleType::Vector, NativeType::Bytes) => Ok(Value::Vector(
                     (value.asBytes.ptr, value.asBytes.length).try_to_rust()?,
                 )),
+                (OracleType::JsonArray, NativeType::JsonArray) => Ok(Value::Array(
+                    Array::ref_from_dpi_ptr(&value.asJsonArray)
+                        .iter()
+                        .map(Node::to_owned)
+                        .collect::<Result<_>>()?,
+                )),
+                (OracleType::JsonObject, NativeType::JsonObject) => Ok(Value::Object(
+                    Object::ref_from_dpi_ptr(&value.asJsonObject)
+                        .iter()
+                        .map(|(key, value)| Ok((key.to_string(), value.to_owned()?)))
+                        .collect::<Result<_>>()?,
+                )),
                 _ => Err(Error::other(format!(
                     "unexpected Oracle type and native type pair: {:?} and {:?}",
                     oracle_type, native_type
@@ -0,0 +1,257 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Owned storage of a tree of `dpiJsonNode` passed to `dpiJson_setValue`
+use crate::types::json::{DateTime, Node, Number, Value};
+use crate::types::{IntervalDS, IntervalYM, NativeType, OracleType, Timestamp};
+use crate::utils::*;
+use crate::Result;
+use odpic_sys::*;
+use std::os::raw::c_char;
+use std::ptr;
+
+/// A node not yet placed in a `dpiJsonNode`
+pub(crate) struct NodeValue {
+    oracle_type: OracleType,
+    native_type: NativeType,
+    value: dpiDataBuffer,
+}
+
+impl NodeValue {
+    pub(crate) fn null() -> NodeValue {
+        NodeValue {
+            oracle_type: OracleType::None,
+            native_type: NativeType::Null,
+            value: unsafe { std::mem::zeroed() },
+        }
+    }
+
+    pub(crate) fn boolean(value: bool) -> NodeValue {
+        NodeValue {
+            oracle_type: OracleType::Boolean,
+            native_type: NativeType::Boolean,
+            value: dpiDataBuffer {
+                asBoolean: value.into(),
+            },
+        }
+    }
+
+    pub(crate) fn float(value: f32) -> NodeValue {
+        NodeValue {
+            oracle_type: OracleType::NativeFloat,
+            native_type: NativeType::Float,
+            value: dpiDataBuffer { asFloat: value },
+        }
+    }
+
+    pub(crate) fn double(oracle_type: OracleType, value: f64) -> NodeValue {
+        NodeValue {
+            oracle_type,
+            native_type: NativeType::Double,
+            value: dpiDataBuffer { asDouble: value },
+        }
+    }
+
+    pub(crate) fn timestamp(oracle_type: OracleType, value: Timestamp) -> NodeValue {
+        NodeValue {
+            oracle_type,
+            native_type: NativeType::Timestamp,
+            value: dpiDataBuffer {
+                asTimestamp: value.into(),
+            },
+        }
+    }
+
+    pub(crate) fn interval_ds(value: IntervalDS) -> NodeValue {
+        NodeValue {
+            oracle_type: OracleType::IntervalDS,
+            native_type: NativeType::IntervalDS,
+            value: dpiDataBuffer {
+                asIntervalDS: value.into(),
+            },
+        }
+    }
+
+    pub(crate) fn interval_ym(value: IntervalYM) -> NodeValue {
+        NodeValue {
+            oracle_type: OracleType::IntervalYM,
+            native_type: NativeType::IntervalYM,
+            value: dpiDataBuffer {
+                asIntervalYM: value.into(),
+            },
+        }
+    }
+}
+
+/// Storage of arrays, objects and byte strings referred by [`NodeValue`]s
+///
+/// Pointers in `NodeValue`s created by a `NodeTree` are valid while the
+/// `NodeTree` is alive because buffers of inner `Vec`s are never moved.
+#[derive(Default)]
+pub(crate) struct NodeTree {
+    bytes: Vec<Vec<u8>>,
+    nodes: Vec<Vec<dpiJsonNode>>,
+    values: Vec<Vec<dpiDataBuffer>>,
+    field_names: Vec<Vec<*mut c_char>>,
+    field_name_lengths: Vec<Vec<u32>>,
+}
+
+impl NodeTree {
+    pub(crate) fn new() -> NodeTree {
+        NodeTree::default()
+    }
+
+    pub(crate) fn bytes(&mut self, oracle_type: OracleType, bytes: Vec<u8>) -> Result<NodeValue> {
+        let value = dpiDataBuffer {
+            asBytes: dpiBytes {
+                ptr: bytes.as_ptr() as *mut c_char,
+                length: bytes.len().try_into()?,
+                encoding: ptr::null(),
+            },
+        };
+        self.bytes.push(bytes);
+        Ok(NodeValue {
+            oracle_type,
+            native_type: NativeType::Bytes,
+            value,
+        })
+    }
+
+    /// Places `elements` in `dpiJsonNode`s and returns pointers to the nodes and their values.
+    fn nodes(&mut self, elements: Vec<NodeValue>) -> (*mut dpiJsonNode, *mut dpiDataBuffer) {
+        let mut values = elements.iter().map(|e| e.value).collect::<Vec<_>>();
+        let mut nodes = elements
+            .iter()
+            .zip(values.iter_mut())
+            .map(|(e, value)| dpiJsonNode {
+                oracleTypeNum: e.oracle_type.to_dpi(),
+                nativeTypeNum: e.native_type.to_dpi(),
+                value,
+            })
+            .collect::<Vec<_>>();
+        let ptrs = (nodes.as_mut_ptr(), values.as_mut_ptr());
+        self.nodes.push(nodes);
+        self.values.push(values);
+        ptrs
+    }
+
+    pub(crate) fn array(&mut self, elements: Vec<NodeValue>) -> Result<NodeValue> {
+        let num_elements = elements.len().try_into()?;
+        let (elements, element_values) = self.nodes(elements);
+        Ok(NodeValue {
+            oracle_type: OracleType::JsonArray,
+            native_type: NativeType::JsonArray,
+            value: dpiDataBuffer {
+                asJsonArray: dpiJsonArray {
+                    numElements: num_elements,
+                    elements,
+                    elementValues: element_values,
+                },
+            },
+        })
+    }
+
+    pub(crate) fn object(&mut self, fields: Vec<(String, NodeValue)>) -> Result<NodeValue> {
+        let num_fields = fields.len().try_into()?;
+        let mut names = Vec::with_capacity(fields.len());
+        let mut lengths = Vec::with_capacity(fields.len());
+        let mut values = Vec::with_capacity(fields.len());
+        for (name, value) in fields {
+            let name = name.into_bytes();
+            names.push(name.as_ptr() as *mut c_char);
+            lengths.push(name.len().try_into()?);
+            self.bytes.push(name);
+            values.push(value);
+        }
+        let (fields, field_values) = self.nodes(values);
+        let object = dpiJsonObject {
+            numFields: num_fields,
+            fieldNames: names.as_mut_ptr(),
+            fieldNameLengths: lengths.as_mut_ptr(),
+            fields,
+            fieldValues: field_values,
+        };
+        self.field_names.push(names);
+        self.field_name_lengths.push(lengths);
+        Ok(NodeValue {
+            oracle_type: OracleType::JsonObject,
+            native_type: NativeType::JsonObject,
+            value: dpiDataBuffer {
+                asJsonObject: object,
+            },
+        })
+    }
+
+    /// Lays out `value` in this tree.
+    pub(crate) fn value(&mut self, value: &Value) -> Result<NodeValue> {
+        Ok(match value {
+            Value::Null => NodeValue::null(),
+            Value::Bool(value) => NodeValue::boolean(*value),
+            Value::Number(Number::String(value)) => {
+                self.bytes(OracleType::Number, value.clone().into_bytes())?
+            }
+            Value::Number(Number::Double(value)) => NodeValue::double(OracleType::Number, *value),
+            Value::Number(Number::Float(value)) => NodeValue::float(*value),
+            Value::String(value) => self.bytes(OracleType::Varchar, value.clone().into_bytes())?,
+            Value::Array(values) => {
+                let elements = values
+                    .iter()
+                    .map(|value| self.value(value))
+                    .collect::<Result<Vec<_>>>()?;
+                self.array(elements)?
+            }
+            Value::Object(map) => {
+                let fields = map
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), self.value(value)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                self.object(fields)?
+            }
+            Value::Raw(value) => self.bytes(OracleType::Raw, value.clone())?,
+            Value::JsonId(value) => self.bytes(OracleType::JsonId, value.clone())?,
+            Value::Date(value) => date_time(OracleType::Date, value),
+            Value::Timestamp(value) => date_time(OracleType::Timestamp, value),
+            Value::TimestampTZ(value) => NodeValue::timestamp(OracleType::TimestampTZ, *value),
+            Value::IntervalDS(value) => NodeValue::interval_ds(*value),
+            Value::IntervalYM(value) => NodeValue::interval_ym(*value),
+            Value::Vector(value) => self.bytes(OracleType::Vector, value.clone())?,
+        })
+    }
+
+    /// Calls `f` with `top` laid out in this tree.
+    pub(crate) fn with_node<R, F>(&self, top: NodeValue, f: F) -> R
+    where
+        F: FnOnce(&Node) -> R,
+    {
+        let mut value = top.value;
+        let node = dpiJsonNode {
+            oracleTypeNum: top.oracle_type.to_dpi(),
+            nativeTypeNum: top.native_type.to_dpi(),
+            value: &mut value,
+        };
+        f(unsafe { Node::ref_from_dpi_ptr(&node) })
+    }
+
+    /// Sets `top` laid out in this tree to `json`.
+    pub(crate) fn set_to(&self, json: *mut dpiJson, top: NodeValue) -> Result<()> {
+        self.with_node(top, |node| call!(dpiJson_setValue(json, node.to_dpi())))
+    }
+}
+
+fn date_time(oracle_type: OracleType, value: &DateTime) -> NodeValue {
+    match value {
+        DateTime::Timestamp(value) => NodeValue::timestamp(oracle_type, *value),
+        DateTime::Double(value) => NodeValue::double(oracle_type, *value),
+    }
+}
@@ -0,0 +1,682 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::types::json::{Map, NativeValue, Node, Value};
+use crate::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// SQL/JSON path expression
+///
+/// This is a subset of path expressions used in `JSON_VALUE` and
+/// `JSON_QUERY` of Oracle SQL. A path starts with `$` followed by steps:
+///
+/// | Step | Description |
+/// |---|---|
+/// | `.name`, `."name"` | field `name` of an object |
+/// | `.*` | all field values of an object |
+/// | `[2]`, `[last]`, `[last-1]` | an array element |
+/// | `[1 to 3]`, `[0, 2, 4 to last]` | array elements in the order of selectors |
+/// | `[*]` | all array elements |
+///
+/// Steps are evaluated in lax mode as in Oracle. A field step on an array is
+/// applied to each element of the array, and an array step on a non-array
+/// value treats the value as an array of one element.
+/// Filter expressions and item methods are not supported.
+///
+/// ```ignore
+/// use odpi_rs::types::json::JsonPath;
+///
+/// let path: JsonPath = "$.items[0 to 1].name".parse()?;
+/// let names = json_value.query(&path);
+///
+/// // borrowed nodes can be queried without `to_owned()`
+/// let node = unsafe { json.unsafe_value(JsonOptions::DEFAULT).await? };
+/// let name = node.get_path(&"$.items[last].name".parse()?);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPath {
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Step {
+    Field(String),
+    AnyField,
+    Elements(Vec<Selector>),
+    AnyElement,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Selector {
+    Index(Index),
+    Range(Index, Index),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Index {
+    Nth(usize),
+    /// `last - n`
+    Last(usize),
+}
+
+impl Index {
+    fn resolve(self, len: usize) -> i64 {
+        match self {
+            Index::Nth(n) => n as i64,
+            Index::Last(n) => len as i64 - 1 - n as i64,
+        }
+    }
+}
+
+impl Selector {
+    /// Returns the range of indexes selected in an array whose length is `len`.
+    fn indexes(self, len: usize) -> std::ops::Range<usize> {
+        let (start, end) = match self {
+            Selector::Index(index) => (index.resolve(len), index.resolve(len)),
+            Selector::Range(start, end) => (start.resolve(len).max(0), end.resolve(len)),
+        };
+        let end = end.min(len as i64 - 1);
+        if 0 <= start && start <= end {
+            start as usize..end as usize + 1
+        } else {
+            0..0
+        }
+    }
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<JsonPath> {
+        Parser::new(path).parse()
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<JsonPath> {
+        JsonPath::parse(s)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("$")?;
+        for step in &self.steps {
+            match step {
+                Step::Field(name) if is_simple_name(name) => write!(f, ".{}", name)?,
+                Step::Field(name) => write!(f, ".{:?}", name)?,
+                Step::AnyField => f.write_str(".*")?,
+                Step::AnyElement => f.write_str("[*]")?,
+                Step::Elements(selectors) => {
+                    f.write_str("[")?;
+                    for (i, selector) in selectors.iter().enumerate() {
+                        if i > 0 {
+                            f.write_str(", ")?;
+                        }
+                        match selector {
+                            Selector::Index(index) => write!(f, "{}", index)?,
+                            Selector::Range(start, end) => write!(f, "{} to {}", start, end)?,
+                        }
+                    }
+                    f.write_str("]")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Index::Nth(n) => write!(f, "{}", n),
+            Index::Last(0) => f.write_str("last"),
+            Index::Last(n) => write!(f, "last-{}", n),
+        }
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_simple_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, is_name_start) && chars.all(is_name_char) && name != "last"
+}
+
+struct Parser<'a> {
+    path: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(path: &'a str) -> Parser<'a> {
+        Parser { path, pos: 0 }
+    }
+
+    fn error(&self) -> Error {
+        Error::other(format!(
+            "invalid JSON path {:?} at position {}",
+            self.path, self.pos
+        ))
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.path[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.path.len() - self.rest().trim_start().len();
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<()> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn parse(mut self) -> Result<JsonPath> {
+        self.expect("$")?;
+        let mut steps = Vec::new();
+        loop {
+            if self.eat(".") {
+                steps.push(self.field()?);
+            } else if self.eat("[") {
+                steps.push(self.elements()?);
+            } else {
+                self.skip_whitespace();
+                if self.rest().is_empty() {
+                    return Ok(JsonPath { steps });
+                }
+                return Err(self.error());
+            }
+        }
+    }
+
+    // Parses a step after `.`.
+    fn field(&mut self) -> Result<Step> {
+        if self.eat("*") {
+            return Ok(Step::AnyField);
+        }
+        if self.eat("\"") {
+            return Ok(Step::Field(self.quoted_name()?));
+        }
+        match self.peek() {
+            Some(c) if is_name_start(c) => {
+                let len = self
+                    .rest()
+                    .find(|c| !is_name_char(c))
+                    .unwrap_or(self.rest().len());
+                let name = self.rest()[..len].to_string();
+                self.pos += len;
+                Ok(Step::Field(name))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    // Parses a double-quoted name after `"`.
+    fn quoted_name(&mut self) -> Result<String> {
+        let mut name = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(name);
+                }
+                '\\' => {
+                    let c = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .filter(|_| hex.len() == 4)
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error())?
+                        }
+                        _ => return Err(self.error()),
+                    };
+                    name.push(c);
+                }
+                c => name.push(c),
+            }
+        }
+        Err(self.error())
+    }
+
+    // Parses a step after `[`.
+    fn elements(&mut self) -> Result<Step> {
+        if self.eat("*") {
+            self.expect("]")?;
+            return Ok(Step::AnyElement);
+        }
+        let mut selectors = Vec::new();
+        loop {
+            let start = self.index()?;
+            if self.eat("to") {
+                selectors.push(Selector::Range(start, self.index()?));
+            } else {
+                selectors.push(Selector::Index(start));
+            }
+            if self.eat("]") {
+                return Ok(Step::Elements(selectors));
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn index(&mut self) -> Result<Index> {
+        if self.eat("last") {
+            if self.eat("-") {
+                Ok(Index::Last(self.number()?))
+            } else {
+                Ok(Index::Last(0))
+            }
+        } else {
+            Ok(Index::Nth(self.number()?))
+        }
+    }
+
+    fn number(&mut self) -> Result<usize> {
+        self.skip_whitespace();
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        let number = self.rest()[..len].parse().map_err(|_| self.error())?;
+        self.pos += len;
+        Ok(number)
+    }
+}
+
+/// A JSON value which a path is evaluated on
+trait Item: Sized {
+    fn field(&self, name: &str) -> Option<Self>;
+    fn field_values(&self) -> Option<Vec<Self>>;
+    fn array_len(&self) -> Option<usize>;
+    fn element(&self, index: usize) -> Self;
+}
+
+// Pushes values selected by `steps` to `out` until its length reaches `limit`.
+fn eval<T: Item>(item: T, steps: &[Step], out: &mut Vec<T>, limit: usize) {
+    if out.len() >= limit {
+        return;
+    }
+    let (step, rest) = match steps.split_first() {
+        Some(step) => step,
+        None => {
+            out.push(item);
+            return;
+        }
+    };
+    match step {
+        Step::Field(_) | Step::AnyField => match item.array_len() {
+            Some(len) => {
+                for index in 0..len {
+                    eval_field(item.element(index), step, rest, out, limit);
+                }
+            }
+            None => eval_field(item, step, rest, out, limit),
+        },
+        Step::AnyElement => match item.array_len() {
+            Some(len) => {
+                for index in 0..len {
+                    eval(item.element(index), rest, out, limit);
+                }
+            }
+            None => eval(item, rest, out, limit),
+        },
+        Step::Elements(selectors) => match item.array_len() {
+            Some(len) => {
+                for selector in selectors {
+                    for index in selector.indexes(len) {
+                        eval(item.element(index), rest, out, limit);
+                    }
+                }
+            }
+            None => {
+                if selectors.iter().any(|s| !s.indexes(1).is_empty()) {
+                    eval(item, rest, out, limit);
+                }
+            }
+        },
+    }
+}
+
+fn eval_field<T: Item>(item: T, step: &Step, rest: &[Step], out: &mut Vec<T>, limit: usize) {
+    match step {
+        Step::Field(name) => {
+            if let Some(value) = item.field(name) {
+                eval(value, rest, out, limit);
+            }
+        }
+        _ => {
+            for value in item.field_values().unwrap_or_default() {
+                eval(value, rest, out, limit);
+            }
+        }
+    }
+}
+
+/// Returns fields sorted by keys because [`Map`] doesn't keep their order.
+fn sorted_fields(map: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut fields = map.iter().collect::<Vec<_>>();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    fields
+}
+
+impl<'a> Item for &'a Value {
+    fn field(&self, name: &str) -> Option<&'a Value> {
+        match self {
+            Value::Object(map) => map.get(name),
+            _ => None,
+        }
+    }
+
+    fn field_values(&self) -> Option<Vec<&'a Value>> {
+        match self {
+            Value::Object(map) => Some(
+                sorted_fields(map)
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        match self {
+            Value::Array(values) => Some(values.len()),
+            _ => None,
+        }
+    }
+
+    fn element(&self, index: usize) -> &'a Value {
+        match self {
+            Value::Array(values) => &values[index],
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Key of a field or an element from the root value
+#[derive(Clone)]
+enum Key {
+    Field(String),
+    Index(usize),
+}
+
+/// A value with keys from the root value to locate it mutably
+struct Located<'a> {
+    value: &'a Value,
+    keys: Vec<Key>,
+}
+
+impl<'a> Located<'a> {
+    fn child(&self, value: &'a Value, key: Key) -> Located<'a> {
+        let mut keys = self.keys.clone();
+        keys.push(key);
+        Located { value, keys }
+    }
+}
+
+impl<'a> Item for Located<'a> {
+    fn field(&self, name: &str) -> Option<Located<'a>> {
+        let value = self.value.field(name)?;
+        Some(self.child(value, Key::Field(name.to_string())))
+    }
+
+    fn field_values(&self) -> Option<Vec<Located<'a>>> {
+        match self.value {
+            Value::Object(map) => Some(
+                sorted_fields(map)
+                    .into_iter()
+                    .map(|(name, value)| self.child(value, Key::Field(name.clone())))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        self.value.array_len()
+    }
+
+    fn element(&self, index: usize) -> Located<'a> {
+        self.child(self.value.element(index), Key::Index(index))
+    }
+}
+
+impl<'a> Item for &'a Node<'a> {
+    fn field(&self, name: &str) -> Option<&'a Node<'a>> {
+        match self.data() {
+            Ok(NativeValue::Object(object)) => object.get(name),
+            _ => None,
+        }
+    }
+
+    fn field_values(&self) -> Option<Vec<&'a Node<'a>>> {
+        match self.data() {
+            Ok(NativeValue::Object(object)) => Some(
+                (0..object.len())
+                    .map(|index| unsafe { object.value_unchecked(index) })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        match self.data() {
+            Ok(NativeValue::Array(array)) => Some(array.len()),
+            _ => None,
+        }
+    }
+
+    fn element(&self, index: usize) -> &'a Node<'a> {
+        match self.data() {
+            Ok(NativeValue::Array(array)) => &array[index],
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Value {
+    /// Returns values selected by `path`.
+    ///
+    /// Fields of [`Value::Object`] are selected by wildcards in the order of keys.
+    pub fn query(&self, path: &JsonPath) -> Vec<&Value> {
+        let mut values = Vec::new();
+        eval(self, &path.steps, &mut values, usize::MAX);
+        values
+    }
+
+    /// Returns the first value selected by `path`.
+    pub fn get_path(&self, path: &JsonPath) -> Option<&Value> {
+        let mut values = Vec::with_capacity(1);
+        eval(self, &path.steps, &mut values, 1);
+        values.pop()
+    }
+
+    /// Returns a mutable reference to the first value selected by `path`.
+    ///
+    /// This is similar to `pointer_mut` of `serde_json::Value` but takes
+    /// a [`JsonPath`].
+    pub fn get_path_mut(&mut self, path: &JsonPath) -> Option<&mut Value> {
+        let keys = {
+            let mut values = Vec::with_capacity(1);
+            let root = Located {
+                value: self,
+                keys: Vec::new(),
+            };
+            eval(root, &path.steps, &mut values, 1);
+            values.pop()?.keys
+        };
+        let mut value = self;
+        for key in keys {
+            value = match (key, value) {
+                (Key::Field(name), Value::Object(map)) => map.get_mut(&name)?,
+                (Key::Index(index), Value::Array(values)) => values.get_mut(index)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+}
+
+impl<'a> Node<'a> {
+    /// Returns nodes selected by `path` without converting the whole tree to [`Value`].
+    pub fn query(&'a self, path: &JsonPath) -> Vec<&'a Node<'a>> {
+        let mut nodes = Vec::new();
+        eval(self, &path.steps, &mut nodes, usize::MAX);
+        nodes
+    }
+
+    /// Returns the first node selected by `path`.
+    pub fn get_path(&'a self, path: &JsonPath) -> Option<&'a Node<'a>> {
+        let mut nodes = Vec::with_capacity(1);
+        eval(self, &path.steps, &mut nodes, 1);
+        nodes.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::json::Number;
+
+    fn num(n: i32) -> Value {
+        Value::Number(Number::String(n.to_string()))
+    }
+
+    fn array(values: &[Value]) -> Value {
+        Value::Array(values.to_vec())
+    }
+
+    fn object(fields: &[(&str, Value)]) -> Value {
+        Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect::<Map<_, _>>(),
+        )
+    }
+
+    fn query(value: &Value, path: &str) -> Result<Vec<Value>> {
+        Ok(value.query(&path.parse()?).into_iter().cloned().collect())
+    }
+
+    #[test]
+    fn parse() -> Result<()> {
+        for path in [
+            "$",
+            "$.a.b[0]",
+            "$.*[*]",
+            "$[1 to 3, last, last-2 to last]",
+            "$.\"a b\".c",
+        ] {
+            assert_eq!(JsonPath::parse(path)?.to_string(), path);
+        }
+        assert_eq!(
+            JsonPath::parse(" $ . a [ 1 , 2 ] . \"\\u0062\" ")?.to_string(),
+            "$.a[1, 2].b"
+        );
+        for path in ["", "a", "$.", "$[", "$[1", "$[a]", "$.a b", "$.\"a"] {
+            assert!(JsonPath::parse(path).is_err(), "{}", path);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn eval() -> Result<()> {
+        let value = object(&[
+            (
+                "items",
+                array(&[
+                    object(&[("id", num(1)), ("tags", array(&[num(10), num(11)]))]),
+                    object(&[("id", num(2)), ("tags", num(20))]),
+                    object(&[("id", num(3))]),
+                ]),
+            ),
+            ("a b", num(4)),
+        ]);
+        assert_eq!(query(&value, "$.items[0].id")?, [num(1)]);
+        assert_eq!(query(&value, "$.items.id")?, [num(1), num(2), num(3)]);
+        assert_eq!(query(&value, "$.items[*].id")?, [num(1), num(2), num(3)]);
+        assert_eq!(query(&value, "$.items[last].id")?, [num(3)]);
+        assert_eq!(
+            query(&value, "$.items[2, 0 to 1].id")?,
+            [num(3), num(1), num(2)]
+        );
+        assert_eq!(query(&value, "$.items[last-1 to 5].id")?, [num(2), num(3)]);
+        assert_eq!(query(&value, "$.items.tags[0]")?, [num(10), num(20)]);
+        assert_eq!(query(&value, "$.items.tags[1]")?, [num(11)]);
+        assert_eq!(query(&value, "$.\"a b\"")?, [num(4)]);
+        assert_eq!(
+            query(&value, "$.items[0].*")?,
+            [num(1), array(&[num(10), num(11)])]
+        );
+        assert!(query(&value, "$.items[3]")?.is_empty());
+        assert!(query(&value, "$.nothing.id")?.is_empty());
+        assert_eq!(value.get_path(&"$.items.id".parse()?), Some(&num(1)));
+        let value = object(&[("c", num(3)), ("a", num(1)), ("d", num(4)), ("b", num(2))]);
+        assert_eq!(query(&value, "$.*")?, [num(1), num(2), num(3), num(4)]);
+        assert_eq!(value.get_path(&"$.*".parse()?), Some(&num(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn get_path_mut() -> Result<()> {
+        let mut value = object(&[("a", array(&[object(&[]), object(&[("b", num(1))])]))]);
+        *value.get_path_mut(&"$.a.b".parse()?).unwrap() = num(2);
+        assert_eq!(query(&value, "$.a[1].b")?, [num(2)]);
+        assert!(value.get_path_mut(&"$.a.c".parse()?).is_none());
+        let mut value = object(&[("b", num(2)), ("a", num(1)), ("c", num(3))]);
+        *value.get_path_mut(&"$.*".parse()?).unwrap() = num(0);
+        assert_eq!(query(&value, "$.*")?, [num(0), num(2), num(3)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,1402 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Serialization and deserialization of JSON values by [serde]
+//!
+//! Oracle scalar types not in the JSON data model are represented by
+//! objects with a single field as follows.
+//!
+//! | Oracle type | Extended JSON object |
+//! |---|---|
+//! | `DATE` | `{"$oracleDate": "2024-01-23T04:56:07"}` |
+//! | `TIMESTAMP` | `{"$oracleTimestamp": "2024-01-23T04:56:07.123456"}` |
+//! | `TIMESTAMP WITH TIME ZONE` | `{"$oracleTimestampTZ": "2024-01-23T04:56:07.123456+09:00"}` |
+//! | `INTERVAL DAY TO SECOND` | `{"$intervalDaySecond": "P1DT2H3M4.5S"}` |
+//! | `INTERVAL YEAR TO MONTH` | `{"$intervalYearMonth": "P1Y2M"}` |
+//! | `RAW` | `{"$rawhex": "0123abcd"}` |
+//! | JSON ID | `{"$rawid": "0123abcd"}` |
+//! | `VECTOR` | `{"$vectorImage": "0123abcd"}` |
+//! | `NUMBER` other than integers | `{"$numberDecimal": "123.45"}` |
+//! | `BINARY_FLOAT` | `{"$numberFloat": 1.5}` |
+//! | `BINARY_DOUBLE` | `{"$numberDouble": "NaN"}` |
+//!
+//! Integer `NUMBER`s are serialized as `i64` or `u64`.
+//! [`Timestamp`] is serialized as `$oracleTimestampTZ` when its time zone
+//! offset isn't zero.
+use crate::types::json::extended::{self, ExtendedValue};
+use crate::types::json::node_tree::{NodeTree, NodeValue};
+use crate::types::json::{array, object, DateTime, Map, NativeValue, Node, Number, Value};
+use crate::types::{IntervalDS, IntervalYM, OracleType, Timestamp};
+use crate::{Error, Result};
+use ::serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use ::serde::ser::{
+    self, Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+use ::serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::result;
+use std::str;
+
+impl Serialize for ExtendedValue {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ExtendedValue::String(value) => serializer.serialize_str(value),
+            ExtendedValue::Number(value) => serializer.serialize_f64(*value),
+        }
+    }
+}
+
+fn serialize_extended<S>(
+    serializer: S,
+    key: &str,
+    value: &ExtendedValue,
+) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(key, value)?;
+    map.end()
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Number(Number::String(value)) => {
+                if let Ok(value) = value.parse::<i64>() {
+                    serializer.serialize_i64(value)
+                } else if let Ok(value) = value.parse::<u64>() {
+                    serializer.serialize_u64(value)
+                } else {
+                    let value = ExtendedValue::String(value.clone());
+                    serialize_extended(serializer, extended::NUMBER_DECIMAL, &value)
+                }
+            }
+            Value::Number(Number::Double(value)) => serializer.serialize_f64(*value),
+            Value::Number(Number::Float(value)) => {
+                let value = ExtendedValue::Number((*value).into());
+                serialize_extended(serializer, extended::NUMBER_FLOAT, &value)
+            }
+            Value::String(value) => serializer.serialize_str(value),
+            Value::Array(values) => serializer.collect_seq(values),
+            Value::Object(map) => serializer.collect_map(map),
+            value => {
+                let (key, value) = extended::encode(value)
+                    .ok_or_else(|| ser::Error::custom(format!("unsupported value {:?}", value)))?;
+                serialize_extended(serializer, key, &value)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> result::Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> result::Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> result::Result<Value, E> {
+        Ok(Value::Number(Number::String(v.to_string())))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> result::Result<Value, E> {
+        Ok(Value::Number(Number::String(v.to_string())))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> result::Result<Value, E> {
+        Ok(Value::Number(Number::String(v.to_string())))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> result::Result<Value, E> {
+        Ok(Value::Number(Number::String(v.to_string())))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> result::Result<Value, E> {
+        Ok(Value::Number(Number::Double(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> result::Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> result::Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> result::Result<Value, E> {
+        Ok(Value::Raw(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> result::Result<Value, E> {
+        Ok(Value::Raw(v))
+    }
+
+    fn visit_none<E>(self) -> result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> result::Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> result::Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> result::Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> result::Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut object = Map::new();
+        if let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value::<Value>()?;
+            if let Some(extended_key) = extended::key(&key) {
+                match map.next_key::<String>()? {
+                    None => {
+                        if let Some(extended_value) = extended::from_value(&value) {
+                            return extended::decode(extended_key, extended_value)
+                                .map_err(de::Error::custom);
+                        }
+                        object.insert(key, value);
+                        return Ok(Value::Object(object));
+                    }
+                    Some(next_key) => {
+                        object.insert(key, value);
+                        object.insert(next_key, map.next_value()?);
+                    }
+                }
+            } else {
+                object.insert(key, value);
+            }
+            while let Some((key, value)) = map.next_entry()? {
+                object.insert(key, value);
+            }
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+macro_rules! serde_impl {
+    ($t:ty, $expecting:literal, $value:ident => $to_value:expr, $($pat:pat => $from_value:expr,)*) => {
+        impl Serialize for $t {
+            fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let $value = *self;
+                let value = $to_value;
+                let (key, value) = extended::encode(&value)
+                    .ok_or_else(|| ser::Error::custom(format!("unsupported value {:?}", value)))?;
+                serialize_extended(serializer, key, &value)
+            }
+        }
+
+        /// Deserialized from an extended JSON object or a string in it.
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D>(deserializer: D) -> result::Result<$t, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                match Value::deserialize(deserializer)? {
+                    $($pat => $from_value,)*
+                    value => Err(de::Error::custom(format!(
+                        concat!("expected ", $expecting, " but got {:?}"),
+                        value
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+serde_impl!(
+    Timestamp, "timestamp",
+    value => if value.tz_hour_offset != 0 || value.tz_minute_offset != 0 {
+        Value::TimestampTZ(value)
+    } else {
+        Value::Timestamp(DateTime::Timestamp(value))
+    },
+    Value::String(s) => extended::parse_timestamp(&s).map_err(de::Error::custom),
+    Value::Date(DateTime::Timestamp(value))
+    | Value::Timestamp(DateTime::Timestamp(value))
+    | Value::TimestampTZ(value) => Ok(value),
+);
+
+serde_impl!(
+    IntervalDS, "interval day to second",
+    value => Value::IntervalDS(value),
+    Value::String(s) => extended::parse_interval_ds(&s).map_err(de::Error::custom),
+    Value::IntervalDS(value) => Ok(value),
+);
+
+serde_impl!(
+    IntervalYM, "interval year to month",
+    value => Value::IntervalYM(value),
+    Value::String(s) => extended::parse_interval_ym(&s).map_err(de::Error::custom),
+    Value::IntervalYM(value) => Ok(value),
+);
+
+//
+// Serializer building a tree of `dpiJsonNode`
+//
+
+pub(crate) struct NodeSerializer<'t> {
+    tree: &'t mut NodeTree,
+}
+
+impl NodeSerializer<'_> {
+    pub(crate) fn new(tree: &mut NodeTree) -> NodeSerializer<'_> {
+        NodeSerializer { tree }
+    }
+
+    fn number<T: ToString>(self, value: T) -> Result<NodeValue> {
+        self.tree
+            .bytes(OracleType::Number, value.to_string().into_bytes())
+    }
+}
+
+fn wrap_variant(
+    tree: &mut NodeTree,
+    variant: Option<&'static str>,
+    value: NodeValue,
+) -> Result<NodeValue> {
+    match variant {
+        Some(variant) => tree.object(vec![(variant.to_string(), value)]),
+        None => Ok(value),
+    }
+}
+
+impl<'t> Serializer for NodeSerializer<'t> {
+    type Ok = NodeValue;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer<'t>;
+    type SerializeTuple = SeqSerializer<'t>;
+    type SerializeTupleStruct = SeqSerializer<'t>;
+    type SerializeTupleVariant = SeqSerializer<'t>;
+    type SerializeMap = MapSerializer<'t>;
+    type SerializeStruct = MapSerializer<'t>;
+    type SerializeStructVariant = MapSerializer<'t>;
+
+    fn serialize_bool(self, v: bool) -> Result<NodeValue> {
+        Ok(NodeValue::boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<NodeValue> {
+        self.number(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<NodeValue> {
+        Ok(NodeValue::float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<NodeValue> {
+        Ok(NodeValue::double(OracleType::Number, v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<NodeValue> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<NodeValue> {
+        self.tree.bytes(OracleType::Varchar, v.as_bytes().to_vec())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<NodeValue> {
+        self.tree.bytes(OracleType::Raw, v.to_vec())
+    }
+
+    fn serialize_none(self) -> Result<NodeValue> {
+        Ok(NodeValue::null())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<NodeValue>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<NodeValue> {
+        Ok(NodeValue::null())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<NodeValue> {
+        Ok(NodeValue::null())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<NodeValue> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<NodeValue>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<NodeValue>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = value.serialize(NodeSerializer::new(self.tree))?;
+        wrap_variant(self.tree, Some(variant), value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer<'t>> {
+        Ok(SeqSerializer {
+            tree: self.tree,
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer<'t>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer<'t>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer<'t>> {
+        Ok(SeqSerializer {
+            tree: self.tree,
+            elements: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer<'t>> {
+        Ok(MapSerializer {
+            tree: self.tree,
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            extended: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer<'t>> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer<'t>> {
+        Ok(MapSerializer {
+            tree: self.tree,
+            fields: Vec::with_capacity(len),
+            key: None,
+            extended: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+pub(crate) struct SeqSerializer<'t> {
+    tree: &'t mut NodeTree,
+    elements: Vec<NodeValue>,
+    variant: Option<&'static str>,
+}
+
+impl SeqSerializer<'_> {
+    fn push<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = value.serialize(NodeSerializer::new(self.tree))?;
+        self.elements.push(value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<NodeValue> {
+        let array = self.tree.array(self.elements)?;
+        wrap_variant(self.tree, self.variant, array)
+    }
+}
+
+macro_rules! seq_serializer_impl {
+    ($($trait:ident::$method:ident,)*) => {$(
+        impl $trait for SeqSerializer<'_> {
+            type Ok = NodeValue;
+            type Error = Error;
+
+            fn $method<T>(&mut self, value: &T) -> Result<()>
+            where
+                T: Serialize + ?Sized,
+            {
+                self.push(value)
+            }
+
+            fn end(self) -> Result<NodeValue> {
+                self.finish()
+            }
+        }
+    )*};
+}
+
+seq_serializer_impl! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+pub(crate) struct MapSerializer<'t> {
+    tree: &'t mut NodeTree,
+    fields: Vec<(String, NodeValue)>,
+    key: Option<String>,
+    // the first field whose key is an extended JSON key and whose value is a scalar
+    extended: Option<(&'static str, ExtendedValue)>,
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer<'_> {
+    fn field<T>(&mut self, key: String, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.fields.is_empty() && self.extended.is_none() {
+            if let Some(extended_key) = extended::key(&key) {
+                if let Ok(value) = value.serialize(ExtendedValueSerializer) {
+                    self.extended = Some((extended_key, value));
+                    return Ok(());
+                }
+            }
+        }
+        self.flush_extended()?;
+        let value = value.serialize(NodeSerializer::new(self.tree))?;
+        self.fields.push((key, value));
+        Ok(())
+    }
+
+    // Adds the field kept in `extended` as a usual field.
+    fn flush_extended(&mut self) -> Result<()> {
+        if let Some((key, value)) = self.extended.take() {
+            let value = match value {
+                ExtendedValue::String(value) => {
+                    self.tree.bytes(OracleType::Varchar, value.into_bytes())?
+                }
+                ExtendedValue::Number(value) => NodeValue::double(OracleType::Number, value),
+            };
+            self.fields.push((key.to_string(), value));
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<NodeValue> {
+        let value = match self.extended {
+            // an extended JSON object with a single field
+            Some((key, value)) => self.tree.value(&extended::decode(key, value)?)?,
+            None => self.tree.object(self.fields)?,
+        };
+        wrap_variant(self.tree, self.variant, value)
+    }
+}
+
+impl SerializeMap for MapSerializer<'_> {
+    type Ok = NodeValue;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::other("serialize_value called before serialize_key"))?;
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<NodeValue> {
+        self.finish()
+    }
+}
+
+macro_rules! struct_serializer_impl {
+    ($($trait:ident,)*) => {$(
+        impl $trait for MapSerializer<'_> {
+            type Ok = NodeValue;
+            type Error = Error;
+
+            fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+            where
+                T: Serialize + ?Sized,
+            {
+                self.field(key.to_string(), value)
+            }
+
+            fn end(self) -> Result<NodeValue> {
+                self.finish()
+            }
+        }
+    )*};
+}
+
+struct_serializer_impl! {
+    SerializeStruct,
+    SerializeStructVariant,
+}
+
+// Implements serializer methods returning an error.
+macro_rules! unsupported_impl {
+    ($error:expr; $($method:ident($($arg:ty),*);)*) => {$(
+        fn $method(self, $(_: $arg),*) -> Result<Self::Ok> {
+            Err($error)
+        }
+    )*};
+}
+
+/// Serializer accepting only strings and numbers to find values of extended JSON objects
+struct ExtendedValueSerializer;
+
+impl Serializer for ExtendedValueSerializer {
+    type Ok = ExtendedValue;
+    type Error = Error;
+    type SerializeSeq = Impossible<ExtendedValue, Error>;
+    type SerializeTuple = Impossible<ExtendedValue, Error>;
+    type SerializeTupleStruct = Impossible<ExtendedValue, Error>;
+    type SerializeTupleVariant = Impossible<ExtendedValue, Error>;
+    type SerializeMap = Impossible<ExtendedValue, Error>;
+    type SerializeStruct = Impossible<ExtendedValue, Error>;
+    type SerializeStructVariant = Impossible<ExtendedValue, Error>;
+
+    fn serialize_i64(self, v: i64) -> Result<ExtendedValue> {
+        Ok(ExtendedValue::Number(v as f64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<ExtendedValue> {
+        Ok(ExtendedValue::Number(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<ExtendedValue> {
+        Ok(ExtendedValue::Number(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<ExtendedValue> {
+        Ok(ExtendedValue::String(v.to_string()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<ExtendedValue> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<ExtendedValue> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<ExtendedValue> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<ExtendedValue> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<ExtendedValue> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<ExtendedValue> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<ExtendedValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<ExtendedValue> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<ExtendedValue>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(not_extended_value())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<ExtendedValue>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(not_extended_value())
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<ExtendedValue>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(not_extended_value())
+    }
+
+    unsupported_impl! {
+        not_extended_value();
+        serialize_bool(bool);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(not_extended_value())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(not_extended_value())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(not_extended_value())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(not_extended_value())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(not_extended_value())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(not_extended_value())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(not_extended_value())
+    }
+}
+
+fn not_extended_value() -> Error {
+    Error::other("not a value of an extended JSON object")
+}
+
+/// Serializer of object keys
+struct KeySerializer;
+
+impl Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<String>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(key_must_be_string())
+    }
+
+    unsupported_impl! {
+        key_must_be_string();
+        serialize_bool(bool);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_must_be_string())
+    }
+}
+
+fn key_must_be_string() -> Error {
+    Error::other("key must be a string")
+}
+
+//
+// Deserializer reading a tree of `dpiJsonNode`
+//
+
+pub(crate) struct NodeDeserializer<'a> {
+    node: &'a Node<'a>,
+}
+
+impl<'a> NodeDeserializer<'a> {
+    pub(crate) fn new(node: &'a Node<'a>) -> NodeDeserializer<'a> {
+        NodeDeserializer { node }
+    }
+
+    // Visits the node as an extended JSON object.
+    fn visit_extended<'de, V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = self.node.to_owned()?;
+        let (key, value) = extended::encode(&value)
+            .ok_or_else(|| Error::other(format!("unexpected JSON value {:?}", value)))?;
+        visitor.visit_map(ExtendedMapAccess {
+            key: Some(key),
+            value: Some(value),
+        })
+    }
+}
+
+fn is_text(oracle_type: OracleType) -> bool {
+    !matches!(
+        oracle_type,
+        OracleType::Raw | OracleType::JsonId | OracleType::Vector
+    )
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let oracle_type = self.node.oracle_type()?;
+        match self.node.data()? {
+            NativeValue::Null => visitor.visit_unit(),
+            NativeValue::Boolean(value) => visitor.visit_bool(value),
+            NativeValue::Bytes(value) => match oracle_type {
+                OracleType::Number => {
+                    let value = str::from_utf8(value)?;
+                    if let Ok(value) = value.parse::<i64>() {
+                        visitor.visit_i64(value)
+                    } else if let Ok(value) = value.parse::<u64>() {
+                        visitor.visit_u64(value)
+                    } else {
+                        visitor.visit_f64(value.parse()?)
+                    }
+                }
+                OracleType::Raw | OracleType::JsonId | OracleType::Vector => {
+                    self.visit_extended(visitor)
+                }
+                _ => visitor.visit_str(str::from_utf8(value)?),
+            },
+            NativeValue::Float(value) => visitor.visit_f32(value),
+            NativeValue::Double(value)
+                if matches!(oracle_type, OracleType::Number | OracleType::NativeDouble) =>
+            {
+                visitor.visit_f64(value)
+            }
+            NativeValue::Array(array) => visitor.visit_seq(NodeSeqAccess { iter: array.iter() }),
+            NativeValue::Object(object) => visitor.visit_map(NodeMapAccess {
+                iter: object.iter(),
+                value: None,
+            }),
+            _ => self.visit_extended(visitor),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let oracle_type = self.node.oracle_type()?;
+        match self.node.data()? {
+            // NUMBER values are got as exact text.
+            NativeValue::Bytes(value) if is_text(oracle_type) => {
+                visitor.visit_str(str::from_utf8(value)?)
+            }
+            NativeValue::Timestamp(_) | NativeValue::IntervalDS(_) | NativeValue::IntervalYM(_) => {
+                match extended::encode(&self.node.to_owned()?) {
+                    Some((_, ExtendedValue::String(value))) => visitor.visit_string(value),
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.node.data()? {
+            NativeValue::Bytes(value) => visitor.visit_bytes(value),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match (self.node.oracle_type()?, self.node.data()?) {
+            // RAW values are deserialized to sequences such as `Vec<u8>`.
+            (OracleType::Raw, NativeValue::Bytes(value)) => visitor.visit_seq(
+                de::value::SeqDeserializer::<_, Error>::new(value.iter().copied()),
+            ),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.node.data()? {
+            NativeValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let oracle_type = self.node.oracle_type()?;
+        match self.node.data()? {
+            NativeValue::Bytes(value) if is_text(oracle_type) => {
+                visitor.visit_enum(str::from_utf8(value)?.into_deserializer())
+            }
+            NativeValue::Object(object) if object.len() == 1 => {
+                let (variant, node) = object
+                    .iter()
+                    .next()
+                    .ok_or_else(|| Error::other("invalid UTF-8 key"))?;
+                visitor.visit_enum(NodeEnumAccess { variant, node })
+            }
+            _ => Err(Error::other(
+                "expected a string or an object with a single field for enum",
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char unit
+        unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct NodeSeqAccess<'a> {
+    iter: array::Iter<'a>,
+}
+
+impl<'de> SeqAccess<'de> for NodeSeqAccess<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.iter
+            .next()
+            .map(|node| seed.deserialize(NodeDeserializer::new(node)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.iter.size_hint().1
+    }
+}
+
+struct NodeMapAccess<'a> {
+    iter: object::Iter<'a>,
+    value: Option<&'a Node<'a>>,
+}
+
+impl<'de> MapAccess<'de> for NodeMapAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let node = self
+            .value
+            .take()
+            .ok_or_else(|| Error::other("next_value called before next_key"))?;
+        seed.deserialize(NodeDeserializer::new(node))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.iter.size_hint().1
+    }
+}
+
+struct ExtendedMapAccess {
+    key: Option<&'static str>,
+    value: Option<ExtendedValue>,
+}
+
+impl<'de> MapAccess<'de> for ExtendedMapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.key
+            .take()
+            .map(|key| seed.deserialize(key.into_deserializer()))
+            .transpose()
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(ExtendedValue::String(value)) => seed.deserialize(value.into_deserializer()),
+            Some(ExtendedValue::Number(value)) => seed.deserialize(value.into_deserializer()),
+            None => Err(Error::other("next_value called before next_key")),
+        }
+    }
+}
+
+struct NodeEnumAccess<'a> {
+    variant: &'a str,
+    node: &'a Node<'a>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for NodeEnumAccess<'a> {
+    type Error = Error;
+    type Variant = NodeDeserializer<'a>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, NodeDeserializer<'a>)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let deserializer: de::value::StrDeserializer<Error> = self.variant.into_deserializer();
+        let variant = seed.deserialize(deserializer)?;
+        Ok((variant, NodeDeserializer::new(self.node)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for NodeDeserializer<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::IgnoredAny::deserialize(self)?;
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::json::node_tree::NodeTree;
+    use ::serde::de::DeserializeOwned;
+    use std::collections::HashMap;
+
+    fn round_trip<T>(value: &T) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut tree = NodeTree::new();
+        let top = value.serialize(NodeSerializer::new(&mut tree))?;
+        tree.with_node(top, |node| T::deserialize(NodeDeserializer::new(node)))
+    }
+
+    const TS: Timestamp = Timestamp {
+        year: 2024,
+        month: 1,
+        day: 23,
+        hour: 4,
+        minute: 56,
+        second: 7,
+        fsecond: 123456000,
+        tz_hour_offset: 0,
+        tz_minute_offset: 0,
+    };
+
+    #[test]
+    fn scalars() -> Result<()> {
+        let value = (
+            -12i64,
+            u64::MAX,
+            1.5f64,
+            "abc".to_string(),
+            true,
+            None::<i32>,
+            Some(7u8),
+        );
+        assert_eq!(round_trip(&value)?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn collections() -> Result<()> {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), vec![1, 2]);
+        map.insert("b".to_string(), vec![]);
+        assert_eq!(round_trip(&map)?, map);
+        Ok(())
+    }
+
+    #[test]
+    fn oracle_types() -> Result<()> {
+        let value = (
+            TS,
+            Timestamp {
+                tz_hour_offset: -9,
+                tz_minute_offset: -30,
+                ..TS
+            },
+            IntervalDS {
+                days: 1,
+                hours: 2,
+                minutes: 3,
+                seconds: 4,
+                fseconds: 5,
+            },
+            IntervalYM {
+                years: -1,
+                months: -2,
+            },
+        );
+        assert_eq!(round_trip(&value)?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn value() -> Result<()> {
+        let mut map = Map::new();
+        for (key, value) in [
+            ("null", Value::Null),
+            ("bool", Value::Bool(false)),
+            ("int", Value::Number(Number::String("-12".into()))),
+            ("string", Value::String("abc".into())),
+            ("raw", Value::Raw(vec![0, 0xff])),
+            ("id", Value::JsonId(vec![0x12])),
+            ("vector", Value::Vector(vec![1, 2, 3])),
+            (
+                "date",
+                Value::Date(DateTime::Timestamp(Timestamp { fsecond: 0, ..TS })),
+            ),
+            ("ts", Value::Timestamp(DateTime::Timestamp(TS))),
+            ("tz", Value::TimestampTZ(TS)),
+            (
+                "array",
+                Value::Array(vec![Value::Array(vec![]), Value::Object(Map::new())]),
+            ),
+        ] {
+            map.insert(key.to_string(), value);
+        }
+        let value = Value::Object(map);
+        assert_eq!(round_trip(&value)?, value);
+        Ok(())
+    }
+}
@@ -0,0 +1,564 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::types::json::extended::{self, ExtendedValue};
+use crate::types::json::{Map, Number, Value};
+use crate::{Error, Result};
+use std::fmt::Write;
+
+// Maximum nesting depth of arrays and objects parsed by `Value::from_json_str`
+const MAX_DEPTH: usize = 1000;
+
+impl Value {
+    /// Returns compact JSON text of the value.
+    ///
+    /// Oracle scalar types not in the JSON data model are written as
+    /// extended JSON objects, which are objects with a single field as
+    /// follows. [`Value::from_json_str`] reads them back to the same variants.
+    ///
+    /// | Key | Value | Variant |
+    /// |---|---|---|
+    /// | `$oracleDate` | `"2024-01-23T04:56:07"` or milliseconds since epoch | [`Value::Date`] |
+    /// | `$oracleTimestamp` | `"2024-01-23T04:56:07.123456"` or milliseconds since epoch | [`Value::Timestamp`] |
+    /// | `$oracleTimestampTZ` | `"2024-01-23T04:56:07.123456+09:00"` | [`Value::TimestampTZ`] |
+    /// | `$intervalDaySecond` | `"P1DT2H3M4.5S"` | [`Value::IntervalDS`] |
+    /// | `$intervalYearMonth` | `"P1Y2M"` | [`Value::IntervalYM`] |
+    /// | `$rawhex` | `"0123abcd"` | [`Value::Raw`] |
+    /// | `$rawid` | `"0123abcd"` | [`Value::JsonId`] |
+    /// | `$vectorImage` | `"0123abcd"` | `Value::Vector` |
+    /// | `$numberDecimal` | `"123.45"` | [`Number::String`] |
+    /// | `$numberFloat` | `1.5` | [`Number::Float`] |
+    /// | `$numberDouble` | `"1.5"`, `"NaN"`, `"Infinity"` or `"-Infinity"` | [`Number::Double`] |
+    ///
+    /// [`Number::String`] is written as it is when it is a valid JSON number.
+    /// Object fields are sorted by keys to make the output stable.
+    pub fn to_json_string(&self) -> String {
+        let mut s = String::new();
+        Writer {
+            out: &mut s,
+            indent: None,
+        }
+        .value(self, 0);
+        s
+    }
+
+    /// Returns pretty-printed JSON text of the value indented by two spaces.
+    ///
+    /// See [`Value::to_json_string`] for details.
+    pub fn to_json_string_pretty(&self) -> String {
+        let mut s = String::new();
+        Writer {
+            out: &mut s,
+            indent: Some("  "),
+        }
+        .value(self, 0);
+        s
+    }
+
+    /// Parses JSON text written by [`Value::to_json_string`] or
+    /// [`Value::to_json_string_pretty`].
+    ///
+    /// Numbers are read as [`Number::String`] without loss of precision.
+    /// Objects with a single field whose key is one of the extended JSON
+    /// keys are read as Oracle scalar types.
+    pub fn from_json_str(s: &str) -> Result<Value> {
+        let mut parser = Parser { s, pos: 0 };
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        if parser.pos != s.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+struct Writer<'a> {
+    out: &'a mut String,
+    indent: Option<&'static str>,
+}
+
+impl Writer<'_> {
+    fn newline(&mut self, depth: usize) {
+        if let Some(indent) = self.indent {
+            self.out.push('\n');
+            for _ in 0..depth {
+                self.out.push_str(indent);
+            }
+        }
+    }
+
+    fn value(&mut self, value: &Value, depth: usize) {
+        match value {
+            Value::Null => self.out.push_str("null"),
+            Value::Bool(value) => write!(self.out, "{}", value).unwrap(),
+            Value::Number(Number::String(value)) => {
+                if is_json_number(value) {
+                    self.out.push_str(value);
+                } else {
+                    self.extended(
+                        extended::NUMBER_DECIMAL,
+                        &ExtendedValue::String(value.clone()),
+                        depth,
+                    );
+                }
+            }
+            Value::Number(Number::Double(value)) => self.extended(
+                extended::NUMBER_DOUBLE,
+                &ExtendedValue::String(double_to_string(*value)),
+                depth,
+            ),
+            Value::Number(Number::Float(value)) => self.extended(
+                extended::NUMBER_FLOAT,
+                &ExtendedValue::Number((*value).into()),
+                depth,
+            ),
+            Value::String(value) => self.string(value),
+            Value::Array(values) => {
+                self.out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(',');
+                    }
+                    self.newline(depth + 1);
+                    self.value(value, depth + 1);
+                }
+                if !values.is_empty() {
+                    self.newline(depth);
+                }
+                self.out.push(']');
+            }
+            Value::Object(map) => {
+                let mut fields = map.iter().collect::<Vec<_>>();
+                fields.sort_by(|a, b| a.0.cmp(b.0));
+                self.out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(',');
+                    }
+                    self.newline(depth + 1);
+                    self.key(key);
+                    self.value(value, depth + 1);
+                }
+                if !fields.is_empty() {
+                    self.newline(depth);
+                }
+                self.out.push('}');
+            }
+            value => {
+                // encode() returns Some for all other variants.
+                if let Some((key, value)) = extended::encode(value) {
+                    self.extended(key, &value, depth);
+                }
+            }
+        }
+    }
+
+    fn number(&mut self, value: f64, depth: usize) {
+        if value.is_finite() {
+            write!(self.out, "{}", value).unwrap();
+        } else {
+            self.extended(
+                extended::NUMBER_DOUBLE,
+                &ExtendedValue::String(double_to_string(value)),
+                depth,
+            );
+        }
+    }
+
+    fn key(&mut self, key: &str) {
+        self.string(key);
+        self.out.push(':');
+        if self.indent.is_some() {
+            self.out.push(' ');
+        }
+    }
+
+    fn extended(&mut self, key: &str, value: &ExtendedValue, depth: usize) {
+        self.out.push('{');
+        self.newline(depth + 1);
+        self.key(key);
+        match value {
+            ExtendedValue::String(value) => self.string(value),
+            ExtendedValue::Number(value) => self.number(*value, depth + 1),
+        }
+        self.newline(depth);
+        self.out.push('}');
+    }
+
+    fn string(&mut self, value: &str) {
+        self.out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{8}' => self.out.push_str("\\b"),
+                '\u{c}' => self.out.push_str("\\f"),
+                c if c < ' ' => write!(self.out, "\\u{:04x}", c as u32).unwrap(),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+}
+
+/// Returns `value` as the string of a `$numberDouble` object.
+fn double_to_string(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.into()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Returns true when `s` matches the number grammar of JSON.
+fn is_json_number(s: &str) -> bool {
+    let mut parser = Parser { s, pos: 0 };
+    parser.number_len() == Some(s.len())
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        Error::other(format!("{} at position {} in JSON text", message, self.pos))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c as char)))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        self.skip_whitespace();
+        let rest = &self.s[self.pos..];
+        for (literal, value) in [
+            ("null", Value::Null),
+            ("true", Value::Bool(true)),
+            ("false", Value::Bool(false)),
+        ] {
+            if rest.starts_with(literal) {
+                self.pos += literal.len();
+                return Ok(value);
+            }
+        }
+        match self.peek() {
+            Some(b'"') => Ok(Value::String(self.string()?)),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        values.push(self.value(depth + 1)?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Value::Array(values))
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut map = Map::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(b':')?;
+                        let value = self.value(depth + 1)?;
+                        map.insert(key, value);
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                if map.len() == 1 {
+                    let (key, value) = map.iter().next().unwrap();
+                    if let Some(key) = extended::key(key) {
+                        if let Some(value) = extended::from_value(value) {
+                            return extended::decode(key, value);
+                        }
+                    }
+                }
+                Ok(Value::Object(map))
+            }
+            _ => match self.number_len() {
+                Some(len) => {
+                    let number = self.s[self.pos..self.pos + len].to_string();
+                    self.pos += len;
+                    Ok(Value::Number(Number::String(number)))
+                }
+                None => Err(self.error("expected a JSON value")),
+            },
+        }
+    }
+
+    /// Returns the length of a number at the current position.
+    fn number_len(&mut self) -> Option<usize> {
+        let bytes = &self.s.as_bytes()[self.pos..];
+        let digits = |start: usize| {
+            bytes[start..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count()
+        };
+        let mut len = usize::from(bytes.first() == Some(&b'-'));
+        match bytes.get(len) {
+            Some(b'0') => len += 1,
+            Some(b'1'..=b'9') => len += digits(len),
+            _ => return None,
+        }
+        if bytes.get(len) == Some(&b'.') {
+            let n = digits(len + 1);
+            if n == 0 {
+                return None;
+            }
+            len += 1 + n;
+        }
+        if matches!(bytes.get(len), Some(b'e' | b'E')) {
+            len += 1;
+            if matches!(bytes.get(len), Some(b'+' | b'-')) {
+                len += 1;
+            }
+            let n = digits(len);
+            if n == 0 {
+                return None;
+            }
+            len += n;
+        }
+        Some(len)
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let hex = self
+            .s
+            .get(self.pos..self.pos + 4)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(hex, 16).unwrap())
+    }
+
+    fn string(&mut self) -> Result<String> {
+        if self.peek() != Some(b'"') {
+            return Err(self.error("expected a string"));
+        }
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let rest = &self.s[self.pos..];
+            let len = rest
+                .find(|c: char| c == '"' || c == '\\' || c < ' ')
+                .ok_or_else(|| self.error("unterminated string"))?;
+            s.push_str(&rest[..len]);
+            self.pos += len;
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let c = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let mut code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.s[self.pos..].starts_with("\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(self.error("invalid surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            s.push(
+                                char::from_u32(code)
+                                    .ok_or_else(|| self.error("invalid unicode escape"))?,
+                            );
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.pos += 1;
+                    s.push(c);
+                }
+                _ => return Err(self.error("control character in string")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::json::DateTime;
+    use crate::types::{IntervalDS, IntervalYM, Timestamp};
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let ts = Timestamp {
+            year: 2024,
+            month: 1,
+            day: 23,
+            hour: 4,
+            minute: 56,
+            second: 7,
+            fsecond: 123456000,
+            tz_hour_offset: -9,
+            tz_minute_offset: -30,
+        };
+        let mut map = Map::new();
+        for (key, value) in [
+            ("null", Value::Null),
+            ("bool", Value::Bool(false)),
+            ("int", Value::Number(Number::String("-12".into()))),
+            ("dec", Value::Number(Number::String("1.5E+40".into()))),
+            ("text", Value::Number(Number::String(".5".into()))),
+            ("float", Value::Number(Number::Float(0.25))),
+            ("inf", Value::Number(Number::Double(f64::NEG_INFINITY))),
+            ("double", Value::Number(Number::Double(0.1))),
+            ("string", Value::String("a\"\\\n\u{1}\u{1f600}".into())),
+            ("raw", Value::Raw(vec![0, 0xff])),
+            ("id", Value::JsonId(vec![0x12])),
+            ("vector", Value::Vector(vec![1, 2, 3])),
+            ("date", Value::Date(DateTime::Double(1e12))),
+            ("ts", Value::Timestamp(DateTime::Timestamp(ts))),
+            ("tz", Value::TimestampTZ(ts)),
+            (
+                "ds",
+                Value::IntervalDS(IntervalDS {
+                    days: 1,
+                    hours: 2,
+                    minutes: 3,
+                    seconds: 4,
+                    fseconds: 5,
+                }),
+            ),
+            (
+                "ym",
+                Value::IntervalYM(IntervalYM {
+                    years: -1,
+                    months: -2,
+                }),
+            ),
+            (
+                "array",
+                Value::Array(vec![Value::Array(vec![]), Value::Object(Map::new())]),
+            ),
+        ] {
+            map.insert(key.to_string(), value);
+        }
+        let value = Value::Object(map);
+        assert_eq!(Value::from_json_str(&value.to_json_string())?, value);
+        assert_eq!(Value::from_json_str(&value.to_json_string_pretty())?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn format() -> Result<()> {
+        let value = Value::from_json_str(
+            r#" { "b" : [ 1 , 2.50 , {"$rawhex":"0a"} ] , "a" : { } , "c": [] } "#,
+        )?;
+        assert_eq!(
+            value.to_json_string(),
+            r#"{"a":{},"b":[1,2.50,{"$rawhex":"0a"}],"c":[]}"#
+        );
+        assert_eq!(
+            value.to_json_string_pretty(),
+            r#"{
+  "a": {},
+  "b": [
+    1,
+    2.50,
+    {
+      "$rawhex": "0a"
+    }
+  ],
+  "c": []
+}"#
+        );
+        assert_eq!(
+            Value::Number(Number::Double(2.0)).to_json_string(),
+            r#"{"$numberDouble":"2"}"#
+        );
+        assert_eq!(
+            Value::Number(Number::Double(f64::NAN)).to_json_string(),
+            r#"{"$numberDouble":"NaN"}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        for s in [
+            "",
+            "01",
+            "1.",
+            "-",
+            "[1,]",
+            "{\"a\"}",
+            "\"\\x\"",
+            "\"a",
+            "[1] 2",
+            "{\"$rawhex\":\"0\"}",
+        ] {
+            assert!(Value::from_json_str(s).is_err(), "{}", s);
+        }
+    }
+}
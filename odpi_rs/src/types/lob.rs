@@ -20,6 +20,13 @@ use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
 use std::ffi::c_char;
 
+mod buffered;
+mod clob;
+mod stream;
+
+pub use clob::{ClobLines, ClobReader, ClobWriter};
+pub use stream::LobStream;
+
 #[derive(Debug)]
 #[odpic_doc]
 pub struct Lob {
@@ -148,6 +155,37 @@ impl Lob {
         Ok(len as usize)
     }
 
+    /// Returns a reader of CLOB or NCLOB text
+    ///
+    /// See [`ClobReader`].
+    #[maybe_async]
+    pub async fn clob_reader(&self) -> Result<ClobReader> {
+        ClobReader::new(self.clone(), self.chunk_size().await?)
+    }
+
+    /// Returns a writer of CLOB or NCLOB text
+    ///
+    /// When `append` is true, text is appended to the current content.
+    /// Otherwise, the content is replaced by written text. See [`ClobWriter`].
+    #[maybe_async]
+    pub async fn clob_writer(&self, append: bool) -> Result<ClobWriter> {
+        let chunk_size = self.chunk_size().await?;
+        let size = if append {
+            Some(self.size().await?)
+        } else {
+            None
+        };
+        ClobWriter::new(self.clone(), chunk_size, size)
+    }
+
+    /// Returns a buffered stream to read and write the LOB
+    ///
+    /// See [`LobStream`].
+    #[maybe_async]
+    pub async fn stream(&self) -> Result<LobStream> {
+        LobStream::new(self.clone(), self.chunk_size().await?)
+    }
+
     pub fn set_directory_and_file_name<D, F>(&self, directory_alias: D, file_name: F) -> Result<()>
     where
         D: AsRef<str>,
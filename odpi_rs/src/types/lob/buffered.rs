@@ -0,0 +1,201 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::types::Lob;
+use crate::*;
+use odpic_sys::*;
+use std::cmp;
+#[cfg(feature = "is_async")]
+use std::future::{self, Future};
+#[cfg(feature = "is_async")]
+use std::pin::Pin;
+#[cfg(feature = "is_async")]
+use std::task::{ready, Context, Poll};
+
+/// Approximate number of bytes or characters read or written at a time
+///
+/// This is rounded up to a multiple of the LOB chunk size.
+const BUFFER_SIZE: u64 = 65536;
+
+#[cfg(feature = "is_async")]
+type TaskFuture<B> = Pin<Box<dyn Future<Output = Result<(B, Result<()>)>> + Send>>;
+
+/// Returns the number of bytes or characters read or written at a time
+pub(super) fn buffer_amount(chunk_size: u32) -> u64 {
+    let chunk_size = cmp::max(chunk_size as u64, 1);
+    (BUFFER_SIZE + chunk_size - 1) / chunk_size * chunk_size
+}
+
+/// LOB with a buffer operated by blocking functions
+///
+/// In async builds, the buffer is moved to a blocking task while it runs.
+pub(super) struct Buffered<B> {
+    lob: Lob,
+    // None while a blocking task owns it
+    buffer: Option<B>,
+    #[cfg(feature = "is_async")]
+    future: Option<TaskFuture<B>>,
+}
+
+impl<B> Buffered<B>
+where
+    B: Send + 'static,
+{
+    pub(super) fn new(lob: Lob, buffer: B) -> Buffered<B> {
+        Buffered {
+            lob,
+            buffer: Some(buffer),
+            #[cfg(feature = "is_async")]
+            future: None,
+        }
+    }
+
+    pub(super) fn lob(&self) -> &Lob {
+        &self.lob
+    }
+
+    pub(super) fn buffer(&self) -> Result<&B> {
+        self.buffer.as_ref().ok_or_else(broken)
+    }
+
+    pub(super) fn buffer_mut(&mut self) -> Result<&mut B> {
+        self.buffer.as_mut().ok_or_else(broken)
+    }
+
+    /// Calls `f` with the buffer and the LOB handle
+    #[cfg(not(feature = "is_async"))]
+    pub(super) fn run<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut B, *mut dpiLob) -> Result<()>,
+    {
+        let handle = self.lob.handle;
+        f(self.buffer_mut()?, handle)
+    }
+
+    /// Calls `f` with the buffer and the LOB handle in a blocking task
+    ///
+    /// The task started by [`Buffered::start`] is waited before that. This
+    /// is cancel safe. When the returned future is dropped, the task is waited
+    /// by the next call.
+    #[cfg(feature = "is_async")]
+    pub(super) async fn run<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut B, *mut dpiLob) -> Result<()> + Send + 'static,
+    {
+        future::poll_fn(|cx| self.poll_task(cx)).await?;
+        self.start(f);
+        future::poll_fn(|cx| self.poll_task(cx)).await
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(super) fn is_running(&self) -> bool {
+        self.future.is_some()
+    }
+
+    /// Starts a blocking task calling `f` with the buffer and the LOB handle
+    #[cfg(feature = "is_async")]
+    pub(super) fn start<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut B, *mut dpiLob) -> Result<()> + Send + 'static,
+    {
+        if let Some(mut buffer) = self.buffer.take() {
+            let lob = ForceSend(self.lob.clone());
+            self.future = Some(Box::pin(task::spawn_blocking(move || {
+                let lob = lob;
+                let result = f(&mut buffer, lob.handle);
+                Ok((buffer, result))
+            })));
+        }
+    }
+
+    /// Waits for the task started by [`Buffered::start`] if it exists
+    #[cfg(feature = "is_async")]
+    pub(super) fn poll_task(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if let Some(future) = self.future.as_mut() {
+            let result = ready!(future.as_mut().poll(cx));
+            self.future = None;
+            let (buffer, result) = result?;
+            self.buffer = Some(buffer);
+            result?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn broken() -> Error {
+    Error::other("LOB buffer is lost by a failed blocking task")
+}
+
+/// Returns the number of UTF-16 code units of UTF-8 `bytes`
+///
+/// An incomplete character at the end is counted as a complete one.
+pub(super) fn utf16_len(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0xBF => 0,
+            0xF0..=0xFF => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Returns the length of `bytes` without an incomplete UTF-8 character at the end
+pub(super) fn complete_len(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    for i in 1..=cmp::min(len, 4) {
+        let b = bytes[len - i];
+        let char_len = match b {
+            0x80..=0xBF => continue,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xFF => 4,
+            _ => 1,
+        };
+        return if i >= char_len { len } else { len - i };
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{buffer_amount, complete_len, utf16_len};
+
+    #[test]
+    fn utf16_len_of_utf8() {
+        assert_eq!(utf16_len(b"abc"), 3);
+        assert_eq!(utf16_len("日本語".as_bytes()), 3);
+        assert_eq!(utf16_len("a😀b".as_bytes()), 4);
+        assert_eq!(utf16_len(&"日本".as_bytes()[..4]), 2);
+    }
+
+    #[test]
+    fn complete_len_of_utf8() {
+        let bytes = "a日😀".as_bytes();
+        assert_eq!(complete_len(bytes), 8);
+        assert_eq!(complete_len(&bytes[..7]), 4);
+        assert_eq!(complete_len(&bytes[..5]), 4);
+        assert_eq!(complete_len(&bytes[..4]), 4);
+        assert_eq!(complete_len(&bytes[..3]), 1);
+        assert_eq!(complete_len(&bytes[..1]), 1);
+        assert_eq!(complete_len(b""), 0);
+    }
+
+    #[test]
+    fn buffer_amount_is_multiple_of_chunk_size() {
+        assert_eq!(buffer_amount(8132), 8132 * 9);
+        assert_eq!(buffer_amount(65536), 65536);
+        assert_eq!(buffer_amount(0), 65536);
+    }
+}
@@ -0,0 +1,492 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use super::buffered::{buffer_amount, complete_len, utf16_len, Buffered};
+use crate::maybe_async;
+use crate::types::{Lob, OracleType};
+use crate::*;
+use odpic_sys::blocking::dpiLob_readBytes;
+use odpic_sys::*;
+use std::ffi::c_char;
+use std::mem;
+#[cfg(feature = "is_async")]
+use std::pin::Pin;
+use std::str;
+#[cfg(feature = "is_async")]
+use std::task::{ready, Context, Poll};
+
+/// Reader of CLOB and NCLOB text
+///
+/// This is created by [`Lob::clob_reader`]. Text is read in the unit of
+/// a multiple of [`Lob::chunk_size`] characters and is never split in the
+/// middle of a character.
+///
+/// Offsets of [`Lob::read_bytes`] are counted in characters, where
+/// a character outside of the Basic Multilingual Plane is counted as two
+/// characters, while Rust strings are counted in UTF-8 bytes. This tracks
+/// both positions by [`ClobReader::char_pos`] and [`ClobReader::byte_pos`].
+///
+/// # Examples
+///
+/// ```ignore
+/// let lob: Lob = stmt.query_value(1)?;
+/// let mut lines = lob.clob_reader()?.lines();
+/// while let Some(line) = lines.next() {
+///     println!("{}", line?);
+/// }
+/// ```
+pub struct ClobReader {
+    inner: Buffered<TextBuffer>,
+}
+
+/// Text read at a time
+///
+/// `text` was read at `char_pos` in characters and `byte_pos` in bytes.
+/// `offset` bytes of them are consumed.
+struct TextBuffer {
+    // number of characters read at a time
+    amount: u64,
+    // number of bytes to hold `amount` characters
+    capacity: usize,
+    char_pos: u64,
+    byte_pos: u64,
+    text: String,
+    offset: usize,
+    // true when the last read reached the end of the LOB
+    eof: bool,
+}
+
+impl TextBuffer {
+    fn char_pos(&self) -> u64 {
+        self.char_pos + utf16_len(&self.text.as_bytes()[..self.offset])
+    }
+
+    fn byte_pos(&self) -> u64 {
+        self.byte_pos + self.offset as u64
+    }
+
+    fn available(&self) -> &str {
+        &self.text[self.offset..]
+    }
+
+    /// Appends all unread text to `buf` and returns the number of appended bytes
+    fn consume_all(&mut self, buf: &mut String) -> usize {
+        let text = self.available();
+        let len = text.len();
+        buf.push_str(text);
+        self.offset += len;
+        len
+    }
+
+    /// Appends unread text until a newline to `buf`
+    ///
+    /// This returns the number of appended bytes and whether a newline is found.
+    fn consume_line(&mut self, buf: &mut String) -> (usize, bool) {
+        let text = self.available();
+        let (len, found) = match text.find('\n') {
+            Some(idx) => (idx + 1, true),
+            None => (text.len(), false),
+        };
+        buf.push_str(&text[..len]);
+        self.offset += len;
+        (len, found)
+    }
+
+    /// Reads the next text after all read text is consumed
+    fn fill(&mut self, handle: *mut dpiLob) -> Result<()> {
+        self.char_pos = self.char_pos();
+        self.byte_pos = self.byte_pos();
+        self.offset = 0;
+        let mut data = mem::take(&mut self.text).into_bytes();
+        data.clear();
+        data.resize(self.capacity, 0);
+        let offset = self.char_pos + 1;
+        let mut len = read_text(handle, offset, self.amount, &mut data)?;
+        if self.amount > 1 && ends_with_split_surrogate(&data[..len], self.amount) {
+            // Read one character short to read the surrogate pair next time.
+            len = read_text(handle, offset, self.amount - 1, &mut data)?;
+        }
+        data.truncate(len);
+        self.text = String::from_utf8(data).map_err(|err| err.utf8_error())?;
+        self.eof = self.text.is_empty();
+        Ok(())
+    }
+}
+
+/// Reads `amount` characters at `offset` to `data` and returns the number of read bytes
+pub(super) fn read_text(
+    handle: *mut dpiLob,
+    offset: u64,
+    amount: u64,
+    data: &mut [u8],
+) -> Result<usize> {
+    let mut len = data.len() as u64;
+    call!(dpiLob_readBytes(
+        handle,
+        offset,
+        amount,
+        data.as_mut_ptr() as *mut c_char,
+        &mut len
+    ))?;
+    Ok(len as usize)
+}
+
+/// Returns `true` when `data` read by `amount` characters ends with a high
+/// surrogate whose low surrogate is beyond `amount`
+///
+/// The lone surrogate is converted to U+FFFD or to the invalid UTF-8 bytes
+/// encoding the surrogate.
+pub(super) fn ends_with_split_surrogate(data: &[u8], amount: u64) -> bool {
+    let head = match str::from_utf8(data) {
+        Ok(text) => match text.strip_suffix(char::REPLACEMENT_CHARACTER) {
+            Some(head) => head.as_bytes(),
+            None => return false,
+        },
+        Err(err) => match &data[err.valid_up_to()..] {
+            [0xED, 0xA0..=0xAF, 0x80..=0xBF] => &data[..err.valid_up_to()],
+            _ => return false,
+        },
+    };
+    utf16_len(head) + 1 == amount
+}
+
+impl ClobReader {
+    pub(crate) fn new(lob: Lob, chunk_size: u32) -> Result<ClobReader> {
+        check_clob(&lob)?;
+        let amount = buffer_amount(chunk_size);
+        let buffer = TextBuffer {
+            amount,
+            capacity: lob.buffer_size(amount)?.try_into()?,
+            char_pos: 0,
+            byte_pos: 0,
+            text: String::new(),
+            offset: 0,
+            eof: false,
+        };
+        Ok(ClobReader {
+            inner: Buffered::new(lob, buffer),
+        })
+    }
+
+    /// Returns the underlying LOB
+    pub fn lob(&self) -> &Lob {
+        self.inner.lob()
+    }
+
+    /// Returns the number of characters read so far
+    ///
+    /// This is the offset passed to [`Lob::read_bytes`] minus one to read
+    /// the next character.
+    pub fn char_pos(&self) -> Result<u64> {
+        Ok(self.inner.buffer()?.char_pos())
+    }
+
+    /// Returns the number of UTF-8 bytes read so far
+    pub fn byte_pos(&self) -> Result<u64> {
+        Ok(self.inner.buffer()?.byte_pos())
+    }
+
+    /// Reads text and appends it to `buf`
+    ///
+    /// This returns the number of appended bytes, which is zero at the end
+    /// of the LOB.
+    #[maybe_async]
+    pub async fn read(&mut self, buf: &mut String) -> Result<usize> {
+        if self.inner.buffer()?.available().is_empty() {
+            self.inner.run(TextBuffer::fill).await?;
+        }
+        Ok(self.inner.buffer_mut()?.consume_all(buf))
+    }
+
+    /// Reads text until a newline and appends it to `buf`
+    ///
+    /// The newline is included in the appended text. This returns the number
+    /// of appended bytes, which is zero at the end of the LOB.
+    #[maybe_async]
+    pub async fn read_line(&mut self, buf: &mut String) -> Result<usize> {
+        let mut len = 0;
+        loop {
+            let (n, found) = self.inner.buffer_mut()?.consume_line(buf);
+            len += n;
+            if found {
+                return Ok(len);
+            }
+            self.inner.run(TextBuffer::fill).await?;
+            if self.inner.buffer()?.eof {
+                return Ok(len);
+            }
+        }
+    }
+
+    /// Reads all text until the end of the LOB and appends it to `buf`
+    ///
+    /// This returns the number of appended bytes.
+    #[maybe_async]
+    pub async fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        let mut len = 0;
+        loop {
+            len += self.inner.buffer_mut()?.consume_all(buf);
+            self.inner.run(TextBuffer::fill).await?;
+            if self.inner.buffer()?.eof {
+                return Ok(len);
+            }
+        }
+    }
+
+    /// Returns lines of the text
+    ///
+    /// Each line doesn't include the newline, `"\n"` or `"\r\n"`.
+    pub fn lines(self) -> ClobLines {
+        ClobLines {
+            reader: self,
+            #[cfg(feature = "is_async")]
+            line: String::new(),
+        }
+    }
+}
+
+/// Lines of CLOB or NCLOB text
+///
+/// This is created by [`ClobReader::lines`]. It implements [`Iterator`]
+/// when none of async runtime features are enabled and [`Stream`] otherwise.
+///
+/// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+pub struct ClobLines {
+    reader: ClobReader,
+    // a line being read
+    #[cfg(feature = "is_async")]
+    line: String,
+}
+
+impl ClobLines {
+    /// Returns the underlying reader
+    pub fn into_inner(self) -> ClobReader {
+        self.reader
+    }
+}
+
+#[sync_impl]
+impl Iterator for ClobLines {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(trim_newline(line))),
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+// Fields are never pinned.
+#[async_impl(AFIT)]
+impl Unpin for ClobLines {}
+
+#[async_impl(AFIT)]
+impl futures_core::Stream for ClobLines {
+    type Item = Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<String>>> {
+        let this = self.get_mut();
+        loop {
+            if let Err(err) = ready!(this.reader.inner.poll_task(cx)) {
+                return Poll::Ready(Some(Err(err)));
+            }
+            let buffer = match this.reader.inner.buffer_mut() {
+                Ok(buffer) => buffer,
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            };
+            let (_, found) = buffer.consume_line(&mut this.line);
+            if found || (buffer.eof && !this.line.is_empty()) {
+                let line = mem::take(&mut this.line);
+                return Poll::Ready(Some(Ok(trim_newline(line))));
+            }
+            if buffer.eof {
+                return Poll::Ready(None);
+            }
+            this.reader.inner.start(TextBuffer::fill);
+        }
+    }
+}
+
+/// Writer of CLOB and NCLOB text
+///
+/// This is created by [`Lob::clob_writer`]. Text is buffered and written in
+/// the unit of about a multiple of [`Lob::chunk_size`] bytes. Only complete
+/// characters are written. An incomplete UTF-8 character at the end of
+/// written bytes is kept in the buffer until the rest are written.
+///
+/// The first write replaces the LOB content by [`Lob::set_from_bytes`]
+/// unless the writer is created in append mode. The following writes
+/// append text by [`Lob::write_bytes`].
+///
+/// Call [`ClobWriter::flush`] before dropping the writer. Otherwise buffered
+/// text is lost in async builds.
+///
+/// # Examples
+///
+/// ```ignore
+/// let lob = conn.new_temp_lob(OracleType::Clob)?;
+/// let mut writer = lob.clob_writer(false)?;
+/// for line in lines {
+///     writer.write_str(line)?;
+///     writer.write_str("\n")?;
+/// }
+/// writer.flush()?;
+/// ```
+pub struct ClobWriter {
+    lob: Lob,
+    // number of bytes written at a time
+    amount: usize,
+    data: Vec<u8>,
+    // position where `data` are written
+    char_pos: u64,
+    // number of bytes written before `data`
+    byte_pos: u64,
+    // true until the LOB content is replaced
+    replace: bool,
+}
+
+impl ClobWriter {
+    pub(crate) fn new(lob: Lob, chunk_size: u32, size: Option<u64>) -> Result<ClobWriter> {
+        check_clob(&lob)?;
+        Ok(ClobWriter {
+            lob,
+            amount: buffer_amount(chunk_size).try_into()?,
+            data: Vec::new(),
+            char_pos: size.unwrap_or(0),
+            byte_pos: 0,
+            replace: size.is_none(),
+        })
+    }
+
+    /// Returns the underlying LOB
+    pub fn lob(&self) -> &Lob {
+        &self.lob
+    }
+
+    /// Returns the number of characters in the LOB after buffered text is written
+    pub fn char_pos(&self) -> u64 {
+        self.char_pos + utf16_len(&self.data)
+    }
+
+    /// Returns the number of UTF-8 bytes written by this writer including buffered text
+    pub fn byte_pos(&self) -> u64 {
+        self.byte_pos + self.data.len() as u64
+    }
+
+    /// Writes text
+    #[maybe_async]
+    pub async fn write_str(&mut self, text: &str) -> Result<()> {
+        self.write_bytes(text.as_bytes()).await
+    }
+
+    /// Writes UTF-8 bytes
+    ///
+    /// `bytes` may end in the middle of a character. The rest must be
+    /// written by the next call.
+    #[maybe_async]
+    pub async fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.data.extend_from_slice(bytes);
+        if self.data.len() >= self.amount {
+            self.write_out().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes buffered text
+    ///
+    /// This fails when an incomplete character is left in the buffer.
+    #[maybe_async]
+    pub async fn flush(&mut self) -> Result<()> {
+        self.write_out().await?;
+        if !self.data.is_empty() {
+            return Err(Error::other(
+                "incomplete UTF-8 character at the end of CLOB data",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes complete characters in the buffer
+    #[maybe_async]
+    async fn write_out(&mut self) -> Result<()> {
+        let len = complete_len(&self.data);
+        let text = str::from_utf8(&self.data[..len])?;
+        if self.replace {
+            self.lob.set_from_bytes(text).await?;
+            self.replace = false;
+        } else if len > 0 {
+            self.lob.write_bytes(self.char_pos + 1, text).await?;
+        }
+        self.char_pos += utf16_len(text.as_bytes());
+        self.byte_pos += len as u64;
+        self.data.drain(..len);
+        Ok(())
+    }
+}
+
+#[sync_impl]
+impl Drop for ClobWriter {
+    fn drop(&mut self) {
+        if !self.data.is_empty() {
+            let _ = self.flush();
+        }
+    }
+}
+
+fn check_clob(lob: &Lob) -> Result<()> {
+    match lob.get_type()? {
+        OracleType::Clob | OracleType::Nclob => Ok(()),
+        lob_type => Err(Error::other(format!(
+            "{:?} isn't a CLOB or NCLOB",
+            lob_type
+        ))),
+    }
+}
+
+fn trim_newline(mut line: String) -> String {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ends_with_split_surrogate, trim_newline};
+
+    #[test]
+    fn trim_newline_of_line() {
+        assert_eq!(trim_newline("abc\n".into()), "abc");
+        assert_eq!(trim_newline("abc\r\n".into()), "abc");
+        assert_eq!(trim_newline("abc".into()), "abc");
+        assert_eq!(trim_newline("abc\r".into()), "abc\r");
+    }
+
+    #[test]
+    fn split_surrogate() {
+        assert!(ends_with_split_surrogate("a日\u{fffd}".as_bytes(), 3));
+        assert!(ends_with_split_surrogate(b"a\xe6\x97\xa5\xed\xa0\xbd", 3));
+        assert!(!ends_with_split_surrogate("a日\u{fffd}".as_bytes(), 4));
+        assert!(!ends_with_split_surrogate("a日b".as_bytes(), 3));
+        assert!(!ends_with_split_surrogate("a😀".as_bytes(), 3));
+        assert!(!ends_with_split_surrogate(b"a\xe6\x97\xa5\xed\xb0\x80", 3));
+        assert!(!ends_with_split_surrogate(b"", 1));
+    }
+}
@@ -0,0 +1,572 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+// I/O traits are implemented only in sync and tokio builds.
+#![cfg_attr(all(feature = "is_async", not(feature = "tokio")), allow(dead_code))]
+use super::buffered::{buffer_amount, complete_len, utf16_len, Buffered};
+use super::clob::{ends_with_split_surrogate, read_text};
+use crate::maybe_async;
+use crate::types::{Lob, OracleType};
+use crate::*;
+use odpic_sys::blocking::{dpiLob_getSize, dpiLob_trim, dpiLob_writeBytes};
+use odpic_sys::*;
+use std::cmp;
+use std::ffi::c_char;
+use std::io;
+use std::io::SeekFrom;
+#[cfg(not(feature = "is_async"))]
+use std::io::{BufRead, Read, Seek, Write};
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::task::{ready, Context, Poll};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+/// Buffered stream over a [`Lob`]
+///
+/// This is created by [`Lob::stream`]. It implements [`Read`], [`BufRead`],
+/// [`Write`] and [`Seek`] when none of async runtime features are enabled
+/// and tokio's [`AsyncRead`], [`AsyncBufRead`], [`AsyncWrite`] and
+/// [`AsyncSeek`] when the `tokio` feature is enabled.
+///
+/// Data are read and written in the unit of a multiple of [`Lob::chunk_size`].
+/// Written data are buffered until the buffer becomes full or the stream is
+/// flushed. In async builds, call `flush()` or `shutdown()` before dropping
+/// the stream. Otherwise buffered data are lost.
+///
+/// Positions are counted in characters for CLOB and NCLOB and in bytes
+/// for BLOB and BFILE, which are same with offsets passed to
+/// [`Lob::read_bytes`] and [`Lob::write_bytes`] except that positions start
+/// from zero. Data are UTF-8 bytes for CLOB and NCLOB. A character outside
+/// of the Basic Multilingual Plane is counted as two characters as Oracle
+/// does.
+///
+/// # Examples
+///
+/// Copy a BLOB to a file.
+///
+/// ```ignore
+/// let lob: Lob = stmt.query_value(1)?;
+/// let mut stream = lob.stream()?;
+/// let mut file = File::create("blob.bin")?;
+/// io::copy(&mut stream, &mut file)?;
+/// ```
+///
+/// Upload a file to a temporary LOB and insert it in a tokio build.
+///
+/// ```ignore
+/// let lob = conn.new_temp_lob(OracleType::Blob).await?;
+/// let mut stream = lob.stream().await?;
+/// let mut file = tokio::fs::File::open("blob.bin").await?;
+/// tokio::io::copy(&mut file, &mut stream).await?;
+/// stream.shutdown().await?;
+/// let stmt = conn.prepare_stmt(false, "insert into some_table values (:1)", "")?;
+/// stmt.bind_value_by_pos(1, &lob)?;
+/// stmt.execute(ExecMode::DEFAULT).await?;
+/// ```
+///
+/// [`Read`]: std::io::Read
+/// [`BufRead`]: std::io::BufRead
+/// [`Write`]: std::io::Write
+/// [`Seek`]: std::io::Seek
+/// [`AsyncRead`]: https://docs.rs/tokio/1/tokio/io/trait.AsyncRead.html
+/// [`AsyncBufRead`]: https://docs.rs/tokio/1/tokio/io/trait.AsyncBufRead.html
+/// [`AsyncWrite`]: https://docs.rs/tokio/1/tokio/io/trait.AsyncWrite.html
+/// [`AsyncSeek`]: https://docs.rs/tokio/1/tokio/io/trait.AsyncSeek.html
+pub struct LobStream {
+    inner: Buffered<Buffer>,
+    #[cfg(feature = "tokio")]
+    filling: bool,
+}
+
+/// Buffer shared by reads and writes
+///
+/// In read mode, `data` holds bytes read at `pos` and `offset` bytes of them
+/// are consumed. In write mode, `data` holds bytes which will be written
+/// at `pos`.
+struct Buffer {
+    is_clob: bool,
+    // number of bytes or characters read at a time
+    amount: u64,
+    // number of bytes to hold `amount` bytes or characters
+    read_capacity: usize,
+    pos: u64,
+    data: Vec<u8>,
+    offset: usize,
+    writing: bool,
+}
+
+impl Buffer {
+    /// Returns the length of `bytes` in the unit of LOB positions
+    fn units(&self, bytes: &[u8]) -> u64 {
+        if self.is_clob {
+            utf16_len(bytes)
+        } else {
+            bytes.len() as u64
+        }
+    }
+
+    fn position(&self) -> u64 {
+        if self.writing {
+            self.pos + self.units(&self.data)
+        } else {
+            self.pos + self.units(&self.data[..self.offset])
+        }
+    }
+
+    fn start_writing(&mut self) {
+        if !self.writing {
+            self.discard();
+            self.writing = true;
+        }
+    }
+
+    /// Discards the read buffer
+    fn discard(&mut self) {
+        self.pos = self.position();
+        self.data.clear();
+        self.offset = 0;
+    }
+
+    /// Appends bytes to the write buffer and returns the number of appended bytes
+    fn append(&mut self, bytes: &[u8]) -> usize {
+        let len = cmp::min(bytes.len(), self.amount as usize - self.data.len());
+        self.data.extend_from_slice(&bytes[..len]);
+        len
+    }
+
+    /// Reads the next data after all read data are consumed
+    fn fill(&mut self, handle: *mut dpiLob) -> Result<()> {
+        self.discard();
+        self.data.resize(self.read_capacity, 0);
+        let result = self.read_data(handle);
+        self.data.truncate(*result.as_ref().unwrap_or(&0));
+        result.map(|_| ())
+    }
+
+    /// Reads data at `pos` to `data` and returns the number of read bytes
+    ///
+    /// CLOB data aren't split in the middle of a surrogate pair as [`ClobReader`].
+    ///
+    /// [`ClobReader`]: super::ClobReader
+    fn read_data(&mut self, handle: *mut dpiLob) -> Result<usize> {
+        let offset = self.pos + 1;
+        let len = read_text(handle, offset, self.amount, &mut self.data)?;
+        if self.is_clob
+            && self.amount > 1
+            && ends_with_split_surrogate(&self.data[..len], self.amount)
+        {
+            // Read one character short to read the surrogate pair next time.
+            return read_text(handle, offset, self.amount - 1, &mut self.data);
+        }
+        Ok(len)
+    }
+
+    /// Writes buffered data
+    ///
+    /// When `all` is false, an incomplete UTF-8 character at the end of
+    /// the CLOB data is left in the buffer. Otherwise, it is an error.
+    fn write_out(&mut self, handle: *mut dpiLob, all: bool) -> Result<()> {
+        let len = if self.is_clob {
+            complete_len(&self.data)
+        } else {
+            self.data.len()
+        };
+        if len > 0 {
+            call!(dpiLob_writeBytes(
+                handle,
+                self.pos + 1,
+                self.data.as_ptr() as *const c_char,
+                len as u64
+            ))?;
+            self.pos += self.units(&self.data[..len]);
+            self.data.drain(..len);
+        }
+        if all && !self.data.is_empty() {
+            return Err(Error::other(
+                "incomplete UTF-8 character at the end of CLOB data",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes all buffered data and switches to read mode
+    fn finish_writing(&mut self, handle: *mut dpiLob) -> Result<()> {
+        if self.writing {
+            self.write_out(handle, true)?;
+            self.writing = false;
+        }
+        Ok(())
+    }
+
+    fn seek(&mut self, handle: *mut dpiLob, pos: SeekFrom) -> Result<u64> {
+        if pos == SeekFrom::Current(0) && !self.writing {
+            return Ok(self.position());
+        }
+        self.finish_writing(handle)?;
+        let (base, offset) = match pos {
+            SeekFrom::Start(pos) => (pos, 0),
+            SeekFrom::End(offset) => (get_value!(dpiLob_getSize(handle))?, offset),
+            SeekFrom::Current(offset) => (self.position(), offset),
+        };
+        let pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.unsigned_abs())
+        }
+        .ok_or_else(|| Error::other("invalid seek to a negative or overflowing position"))?;
+        self.pos = pos;
+        self.data.clear();
+        self.offset = 0;
+        Ok(pos)
+    }
+
+    fn set_len(&mut self, handle: *mut dpiLob, size: u64) -> Result<()> {
+        self.finish_writing(handle)?;
+        self.discard();
+        call!(dpiLob_trim(handle, size))
+    }
+}
+
+impl LobStream {
+    pub(crate) fn new(lob: Lob, chunk_size: u32) -> Result<LobStream> {
+        let is_clob = matches!(lob.get_type()?, OracleType::Clob | OracleType::Nclob);
+        let amount = buffer_amount(chunk_size);
+        let read_capacity = if is_clob {
+            lob.buffer_size(amount)?
+        } else {
+            amount
+        };
+        let buffer = Buffer {
+            is_clob,
+            amount,
+            read_capacity: read_capacity.try_into()?,
+            pos: 0,
+            data: Vec::new(),
+            offset: 0,
+            writing: false,
+        };
+        Ok(LobStream {
+            inner: Buffered::new(lob, buffer),
+            #[cfg(feature = "tokio")]
+            filling: false,
+        })
+    }
+
+    /// Returns the underlying LOB
+    ///
+    /// Flush the stream before accessing the LOB directly.
+    pub fn lob(&self) -> &Lob {
+        self.inner.lob()
+    }
+
+    /// Truncates the LOB to `size` bytes or characters
+    ///
+    /// Buffered data are written before that. The current position isn't changed.
+    #[maybe_async]
+    pub async fn set_len(&mut self, size: u64) -> Result<()> {
+        self.inner
+            .run(move |buffer, handle| buffer.set_len(handle, size))
+            .await
+    }
+}
+
+#[cfg(not(feature = "is_async"))]
+impl Read for LobStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.fill_buf()?;
+        let len = cmp::min(data.len(), buf.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+#[cfg(not(feature = "is_async"))]
+impl BufRead for LobStream {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let handle = self.inner.lob().handle;
+        let buffer = self.inner.buffer_mut().map_err(io_error)?;
+        buffer.finish_writing(handle).map_err(io_error)?;
+        if buffer.offset >= buffer.data.len() {
+            buffer.fill(handle).map_err(io_error)?;
+        }
+        Ok(&buffer.data[buffer.offset..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buffer) = self.inner.buffer_mut() {
+            buffer.offset = cmp::min(buffer.offset + amt, buffer.data.len());
+        }
+    }
+}
+
+#[cfg(not(feature = "is_async"))]
+impl Write for LobStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let handle = self.inner.lob().handle;
+        let buffer = self.inner.buffer_mut().map_err(io_error)?;
+        buffer.start_writing();
+        if buffer.data.len() >= buffer.amount as usize {
+            buffer.write_out(handle, false).map_err(io_error)?;
+        }
+        Ok(buffer.append(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner
+            .run(|buffer, handle| buffer.finish_writing(handle))
+            .map_err(io_error)
+    }
+}
+
+#[cfg(not(feature = "is_async"))]
+impl Seek for LobStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let handle = self.inner.lob().handle;
+        self.inner
+            .buffer_mut()
+            .and_then(|buffer| buffer.seek(handle, pos))
+            .map_err(io_error)
+    }
+}
+
+#[cfg(not(feature = "is_async"))]
+impl Drop for LobStream {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl LobStream {
+    /// Waits for the running blocking task if it exists
+    fn poll_task(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let result = ready!(self.inner.poll_task(cx));
+        if result.is_err() {
+            self.filling = false;
+        }
+        Poll::Ready(result.map_err(io_error))
+    }
+
+    /// Starts a blocking task operating the buffer
+    fn start<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Buffer, *mut dpiLob) -> Result<()> + Send + 'static,
+    {
+        self.filling = false;
+        self.inner.start(f);
+    }
+}
+
+// Fields are never pinned.
+#[cfg(feature = "tokio")]
+impl Unpin for LobStream {}
+
+#[cfg(feature = "tokio")]
+impl AsyncRead for LobStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let data = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = cmp::min(data.len(), buf.remaining());
+        buf.put_slice(&data[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncBufRead for LobStream {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_task(cx))?;
+            let buffer = this.inner.buffer_mut().map_err(io_error)?;
+            if buffer.writing {
+                this.start(|buffer, handle| buffer.finish_writing(handle));
+            } else if buffer.offset >= buffer.data.len() && !this.filling {
+                this.start(|buffer, handle| buffer.fill(handle));
+                this.filling = true;
+            } else {
+                // Data read by the last blocking task are returned even
+                // when they are empty at the end of the LOB.
+                this.filling = false;
+                break;
+            }
+        }
+        let buffer = this.inner.buffer_mut().map_err(io_error)?;
+        Poll::Ready(Ok(&buffer.data[buffer.offset..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        if let Ok(buffer) = self.get_mut().inner.buffer_mut() {
+            buffer.offset = cmp::min(buffer.offset + amt, buffer.data.len());
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncWrite for LobStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_task(cx))?;
+            let buffer = this.inner.buffer_mut().map_err(io_error)?;
+            buffer.start_writing();
+            if buffer.data.len() < buffer.amount as usize {
+                return Poll::Ready(Ok(buffer.append(buf)));
+            }
+            this.start(|buffer, handle| buffer.write_out(handle, false));
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_task(cx))?;
+            let buffer = this.inner.buffer_mut().map_err(io_error)?;
+            if !buffer.writing {
+                return Poll::Ready(Ok(()));
+            }
+            this.start(|buffer, handle| buffer.finish_writing(handle));
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncSeek for LobStream {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+        if this.inner.is_running() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "other operation is in progress",
+            ));
+        }
+        this.inner.buffer_mut().map_err(io_error)?;
+        this.start(move |buffer, handle| buffer.seek(handle, position).map(|_| ()));
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        ready!(this.poll_task(cx))?;
+        let buffer = this.inner.buffer_mut().map_err(io_error)?;
+        Poll::Ready(Ok(buffer.position()))
+    }
+}
+
+fn io_error(err: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(all(test, any(not(feature = "is_async"), feature = "tokio")))]
+mod tests {
+    use super::buffer_amount;
+    use crate::test_util;
+    use crate::types::OracleType;
+    use std::error::Error;
+    use std::io::SeekFrom;
+    #[cfg(not(feature = "is_async"))]
+    use std::io::{Read, Seek, Write};
+    #[cfg(feature = "tokio")]
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+    type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+    #[crate::test]
+    async fn blob_stream() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let lob = conn.new_temp_lob(OracleType::Blob).await?;
+        let data = (0..200_000u32).map(|i| i as u8).collect::<Vec<_>>();
+        let mut stream = lob.stream().await?;
+        stream.write_all(&data).await?;
+        stream.flush().await?;
+        let size = lob.size().await?;
+        assert_eq!(size, data.len() as u64);
+
+        let pos = stream.seek(SeekFrom::Start(0)).await?;
+        assert_eq!(pos, 0);
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await?;
+        assert_eq!(buf, data);
+
+        let pos = stream.seek(SeekFrom::End(-10)).await?;
+        assert_eq!(pos, data.len() as u64 - 10);
+        let mut buf = [0u8; 20];
+        let len = stream.read(&mut buf).await?;
+        assert_eq!(&buf[..len], &data[data.len() - 10..]);
+
+        // Overwrite and truncate data in the middle.
+        let pos = stream.seek(SeekFrom::Start(100)).await?;
+        assert_eq!(pos, 100);
+        stream.write_all(&[0xff; 10]).await?;
+        stream.set_len(105).await?;
+        let pos = stream.seek(SeekFrom::Start(95)).await?;
+        assert_eq!(pos, 95);
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await?;
+        assert_eq!(buf, [95, 96, 97, 98, 99, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn clob_stream() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let lob = conn.new_temp_lob(OracleType::Clob).await?;
+        let amount = buffer_amount(lob.chunk_size().await?) as usize;
+        // The first read of `amount` characters ends in the middle of
+        // the surrogate pair of U+1F600.
+        let text = format!("{}😀日{}", "a".repeat(amount - 1), "b".repeat(amount));
+        let mut stream = lob.stream().await?;
+        stream.write_all(text.as_bytes()).await?;
+        stream.flush().await?;
+        let size = lob.size().await?;
+        assert_eq!(size, 2 * amount as u64 + 2);
+
+        let pos = stream.seek(SeekFrom::Start(0)).await?;
+        assert_eq!(pos, 0);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).await?;
+        assert_eq!(buf, text);
+        let pos = stream.seek(SeekFrom::Current(0)).await?;
+        assert_eq!(pos, size);
+
+        // Positions are counted in characters.
+        let pos = stream.seek(SeekFrom::Start(amount as u64 + 1)).await?;
+        assert_eq!(pos, amount as u64 + 1);
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await?;
+        assert_eq!(&buf, "日b".as_bytes());
+        Ok(())
+    }
+}
@@ -12,9 +12,10 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
+use crate::conn::Conn;
 use crate::stmt::Stmt;
 use crate::types::{
-    IntervalDS, IntervalYM, Json, Lob, NativeType, Object, Rowid, Timestamp, Vector,
+    IntervalDS, IntervalYM, Json, Lob, NativeType, Object, ObjectType, Rowid, Timestamp, Vector,
 };
 use crate::*;
 use odpic_sys::*;
@@ -73,7 +74,7 @@ impl NativeValue<'_> {
                 }
                 NativeType::Lob => NativeValue::Lob(to_opt(data, Lob::with_add_ref(value.asLOB))),
                 NativeType::Object => {
-                    NativeValue::Object(to_opt(data, Object::with_add_ref(value.asObject)))
+                    NativeValue::Object(to_opt(data, Object::with_add_ref(value.asObject, None)))
                 }
                 NativeType::Stmt => {
                     NativeValue::Stmt(to_opt(data, Stmt::with_add_ref(value.asStmt)))
@@ -94,6 +95,31 @@ impl NativeValue<'_> {
             })
         }
     }
+
+    // Sets the object type, which dpiData doesn't have, to the object value.
+    pub(crate) fn with_object_type(mut self, objtype: Option<&ObjectType>) -> Self {
+        if let NativeValue::Object(Some(obj)) = &mut self {
+            obj.set_object_type(objtype);
+        }
+        self
+    }
+
+    // Sets the connection and the define settings of the statement, which
+    // fetched the REF CURSOR value, to the value.
+    pub(crate) fn with_parent_stmt(mut self, parent: &Stmt) -> Self {
+        if let NativeValue::Stmt(Some(stmt)) = &mut self {
+            stmt.set_parent(parent);
+        }
+        self
+    }
+
+    // Sets the connection and its define handler to the REF CURSOR value.
+    pub(crate) fn with_conn(mut self, conn: &Conn) -> Self {
+        if let NativeValue::Stmt(Some(stmt)) = &mut self {
+            stmt.set_conn(conn);
+        }
+        self
+    }
 }
 
 impl<'a> TryFrom<NativeValue<'a>> for i64 {
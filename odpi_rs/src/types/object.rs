@@ -12,49 +12,158 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
-use crate::Result;
+use crate::conn::Conn;
+use crate::maybe_async;
+use crate::stmt::BindValue;
+use crate::types::{
+    FromObject, FromSql, NativeType, NativeValue, ObjectAttr, ObjectType, OracleType, ToObject,
+};
+use crate::utils::*;
+use crate::{Error, Result};
 use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
+use std::ffi::c_char;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem;
+
+// The buffer size to get NUMBER values as text.
+// This is same with DPI_NUMBER_AS_TEXT_CHARS in ODPI-C.
+const NUMBER_AS_TEXT_CHARS: usize = 172;
 
 #[derive(Debug)]
 #[odpic_doc]
 pub struct Object {
     pub(crate) handle: *mut dpiObject,
+    // None when the object is got from dpiData whose object type is unknown.
+    objtype: Option<ObjectType>,
 }
 
 impl Object {
-    pub(crate) fn new(handle: *mut dpiObject) -> Object {
-        Object { handle }
+    pub(crate) fn new(handle: *mut dpiObject, objtype: Option<ObjectType>) -> Object {
+        Object { handle, objtype }
     }
 
-    pub(crate) fn with_add_ref(handle: *mut dpiObject) -> Object {
+    pub(crate) fn with_add_ref(handle: *mut dpiObject, objtype: Option<ObjectType>) -> Object {
         unsafe { dpiObject_addRef(handle) };
-        Object { handle }
+        Object { handle, objtype }
     }
 
-    // // append an element to the collection
-    // DPI_EXPORT int dpiObject_appendElement(dpiObject *obj,
-    //         dpiNativeTypeNum nativeTypeNum, dpiData *value);
+    pub(crate) fn set_object_type(&mut self, objtype: Option<&ObjectType>) {
+        self.objtype = objtype.cloned();
+    }
+
+    /// Returns the object type, or `None` when it is unknown.
+    pub fn object_type(&self) -> Option<&ObjectType> {
+        self.objtype.as_ref()
+    }
+
+    /// Appends an element to the collection.
+    pub fn append_element<T>(&self, value: &T) -> Result<()>
+    where
+        T: BindValue,
+    {
+        value.with_data(None, |data| {
+            call!(dpiObject_appendElement(
+                self.handle,
+                T::native_type_num(),
+                data
+            ))
+        })
+    }
+
+    /// Creates an object from a Rust value.
+    ///
+    /// The object type is looked up by [`ToObject::TYPE_NAME`].
+    #[maybe_async]
+    pub async fn from_value<T>(conn: &Conn, value: &T) -> Result<Object>
+    where
+        T: ToObject,
+    {
+        let objtype = conn
+            .object_type(type_name_or_err::<T>(T::TYPE_NAME)?)
+            .await?;
+        value.to_object(&objtype)
+    }
+
+    /// Converts the object to a Rust value.
+    ///
+    /// The object type is looked up by [`FromObject::TYPE_NAME`].
+    #[maybe_async]
+    pub async fn to_value<T>(&self, conn: &Conn) -> Result<T>
+    where
+        T: FromObject,
+    {
+        let objtype = conn
+            .object_type(type_name_or_err::<T>(T::TYPE_NAME)?)
+            .await?;
+        T::from_object(self, &objtype)
+    }
 
     pub fn copy(&self) -> Result<Object> {
-        Ok(Object::new(get_value!(dpiObject_copy(self.handle))?))
+        Ok(Object::new(
+            get_value!(dpiObject_copy(self.handle))?,
+            self.objtype.clone(),
+        ))
     }
 
     pub fn delete_element_by_index(&self, index: i32) -> Result<()> {
         call!(dpiObject_deleteElementByIndex(self.handle, index))
     }
 
-    // // get the value of the specified attribute
-    // DPI_EXPORT int dpiObject_getAttributeValue(dpiObject *obj, dpiObjectAttr *attr,
-    //         dpiNativeTypeNum nativeTypeNum, dpiData *value);
+    /// Gets the value of the specified attribute.
+    ///
+    /// NUMBER attributes are fetched as text and converted to `T` without
+    /// going through floating point numbers.
+    pub fn attribute_value<T>(&self, attr: &ObjectAttr) -> Result<T>
+    where
+        T: FromSql,
+    {
+        let info = get_value!(dpiObjectAttr_getInfo(attr.handle))?;
+        let value_type = ValueType::new(&info.typeInfo)?;
+        let mut buf = [0u8; NUMBER_AS_TEXT_CHARS];
+        let mut data = new_data(value_type.native_type, &mut buf);
+        call!(dpiObject_getAttributeValue(
+            self.handle,
+            attr.handle,
+            value_type.native_type.to_dpi(),
+            &mut data
+        ))?;
+        value_from_data(&data, &value_type)
+    }
 
     pub fn element_exists_by_index(&self, index: i32) -> Result<bool> {
         Ok(get_value!(dpiObject_getElementExistsByIndex(self.handle, index))? != 0)
     }
 
-    // // get the value of the element in a collection at the specified index
-    // DPI_EXPORT int dpiObject_getElementValueByIndex(dpiObject *obj, int32_t index,
-    //         dpiNativeTypeNum nativeTypeNum, dpiData *value);
+    /// Gets the value of the element in the collection at the specified index.
+    ///
+    /// NUMBER elements are fetched as text and converted to `T` without
+    /// going through floating point numbers.
+    pub fn element_value_by_index<T>(&self, index: i32) -> Result<T>
+    where
+        T: FromSql,
+    {
+        self.element_value(index, &self.element_type()?)
+    }
+
+    /// Returns an iterator over the indexes and values of the elements in the collection.
+    ///
+    /// The iterator walks the collection by [`Object::first_index`] and [`Object::next_index`]
+    /// so that indexes not in use in sparse collections such as PL/SQL index-by
+    /// tables are skipped.
+    pub fn elements<T>(&self) -> Elements<'_, T>
+    where
+        T: FromSql,
+    {
+        Elements {
+            obj: self,
+            element_type: None,
+            index: None,
+            done: false,
+            phantom: PhantomData,
+        }
+    }
 
     pub fn first_index(&self) -> Result<Option<i32>> {
         let (index, exists) = get_2values!(dpiObject_getFirstIndex(self.handle))?;
@@ -96,17 +205,197 @@ impl Object {
         get_value!(dpiObject_getSize(self.handle))
     }
 
-    // // set the value of the specified attribute
-    // DPI_EXPORT int dpiObject_setAttributeValue(dpiObject *obj, dpiObjectAttr *attr,
-    //         dpiNativeTypeNum nativeTypeNum, dpiData *value);
+    /// Sets the value of the specified attribute.
+    ///
+    /// Use `None` of `Option<T>` to set NULL.
+    pub fn set_attribute_value<T>(&self, attr: &ObjectAttr, value: &T) -> Result<()>
+    where
+        T: BindValue,
+    {
+        value.with_data(None, |data| {
+            call!(dpiObject_setAttributeValue(
+                self.handle,
+                attr.handle,
+                T::native_type_num(),
+                data
+            ))
+        })
+    }
 
-    // // set the value of the element in a collection at the specified index
-    // DPI_EXPORT int dpiObject_setElementValueByIndex(dpiObject *obj, int32_t index,
-    //         dpiNativeTypeNum nativeTypeNum, dpiData *value);
+    /// Sets the value of the element in the collection at the specified index.
+    pub fn set_element_value_by_index<T>(&self, index: i32, value: &T) -> Result<()>
+    where
+        T: BindValue,
+    {
+        value.with_data(None, |data| {
+            call!(dpiObject_setElementValueByIndex(
+                self.handle,
+                index,
+                T::native_type_num(),
+                data
+            ))
+        })
+    }
 
     pub fn trim(&self, num_to_trim: u32) -> Result<()> {
         call!(dpiObject_trim(self.handle, num_to_trim))
     }
+
+    // Returns how to get elements of the collection.
+    // The element type is got from the object type of the collection.
+    fn element_type(&self) -> Result<ValueType> {
+        let objtype = self
+            .objtype
+            .as_ref()
+            .ok_or_else(|| Error::other("the object type of the collection is unknown"))?;
+        let info = get_value!(dpiObjectType_getInfo(objtype.handle))?;
+        if info.isCollection == 0 {
+            return Err(Error::other(format!("{:?} isn't a collection", objtype)));
+        }
+        ValueType::new(&info.elementTypeInfo)
+    }
+
+    fn element_value<T>(&self, index: i32, element_type: &ValueType) -> Result<T>
+    where
+        T: FromSql,
+    {
+        let mut buf = [0u8; NUMBER_AS_TEXT_CHARS];
+        let mut data = new_data(element_type.native_type, &mut buf);
+        call!(dpiObject_getElementValueByIndex(
+            self.handle,
+            index,
+            element_type.native_type.to_dpi(),
+            &mut data
+        ))?;
+        value_from_data(&data, element_type)
+    }
+}
+
+/// Iterator returned by [`Object::elements`]
+///
+/// This yields pairs of an index and an element value.
+pub struct Elements<'a, T> {
+    obj: &'a Object,
+    // got at the first call of next()
+    element_type: Option<ValueType>,
+    index: Option<i32>,
+    done: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Iterator for Elements<'_, T>
+where
+    T: FromSql,
+{
+    type Item = Result<(i32, T)>;
+
+    fn next(&mut self) -> Option<Result<(i32, T)>> {
+        if self.done {
+            return None;
+        }
+        let next_index = match self.index {
+            None => self.obj.first_index(),
+            Some(index) => self.obj.next_index(index),
+        };
+        match next_index {
+            Ok(Some(index)) => {
+                self.index = Some(index);
+                if self.element_type.is_none() {
+                    match self.obj.element_type() {
+                        Ok(element_type) => self.element_type = Some(element_type),
+                        Err(err) => {
+                            self.done = true;
+                            return Some(Err(err));
+                        }
+                    }
+                }
+                let element_type = self.element_type.as_ref()?;
+                Some(
+                    self.obj
+                        .element_value(index, element_type)
+                        .map(|value| (index, value)),
+                )
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<T> FusedIterator for Elements<'_, T> where T: FromSql {}
+
+fn type_name_or_err<T>(type_name: Option<&'static str>) -> Result<&'static str> {
+    type_name.ok_or_else(|| {
+        Error::other(format!(
+            "no object type name for {}. Use #[odpi(type = \"SCHEMA.TYPE_NAME\")]",
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+// Creates dpiData passed to dpiObject_getAttributeValue() and so on.
+// When the native type is bytes, a buffer is set to receive NUMBER values as text.
+fn new_data(native_type: NativeType, buf: &mut [u8; NUMBER_AS_TEXT_CHARS]) -> dpiData {
+    let mut data: dpiData = unsafe { mem::zeroed() };
+    if native_type == NativeType::Bytes {
+        data.value.asBytes.ptr = buf.as_mut_ptr() as *mut c_char;
+        data.value.asBytes.length = NUMBER_AS_TEXT_CHARS as u32;
+    }
+    data
+}
+
+// The native type and the object type of an attribute or an element
+struct ValueType {
+    native_type: NativeType,
+    objtype: Option<ObjectType>,
+}
+
+impl ValueType {
+    // NUMBER values are transferred as text to keep their precision.
+    fn new(info: &dpiDataTypeInfo) -> Result<ValueType> {
+        let oracle_type: OracleType = info.oracleTypeNum.try_to_rust()?;
+        let native_type = match oracle_type {
+            OracleType::Number => NativeType::Bytes,
+            _ => info.defaultNativeTypeNum.try_to_rust()?,
+        };
+        Ok(ValueType {
+            native_type,
+            objtype: ObjectType::with_add_ref(info.objectType),
+        })
+    }
+}
+
+// Converts dpiData got from an object to a rust value.
+// ODPI-C returns new references for LOB and object values. They are released here
+// because NativeValue::from_dpi_data() adds another reference.
+fn value_from_data<T>(data: &dpiData, value_type: &ValueType) -> Result<T>
+where
+    T: FromSql,
+{
+    let native_type = value_type.native_type;
+    let result = NativeValue::from_dpi_data(data, native_type)
+        .map(|value| value.with_object_type(value_type.objtype.as_ref()))
+        .and_then(T::from_sql);
+    if data.isNull == 0 {
+        match native_type {
+            NativeType::Lob => {
+                let handle = unsafe { data.value.asLOB };
+                release_handle!(dpiLob_release(handle))
+            }
+            NativeType::Object => {
+                let handle = unsafe { data.value.asObject };
+                release_handle!(dpiObject_release(handle))
+            }
+            _ => (),
+        }
+    }
+    result
 }
 
 impl Clone for Object {
@@ -114,6 +403,7 @@ impl Clone for Object {
         unsafe { dpiObject_addRef(self.handle) };
         Object {
             handle: self.handle,
+            objtype: self.objtype.clone(),
         }
     }
 }
@@ -123,3 +413,169 @@ impl Drop for Object {
         release_handle!(dpiObject_release(self.handle));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::conn::Conn;
+    use crate::stmt::ExecMode;
+    use crate::types::{FromObject, Lob, Object, OracleType, ToObject};
+    use crate::{maybe_async, test_util, Result};
+
+    #[derive(Debug, PartialEq, FromObject, ToObject)]
+    #[odpi(type = "UDT_SUBOBJECT")]
+    struct SubObject {
+        #[odpi(rename = "SUBNUMBERVALUE")]
+        number: Option<i64>,
+        #[odpi(rename = "SUBSTRINGVALUE")]
+        string: Option<String>,
+    }
+
+    #[derive(FromObject, ToObject)]
+    #[odpi(type = "ODPI_RS_DERIVE_TEST")]
+    struct DeriveTest {
+        id: i64,
+        data: Vec<u8>,
+        text: Option<Lob>,
+        subobject: Option<SubObject>,
+        subobjects: Vec<SubObject>,
+    }
+
+    #[crate::test]
+    async fn collection_elements() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let objtype = conn.object_type("UDT_ARRAY").await?;
+        let array = objtype.create_object()?;
+        array.append_element(&1i64)?;
+        array.append_element(&2.5f64)?;
+        array.append_element(&Option::<i64>::None)?;
+        assert_eq!(array.size()?, 3);
+        assert_eq!(array.element_value_by_index::<f64>(1)?, 2.5);
+        assert_eq!(array.element_value_by_index::<Option<i64>>(2)?, None);
+
+        array.set_element_value_by_index(1, &20i64)?;
+        array.set_element_value_by_index(2, &"30")?;
+        assert_eq!(array.element_value_by_index::<i64>(1)?, 20);
+        assert_eq!(array.element_value_by_index::<String>(2)?, "30");
+
+        let elements = array.elements::<i64>().collect::<Result<Vec<_>>>()?;
+        assert_eq!(elements, [(0, 1), (1, 20), (2, 30)]);
+        array.trim(1)?;
+        let elements = array.elements::<i64>().collect::<Result<Vec<_>>>()?;
+        assert_eq!(elements, [(0, 1), (1, 20)]);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn sparse_collection_elements() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let objtype = conn
+            .object_type("PKG_TESTSTRINGARRAYS.UDT_STRINGLIST")
+            .await?;
+        let list = objtype.create_object()?;
+        list.set_element_value_by_index(-5, &"a")?;
+        list.set_element_value_by_index(3, &"b")?;
+        list.set_element_value_by_index(10, &"c")?;
+        list.delete_element_by_index(3)?;
+        let elements = list.elements::<String>().collect::<Result<Vec<_>>>()?;
+        assert_eq!(elements, [(-5, "a".to_string()), (10, "c".to_string())]);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn object_elements() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let array_type = conn.object_type("UDT_OBJECTARRAY").await?;
+        let sub_type = conn.object_type("UDT_SUBOBJECT").await?;
+        let attrs = sub_type.attributes()?;
+        let array = array_type.create_object()?;
+        for (num, s) in [(1i64, "one"), (2, "two")] {
+            let obj = sub_type.create_object()?;
+            obj.set_attribute_value(&attrs[0], &num)?;
+            obj.set_attribute_value(&attrs[1], &s)?;
+            array.append_element(&obj)?;
+        }
+        let mut values = vec![];
+        for element in array.elements::<Object>() {
+            let (_, obj) = element?;
+            assert!(obj.object_type().is_some());
+            values.push((
+                obj.attribute_value::<i64>(&attrs[0])?,
+                obj.attribute_value::<String>(&attrs[1])?,
+            ));
+        }
+        assert_eq!(values, [(1, "one".to_string()), (2, "two".to_string())]);
+        Ok(())
+    }
+
+    // Converts `value` to an object and back and returns the converted value
+    // and the contents of its CLOB
+    #[maybe_async]
+    async fn convert_derive_test(conn: &Conn, value: &DeriveTest) -> Result<(DeriveTest, Vec<u8>)> {
+        let obj = Object::from_value(conn, value).await?;
+        let got: DeriveTest = obj.to_value(conn).await?;
+        let mut buf = [0u8; 20];
+        let len = match &got.text {
+            Some(text) => text.read_bytes(1, 20, &mut buf).await?,
+            None => 0,
+        };
+        Ok((got, buf[..len].to_vec()))
+    }
+
+    #[crate::test]
+    async fn derive_round_trip() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let text = conn.new_temp_lob(OracleType::Clob).await?;
+        text.set_from_bytes("clob text").await?;
+        let value = DeriveTest {
+            id: 123,
+            data: vec![0, 1, 2, 255],
+            text: Some(text),
+            subobject: None,
+            subobjects: vec![
+                SubObject {
+                    number: Some(1),
+                    string: Some("one".into()),
+                },
+                SubObject {
+                    number: None,
+                    string: None,
+                },
+            ],
+        };
+        conn.prepare_stmt(
+            false,
+            "create or replace type odpi_rs_derive_test force as object (
+               id number(9),
+               data raw(16),
+               text clob,
+               subobject udt_SubObject,
+               subobjects udt_ObjectArray)",
+            "",
+        )?
+        .execute(ExecMode::DEFAULT)
+        .await?;
+        // The type is dropped before the results are checked so that it
+        // isn't left behind when the test fails.
+        let result = convert_derive_test(&conn, &value).await;
+        conn.prepare_stmt(false, "drop type odpi_rs_derive_test force", "")?
+            .execute(ExecMode::DEFAULT)
+            .await?;
+        let (got, text) = result?;
+        assert_eq!(got.id, 123);
+        assert_eq!(got.data, [0, 1, 2, 255]);
+        assert!(got.text.is_some());
+        assert_eq!(text, b"clob text");
+        assert_eq!(got.subobject, None);
+        assert_eq!(got.subobjects, value.subobjects);
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn elements_of_non_collection() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let obj = conn.object_type("UDT_SUBOBJECT").await?.create_object()?;
+        assert!(obj.element_value_by_index::<i64>(0).is_err());
+        assert!(obj.elements::<i64>().next().unwrap().is_err());
+        Ok(())
+    }
+}
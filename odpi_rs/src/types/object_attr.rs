@@ -12,6 +12,9 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
+use crate::types::ObjectAttrInfo;
+use crate::utils::*;
+use crate::Result;
 use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
 
@@ -25,6 +28,10 @@ impl ObjectAttr {
     pub(crate) fn new(handle: *mut dpiObjectAttr) -> ObjectAttr {
         ObjectAttr { handle }
     }
+
+    pub fn info(&self) -> Result<ObjectAttrInfo> {
+        get_value!(dpiObjectAttr_getInfo(self.handle))?.try_to_rust()
+    }
 }
 
 impl Clone for ObjectAttr {
@@ -0,0 +1,81 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::types::DataTypeInfo;
+use crate::utils::*;
+use crate::Result;
+use odpi_rs_procmacro::odpic_doc;
+use odpic_sys::*;
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+#[odpic_doc]
+pub struct ObjectAttrInfo {
+    pub name: String,
+    pub type_info: DataTypeInfo,
+}
+
+impl TryToRust<ObjectAttrInfo> for dpiObjectAttrInfo {
+    fn try_to_rust(&self) -> Result<ObjectAttrInfo> {
+        Ok(ObjectAttrInfo {
+            name: (self.name, self.nameLength).try_to_rust()?,
+            type_info: self.typeInfo.try_to_rust()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::OracleType;
+    use crate::{test_util, Result};
+
+    #[crate::test]
+    async fn object_attr_info() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let objtype = conn.object_type("UDT_OBJECT").await?;
+        let attrs = objtype
+            .attributes()?
+            .iter()
+            .map(|attr| attr.info())
+            .collect::<Result<Vec<_>>>()?;
+        let names = attrs
+            .iter()
+            .map(|info| info.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "NUMBERVALUE",
+                "STRINGVALUE",
+                "FIXEDCHARVALUE",
+                "DATEVALUE",
+                "TIMESTAMPVALUE",
+                "SUBOBJECTVALUE",
+                "SUBOBJECTARRAY"
+            ]
+        );
+        assert_eq!(attrs[0].type_info.oracle_type, Some(OracleType::Number));
+        assert_eq!(attrs[1].type_info.oracle_type, Some(OracleType::Varchar));
+        assert_eq!(attrs[1].type_info.db_size_in_bytes, 60);
+        assert_eq!(attrs[2].type_info.oracle_type, Some(OracleType::Char));
+        assert_eq!(attrs[3].type_info.oracle_type, Some(OracleType::Date));
+        assert_eq!(attrs[4].type_info.oracle_type, Some(OracleType::Timestamp));
+        for (info, type_name) in [(&attrs[5], "UDT_SUBOBJECT"), (&attrs[6], "UDT_OBJECTARRAY")] {
+            assert_eq!(info.type_info.oracle_type, Some(OracleType::Object));
+            let objtype = info.type_info.object_type.as_ref().unwrap();
+            assert_eq!(objtype.info()?.name, type_name);
+        }
+        Ok(())
+    }
+}
@@ -12,22 +12,31 @@
 //
 // (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
 //-----------------------------------------------------------------------------
-use crate::types::{Object, ObjectAttr};
+use crate::types::{
+    FromObjectValue, Object, ObjectAttr, ObjectAttrInfo, ObjectTypeInfo, ToObjectValue,
+};
 use crate::utils::*;
 use crate::*;
 use odpi_rs_procmacro::odpic_doc;
 use odpic_sys::*;
+use once_cell::unsync::OnceCell;
+use std::fmt;
 use std::ptr;
+use std::rc::Rc;
 
-#[derive(Debug)]
 #[odpic_doc]
 pub struct ObjectType {
     pub(crate) handle: *mut dpiObjectType,
+    // shared by clones
+    attr_map: Rc<OnceCell<ObjectAttrMap>>,
 }
 
 impl ObjectType {
     pub(crate) fn new(handle: *mut dpiObjectType) -> ObjectType {
-        ObjectType { handle }
+        ObjectType {
+            handle,
+            attr_map: Rc::new(OnceCell::new()),
+        }
     }
 
     pub(crate) fn with_add_ref(handle: *mut dpiObjectType) -> Option<ObjectType> {
@@ -35,26 +44,99 @@ impl ObjectType {
             None
         } else {
             unsafe { dpiObjectType_addRef(handle) };
-            Some(ObjectType { handle })
+            Some(ObjectType::new(handle))
         }
     }
 
     pub fn create_object(&self) -> Result<Object> {
-        Ok(Object::new(get_value!(dpiObjectType_createObject(
-            self.handle
-        ))?))
+        Ok(Object::new(
+            get_value!(dpiObjectType_createObject(self.handle))?,
+            Some(self.clone()),
+        ))
     }
 
-    pub fn attributes(&self, num_attributes: u16) -> Result<ObjectAttr> {
-        Ok(ObjectAttr::new(get_value!(dpiObjectType_getAttributes(
+    pub fn attributes(&self) -> Result<Vec<ObjectAttr>> {
+        let num_attributes = get_value!(dpiObjectType_getInfo(self.handle))?.numAttributes;
+        let usize_num_attributes = num_attributes.into();
+        let mut attrs = Vec::with_capacity(usize_num_attributes);
+        call!(dpiObjectType_getAttributes(
             self.handle,
-            num_attributes
-        ))?))
+            num_attributes,
+            attrs.as_mut_ptr()
+        ))?;
+        unsafe {
+            attrs.set_len(usize_num_attributes);
+        }
+        Ok(attrs.into_iter().map(ObjectAttr::new).collect())
+    }
+
+    pub fn info(&self) -> Result<ObjectTypeInfo> {
+        get_value!(dpiObjectType_getInfo(self.handle))?.try_to_rust()
+    }
+
+    /// Returns attributes looked up by name
+    ///
+    /// This is used by code generated by `#[derive(ToObject)]` and `#[derive(FromObject)]`.
+    /// The attributes are got at the first call and cached.
+    #[doc(hidden)]
+    pub fn attr_map(&self) -> Result<&ObjectAttrMap> {
+        self.attr_map.get_or_try_init(|| ObjectAttrMap::new(self))
+    }
+}
+
+/// Attributes of an object type looked up by name
+///
+/// This is used by code generated by `#[derive(ToObject)]` and `#[derive(FromObject)]`.
+#[doc(hidden)]
+pub struct ObjectAttrMap {
+    type_name: String,
+    attrs: Vec<(ObjectAttr, ObjectAttrInfo)>,
+}
+
+impl ObjectAttrMap {
+    fn new(objtype: &ObjectType) -> Result<ObjectAttrMap> {
+        let info = objtype.info()?;
+        let attrs = objtype
+            .attributes()?
+            .into_iter()
+            .map(|attr| {
+                let info = attr.info()?;
+                Ok((attr, info))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ObjectAttrMap {
+            type_name: format!("{}.{}", info.schema, info.name),
+            attrs,
+        })
+    }
+
+    fn get(&self, name: &str) -> Result<&(ObjectAttr, ObjectAttrInfo)> {
+        self.attrs
+            .iter()
+            .find(|(_, info)| info.name == name)
+            .ok_or_else(|| {
+                Error::other(format!(
+                    "attribute {} not found in object type {}",
+                    name, self.type_name
+                ))
+            })
+    }
+
+    pub fn value<T>(&self, obj: &Object, name: &str) -> Result<T>
+    where
+        T: FromObjectValue,
+    {
+        let (attr, info) = self.get(name)?;
+        T::from_object_value(obj.attribute_value(attr)?, &info.type_info)
     }
 
-    // // return information about the object type
-    // DPI_EXPORT int dpiObjectType_getInfo(dpiObjectType *objType,
-    //         dpiObjectTypeInfo *info);
+    pub fn set_value<T>(&self, obj: &Object, name: &str, value: &T) -> Result<()>
+    where
+        T: ToObjectValue,
+    {
+        let (attr, info) = self.get(name)?;
+        obj.set_attribute_value(attr, &value.to_object_value(&info.type_info)?)
+    }
 }
 
 impl Clone for ObjectType {
@@ -62,10 +144,19 @@ impl Clone for ObjectType {
         unsafe { dpiObjectType_addRef(self.handle) };
         ObjectType {
             handle: self.handle,
+            attr_map: self.attr_map.clone(),
         }
     }
 }
 
+impl fmt::Debug for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectType")
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
 impl Drop for ObjectType {
     fn drop(&mut self) {
         release_handle!(dpiObjectType_release(self.handle));
@@ -0,0 +1,83 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::types::DataTypeInfo;
+use crate::utils::*;
+use crate::Result;
+use odpi_rs_procmacro::odpic_doc;
+use odpic_sys::*;
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+#[odpic_doc]
+pub struct ObjectTypeInfo {
+    pub schema: String,
+    pub name: String,
+    pub is_collection: bool,
+    /// `None` when the object type isn't a collection.
+    pub element_type_info: Option<DataTypeInfo>,
+    pub num_attributes: u16,
+    pub package_name: Option<String>,
+}
+
+impl TryToRust<ObjectTypeInfo> for dpiObjectTypeInfo {
+    fn try_to_rust(&self) -> Result<ObjectTypeInfo> {
+        let is_collection = self.isCollection.to_rust();
+        Ok(ObjectTypeInfo {
+            schema: (self.schema, self.schemaLength).try_to_rust()?,
+            name: (self.name, self.nameLength).try_to_rust()?,
+            is_collection,
+            element_type_info: if is_collection {
+                Some(self.elementTypeInfo.try_to_rust()?)
+            } else {
+                None
+            },
+            num_attributes: self.numAttributes,
+            package_name: (self.packageName, self.packageNameLength).try_to_rust()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::OracleType;
+    use crate::{test_util, Result};
+
+    #[crate::test]
+    async fn object_type_info() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let objtype = conn.object_type("UDT_OBJECT").await?;
+        let info = objtype.info()?;
+        assert_eq!(info.schema, test_util::main_user().to_uppercase());
+        assert_eq!(info.name, "UDT_OBJECT");
+        assert!(!info.is_collection);
+        assert!(info.element_type_info.is_none());
+        assert_eq!(info.num_attributes, 7);
+        assert_eq!(info.package_name, None);
+        assert_eq!(objtype.attributes()?.len(), info.num_attributes as usize);
+
+        let objtype = conn.object_type("UDT_OBJECTARRAY").await?;
+        let info = objtype.info()?;
+        assert_eq!(info.schema, test_util::main_user().to_uppercase());
+        assert_eq!(info.name, "UDT_OBJECTARRAY");
+        assert!(info.is_collection);
+        assert_eq!(info.num_attributes, 0);
+        assert!(objtype.attributes()?.is_empty());
+        let element_type_info = info.element_type_info.unwrap();
+        assert_eq!(element_type_info.oracle_type, Some(OracleType::Object));
+        let element_type = element_type_info.object_type.unwrap();
+        assert_eq!(element_type.info()?.name, "UDT_SUBOBJECT");
+        Ok(())
+    }
+}
@@ -0,0 +1,698 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::stmt::ToBindValue;
+use crate::types::{DataTypeInfo, FromObjectValue, FromSql, NativeValue, ToObjectValue};
+use crate::{Error, Result};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::{self, FromStr};
+
+/// Maximum number of significant digits
+const MAX_DIGITS: usize = 40;
+/// Maximum exponent of the most significant digit
+const MAX_EXPONENT: i32 = 125;
+/// Minimum exponent of the most significant digit
+const MIN_EXPONENT: i32 = -130;
+
+/// Decimal number holding Oracle NUMBER values losslessly
+///
+/// It holds up to 40 significant decimal digits and numbers whose absolute values
+/// are between 1.0 x 10<sup>-130</sup> and 1.0 x 10<sup>126</sup> (exclusive), the same as Oracle NUMBER.
+/// Results of arithmetic operations are rounded to 40 significant digits.
+/// Operators panic on overflow and division by zero. Use `checked_*` methods to avoid panics.
+///
+/// NUMBER columns must be fetched as [`NativeType::Bytes`][crate::types::NativeType::Bytes]
+/// because ODPI-C fetches them as `f64` by default unless they are integers.
+/// [`FromSql`] fails for floating point numbers instead of converting them lossily.
+/// Use `TryFrom<f64>` explicitly to convert `BINARY_DOUBLE` values.
+///
+/// # Examples
+///
+/// ```ignore
+/// use odpi_rs::types::{NativeType, OraNumber, OracleType};
+///
+/// stmt.execute(ExecMode::DEFAULT)?;
+/// stmt.define_value(1, OracleType::Number, NativeType::Bytes, 0, false, None)?;
+/// stmt.fetch()?;
+/// let price: OraNumber = stmt.query_value(1)?;
+/// let total = price * "1.08".parse::<OraNumber>()?;
+/// println!("{}", total);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OraNumber {
+    /// Digits of the coefficient from the most significant one without leading
+    /// and trailing zeros. Unused elements are zero.
+    digits: [u8; MAX_DIGITS],
+    len: u8,
+    /// Exponent of the least significant digit
+    exponent: i16,
+    negative: bool,
+}
+
+impl OraNumber {
+    /// Zero
+    pub const ZERO: OraNumber = OraNumber {
+        digits: [0; MAX_DIGITS],
+        len: 0,
+        exponent: 0,
+        negative: false,
+    };
+
+    /// Creates a number `digits` x 10<sup>`exponent`</sup>, where `digits` are
+    /// decimal digits from the most significant one.
+    ///
+    /// It returns `None` on overflow.
+    fn from_parts(negative: bool, mut digits: Vec<u8>, mut exponent: i32) -> Option<OraNumber> {
+        let first = match digits.iter().position(|d| *d != 0) {
+            Some(first) => first,
+            None => return Some(OraNumber::ZERO),
+        };
+        digits.drain(..first);
+        if digits.len() > MAX_DIGITS {
+            // round half away from zero
+            let round_up = digits[MAX_DIGITS] >= 5;
+            exponent += (digits.len() - MAX_DIGITS) as i32;
+            digits.truncate(MAX_DIGITS);
+            if round_up {
+                let mut carry = true;
+                for d in digits.iter_mut().rev() {
+                    if *d == 9 {
+                        *d = 0;
+                    } else {
+                        *d += 1;
+                        carry = false;
+                        break;
+                    }
+                }
+                if carry {
+                    // 99...9 + 1 = 10...0
+                    digits.insert(0, 1);
+                    digits.pop();
+                    exponent += 1;
+                }
+            }
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+            exponent += 1;
+        }
+        let msd_exponent = exponent + digits.len() as i32 - 1;
+        if msd_exponent > MAX_EXPONENT {
+            return None;
+        }
+        if msd_exponent < MIN_EXPONENT {
+            return Some(OraNumber::ZERO);
+        }
+        let mut num = OraNumber {
+            digits: [0; MAX_DIGITS],
+            len: digits.len() as u8,
+            exponent: exponent as i16,
+            negative,
+        };
+        num.digits[..digits.len()].copy_from_slice(&digits);
+        Some(num)
+    }
+
+    fn coefficient(&self) -> &[u8] {
+        &self.digits[..self.len as usize]
+    }
+
+    /// Exponent of the most significant digit
+    fn msd_exponent(&self) -> i32 {
+        i32::from(self.exponent) + i32::from(self.len) - 1
+    }
+
+    /// Returns `true` if `self` is zero.
+    pub fn is_zero(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if `self` is less than zero.
+    pub fn is_sign_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the absolute value of `self`.
+    pub fn abs(&self) -> OraNumber {
+        OraNumber {
+            negative: false,
+            ..*self
+        }
+    }
+
+    /// Adds `rhs` to `self`, returning `None` on overflow.
+    pub fn checked_add(&self, rhs: &OraNumber) -> Option<OraNumber> {
+        if self.is_zero() {
+            return Some(*rhs);
+        }
+        if rhs.is_zero() {
+            return Some(*self);
+        }
+        // align the coefficients to the smaller exponent
+        let exponent = self.exponent.min(rhs.exponent);
+        let lhs_digits = scale_digits(self, exponent);
+        let rhs_digits = scale_digits(rhs, exponent);
+        let exponent = i32::from(exponent);
+        if self.negative == rhs.negative {
+            OraNumber::from_parts(
+                self.negative,
+                add_digits(&lhs_digits, &rhs_digits),
+                exponent,
+            )
+        } else {
+            match cmp_digits(&lhs_digits, &rhs_digits) {
+                Ordering::Equal => Some(OraNumber::ZERO),
+                Ordering::Greater => OraNumber::from_parts(
+                    self.negative,
+                    sub_digits(&lhs_digits, &rhs_digits),
+                    exponent,
+                ),
+                Ordering::Less => OraNumber::from_parts(
+                    rhs.negative,
+                    sub_digits(&rhs_digits, &lhs_digits),
+                    exponent,
+                ),
+            }
+        }
+    }
+
+    /// Subtracts `rhs` from `self`, returning `None` on overflow.
+    pub fn checked_sub(&self, rhs: &OraNumber) -> Option<OraNumber> {
+        self.checked_add(&-*rhs)
+    }
+
+    /// Multiplies `self` by `rhs`, returning `None` on overflow.
+    pub fn checked_mul(&self, rhs: &OraNumber) -> Option<OraNumber> {
+        if self.is_zero() || rhs.is_zero() {
+            return Some(OraNumber::ZERO);
+        }
+        let lhs_digits = self.coefficient();
+        let rhs_digits = rhs.coefficient();
+        let mut product = vec![0u32; lhs_digits.len() + rhs_digits.len()];
+        for (i, l) in lhs_digits.iter().enumerate() {
+            for (j, r) in rhs_digits.iter().enumerate() {
+                product[i + j + 1] += u32::from(*l) * u32::from(*r);
+            }
+        }
+        let mut carry = 0;
+        for d in product.iter_mut().rev() {
+            *d += carry;
+            carry = *d / 10;
+            *d %= 10;
+        }
+        OraNumber::from_parts(
+            self.negative != rhs.negative,
+            product.into_iter().map(|d| d as u8).collect(),
+            i32::from(self.exponent) + i32::from(rhs.exponent),
+        )
+    }
+
+    /// Divides `self` by `rhs`, returning `None` on overflow or division by zero.
+    pub fn checked_div(&self, rhs: &OraNumber) -> Option<OraNumber> {
+        if rhs.is_zero() {
+            return None;
+        }
+        if self.is_zero() {
+            return Some(OraNumber::ZERO);
+        }
+        let divisor = rhs.coefficient();
+        // Append zeros to the dividend so that the quotient has more than
+        // MAX_DIGITS digits, which are enough to round it.
+        let scale = (divisor.len() + MAX_DIGITS + 1).saturating_sub(self.len as usize);
+        let mut quotient = Vec::with_capacity(self.len as usize + scale);
+        let mut remainder = Vec::<u8>::with_capacity(divisor.len() + 1);
+        for d in self
+            .coefficient()
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(0).take(scale))
+        {
+            if !(remainder.is_empty() && d == 0) {
+                remainder.push(d);
+            }
+            let mut q = 0;
+            while cmp_digits(&remainder, divisor) != Ordering::Less {
+                remainder = sub_digits(&remainder, divisor);
+                let first = remainder.iter().position(|d| *d != 0);
+                remainder.drain(..first.unwrap_or(remainder.len()));
+                q += 1;
+            }
+            quotient.push(q);
+        }
+        OraNumber::from_parts(
+            self.negative != rhs.negative,
+            quotient,
+            i32::from(self.exponent) - i32::from(rhs.exponent) - scale as i32,
+        )
+    }
+
+    /// Returns text to be converted to Oracle NUMBER.
+    ///
+    /// Numbers with fractional parts are formatted in exponential notation
+    /// without decimal points such as `12345E-2` so that they don't depend
+    /// on `NLS_NUMERIC_CHARACTERS`.
+    pub(crate) fn to_bind_text(self) -> String {
+        if self.exponent >= 0 {
+            return self.to_string();
+        }
+        let mut s = String::with_capacity(self.len as usize + 6);
+        if self.negative {
+            s.push('-');
+        }
+        s.extend(self.coefficient().iter().map(|d| char::from(b'0' + d)));
+        s.push('E');
+        s.push_str(&self.exponent.to_string());
+        s
+    }
+
+    /// Converts the number to a decimal type by parsing its text with `parse`.
+    #[cfg(any(feature = "bigdecimal", feature = "rust_decimal"))]
+    pub(crate) fn to_decimal<T, E>(
+        self,
+        type_name: &str,
+        parse: fn(&str) -> std::result::Result<T, E>,
+    ) -> Result<T>
+    where
+        E: fmt::Display,
+    {
+        parse(&self.to_string()).map_err(|err| {
+            Error::other(format!(
+                "failed to convert {} to {}: {}",
+                self, type_name, err
+            ))
+        })
+    }
+
+    /// Returns text to be converted to Oracle NUMBER from a decimal type.
+    ///
+    /// It fails when `value` is out of the range of Oracle NUMBER.
+    #[cfg(any(feature = "bigdecimal", feature = "rust_decimal"))]
+    pub(crate) fn decimal_bind_text<T>(value: &T) -> Result<String>
+    where
+        T: fmt::Display,
+    {
+        Ok(OraNumber::from_str(&value.to_string())?.to_bind_text())
+    }
+}
+
+/// Returns the coefficient digits of `num` whose least significant digit is at `exponent`.
+fn scale_digits(num: &OraNumber, exponent: i16) -> Vec<u8> {
+    let zeros = (num.exponent - exponent) as usize;
+    let mut digits = Vec::with_capacity(num.len as usize + zeros);
+    digits.extend_from_slice(num.coefficient());
+    digits.resize(num.len as usize + zeros, 0);
+    digits
+}
+
+/// Compares digits without leading zeros.
+fn cmp_digits(lhs: &[u8], rhs: &[u8]) -> Ordering {
+    lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(rhs))
+}
+
+fn add_digits(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
+    let len = lhs.len().max(rhs.len()) + 1;
+    let mut sum = vec![0; len];
+    let mut carry = 0;
+    for i in 0..len {
+        let l = if i < lhs.len() {
+            lhs[lhs.len() - 1 - i]
+        } else {
+            0
+        };
+        let r = if i < rhs.len() {
+            rhs[rhs.len() - 1 - i]
+        } else {
+            0
+        };
+        let d = l + r + carry;
+        sum[len - 1 - i] = d % 10;
+        carry = d / 10;
+    }
+    sum
+}
+
+/// Subtracts `rhs` from `lhs`, where `lhs` >= `rhs`.
+fn sub_digits(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
+    let mut diff = lhs.to_vec();
+    let mut borrow = 0;
+    for i in 0..lhs.len() {
+        let r = if i < rhs.len() {
+            rhs[rhs.len() - 1 - i]
+        } else {
+            0
+        };
+        let d = &mut diff[lhs.len() - 1 - i];
+        let sub = r + borrow;
+        if *d >= sub {
+            *d -= sub;
+            borrow = 0;
+        } else {
+            *d = *d + 10 - sub;
+            borrow = 1;
+        }
+    }
+    diff
+}
+
+impl Default for OraNumber {
+    fn default() -> OraNumber {
+        OraNumber::ZERO
+    }
+}
+
+/// Parses decimal numbers such as `-123.45`, `.5` and `1.5E+20`.
+///
+/// Numbers with more than 40 significant digits are rounded.
+impl FromStr for OraNumber {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<OraNumber> {
+        let invalid = || Error::other(format!("invalid number {:?}", s));
+        let bytes = s.as_bytes();
+        let (negative, mut rest) = match bytes.first() {
+            Some(b'-') => (true, &bytes[1..]),
+            Some(b'+') => (false, &bytes[1..]),
+            _ => (false, bytes),
+        };
+        let mut digits = Vec::with_capacity(rest.len());
+        let mut exponent = 0i32;
+        let mut has_digits = false;
+        let mut has_point = false;
+        while let Some((c, tail)) = rest.split_first() {
+            match c {
+                b'0'..=b'9' => {
+                    // Leading zeros are skipped to limit the buffer size.
+                    if !digits.is_empty() || *c != b'0' {
+                        digits.push(c - b'0');
+                    }
+                    if has_point {
+                        exponent -= 1;
+                    }
+                    has_digits = true;
+                }
+                b'.' if !has_point => has_point = true,
+                _ => break,
+            }
+            rest = tail;
+        }
+        if !has_digits {
+            return Err(invalid());
+        }
+        if let Some((b'e' | b'E', tail)) = rest.split_first() {
+            let exp = str::from_utf8(tail).map_err(|_| invalid())?;
+            if exp.is_empty() || exp.len() > 6 || exp.starts_with('+') && exp.len() == 1 {
+                return Err(invalid());
+            }
+            exponent += exp.parse::<i32>().map_err(|_| invalid())?;
+        } else if !rest.is_empty() {
+            return Err(invalid());
+        }
+        OraNumber::from_parts(negative, digits, exponent)
+            .ok_or_else(|| Error::other(format!("number {} out of range", s)))
+    }
+}
+
+/// Formats the number in decimal notation without exponent such as `-123.45` and `0.5`.
+impl fmt::Display for OraNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad("0");
+        }
+        let mut s = String::with_capacity(self.len as usize + 3);
+        if self.negative {
+            s.push('-');
+        }
+        let digits = self.coefficient().iter().map(|d| char::from(b'0' + d));
+        if self.exponent >= 0 {
+            s.extend(digits);
+            s.extend(std::iter::repeat('0').take(self.exponent as usize));
+        } else {
+            let int_len = self.msd_exponent() + 1;
+            if int_len > 0 {
+                let (int, frac) = self.coefficient().split_at(int_len as usize);
+                s.extend(int.iter().map(|d| char::from(b'0' + d)));
+                s.push('.');
+                s.extend(frac.iter().map(|d| char::from(b'0' + d)));
+            } else {
+                s.push_str("0.");
+                s.extend(std::iter::repeat('0').take(-int_len as usize));
+                s.extend(digits);
+            }
+        }
+        f.pad(&s)
+    }
+}
+
+impl fmt::Debug for OraNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OraNumber({})", self)
+    }
+}
+
+impl Ord for OraNumber {
+    fn cmp(&self, other: &OraNumber) -> Ordering {
+        let sign = |n: &OraNumber| match (n.is_zero(), n.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+        let ord = sign(self).cmp(&sign(other));
+        if ord != Ordering::Equal || self.is_zero() {
+            return ord;
+        }
+        // Unused digits are zero. Comparing all digits works.
+        let ord = self
+            .msd_exponent()
+            .cmp(&other.msd_exponent())
+            .then_with(|| self.digits.cmp(&other.digits));
+        if self.negative {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+}
+
+impl PartialOrd for OraNumber {
+    fn partial_cmp(&self, other: &OraNumber) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for OraNumber {
+    type Output = OraNumber;
+
+    fn neg(self) -> OraNumber {
+        OraNumber {
+            negative: !self.negative && !self.is_zero(),
+            ..self
+        }
+    }
+}
+
+macro_rules! ora_number_op_impl {
+    ($($trait:ident $method:ident $checked:ident $msg:literal,)*) => {$(
+        impl $trait for OraNumber {
+            type Output = OraNumber;
+
+            fn $method(self, rhs: OraNumber) -> OraNumber {
+                self.$checked(&rhs).expect($msg)
+            }
+        }
+
+        impl $trait<&OraNumber> for &OraNumber {
+            type Output = OraNumber;
+
+            fn $method(self, rhs: &OraNumber) -> OraNumber {
+                self.$checked(rhs).expect($msg)
+            }
+        }
+    )*};
+}
+
+ora_number_op_impl! {
+    Add add checked_add "attempt to add with overflow",
+    Sub sub checked_sub "attempt to subtract with overflow",
+    Mul mul checked_mul "attempt to multiply with overflow",
+    Div div checked_div "attempt to divide by zero or with overflow",
+}
+
+macro_rules! ora_number_int_impl {
+    ($($t:ty)*) => {$(
+        impl From<$t> for OraNumber {
+            fn from(value: $t) -> OraNumber {
+                value.to_string().parse().unwrap()
+            }
+        }
+
+        impl TryFrom<OraNumber> for $t {
+            type Error = Error;
+
+            fn try_from(value: OraNumber) -> Result<$t> {
+                Ok(value.to_string().parse()?)
+            }
+        }
+    )*};
+}
+
+ora_number_int_impl! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+macro_rules! ora_number_float_impl {
+    ($($t:ty)*) => {$(
+        /// Converts the shortest decimal representation of the floating point number.
+        impl TryFrom<$t> for OraNumber {
+            type Error = Error;
+
+            fn try_from(value: $t) -> Result<OraNumber> {
+                if value.is_finite() {
+                    value.to_string().parse()
+                } else {
+                    Err(Error::other(format!("cannot convert {} to OraNumber", value)))
+                }
+            }
+        }
+
+        impl From<OraNumber> for $t {
+            fn from(value: OraNumber) -> $t {
+                value.to_string().parse().unwrap()
+            }
+        }
+    )*};
+}
+
+ora_number_float_impl! { f32 f64 }
+
+impl FromSql for OraNumber {
+    fn from_sql(value: NativeValue<'_>) -> Result<OraNumber> {
+        match value {
+            NativeValue::Bytes(Some(bytes)) => str::from_utf8(bytes)?.parse(),
+            NativeValue::Int64(Some(value)) => Ok(value.into()),
+            NativeValue::Uint64(Some(value)) => Ok(value.into()),
+            NativeValue::Float(Some(_)) | NativeValue::Double(Some(_)) => Err(Error::other(
+                "cannot convert a floating point number to OraNumber losslessly; \
+                 define the column as NativeType::Bytes",
+            )),
+            NativeValue::Bytes(None) | NativeValue::Int64(None) | NativeValue::Uint64(None) => {
+                Err(Error::NullValue)
+            }
+            _ => Err(Error::other(format!(
+                "failed to convert to OraNumber from {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Binds the number as text to keep its precision.
+impl ToBindValue for OraNumber {
+    type Value<'a> = String;
+
+    fn to_bind_value(&self) -> Result<String> {
+        Ok(self.to_bind_text())
+    }
+}
+
+impl FromObjectValue for OraNumber {
+    type Value = OraNumber;
+
+    fn from_object_value(value: OraNumber, _type_info: &DataTypeInfo) -> Result<OraNumber> {
+        Ok(value)
+    }
+}
+
+impl ToObjectValue for OraNumber {
+    type Value<'a> = String;
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<String> {
+        Ok(self.to_bind_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(s: &str) -> OraNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_format() {
+        assert_eq!(num("0").to_string(), "0");
+        assert_eq!(num("-0.0").to_string(), "0");
+        assert_eq!(num("123.4500").to_string(), "123.45");
+        assert_eq!(num("-.5").to_string(), "-0.5");
+        assert_eq!(num("1.5E+3").to_string(), "1500");
+        assert_eq!(num("12E-5").to_string(), "0.00012");
+        assert_eq!(
+            num("1234567890123456789012345678901234567890").to_string(),
+            "1234567890123456789012345678901234567890"
+        );
+        assert_eq!(
+            num("12345678901234567890123456789012345678905").to_string(),
+            "12345678901234567890123456789012345678910"
+        );
+        assert_eq!(num("-123.45").to_bind_text(), "-12345E-2");
+        assert_eq!(num("1e125").to_bind_text().len(), 126);
+        assert!("1e126".parse::<OraNumber>().is_err());
+        assert!(num("1e-131").is_zero());
+        for s in ["", "-", ".", "1..2", "1e", "1e+", "a", "1 "] {
+            assert!(s.parse::<OraNumber>().is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn compare() {
+        assert!(num("-2") < num("-1.5"));
+        assert!(num("-1.5") < num("0"));
+        assert!(num("0") < num("0.001"));
+        assert!(num("0.001") < num("1"));
+        assert!(num("99") < num("100"));
+        assert_eq!(num("1.50"), num("1.5"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(num("0.1") + num("0.2"), num("0.3"));
+        assert_eq!(num("1") - num("1.0001"), num("-0.0001"));
+        assert_eq!(num("-1.5") * num("2.5"), num("-3.75"));
+        assert_eq!(
+            (num("1") / num("3")).to_string(),
+            "0.3333333333333333333333333333333333333333"
+        );
+        assert_eq!(
+            (num("2") / num("3")).to_string(),
+            "0.6666666666666666666666666666666666666667"
+        );
+        assert_eq!(num("1e100") + num("1e-100"), num("1e100"));
+        assert_eq!(num("1e125").checked_mul(&num("10")), None);
+        assert_eq!(num("1").checked_div(&OraNumber::ZERO), None);
+    }
+
+    #[test]
+    fn from_sql() {
+        let value = OraNumber::from_sql(NativeValue::Bytes(Some(b"-12.5"))).unwrap();
+        assert_eq!(value, num("-12.5"));
+        let value = OraNumber::from_sql(NativeValue::Int64(Some(42))).unwrap();
+        assert_eq!(value, num("42"));
+        assert!(OraNumber::from_sql(NativeValue::Double(Some(0.1))).is_err());
+        assert!(OraNumber::from_sql(NativeValue::Float(Some(0.1))).is_err());
+        assert!(matches!(
+            OraNumber::from_sql(NativeValue::Bytes(None)),
+            Err(Error::NullValue)
+        ));
+    }
+}
@@ -0,0 +1,131 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Conversions between Oracle NUMBER and [rust_decimal] types
+//!
+//! Values are converted via text by [`OraNumber`] so that no precision goes
+//! through floating point numbers.
+//! NUMBER columns must be fetched as [`NativeType::Bytes`][crate::types::NativeType::Bytes]
+//! as described in [`OraNumber`].
+use crate::stmt::ToBindValue;
+use crate::types::{DataTypeInfo, FromObjectValue, FromSql, NativeValue, OraNumber, ToObjectValue};
+use crate::Result;
+use ::rust_decimal::Decimal;
+
+impl FromSql for Decimal {
+    fn from_sql(value: NativeValue<'_>) -> Result<Decimal> {
+        OraNumber::from_sql(value)?.to_decimal("Decimal", Decimal::from_str_exact)
+    }
+}
+
+impl ToBindValue for Decimal {
+    type Value<'a> = String;
+
+    fn to_bind_value(&self) -> Result<String> {
+        OraNumber::decimal_bind_text(self)
+    }
+}
+
+impl FromObjectValue for Decimal {
+    type Value = OraNumber;
+
+    fn from_object_value(value: OraNumber, _type_info: &DataTypeInfo) -> Result<Decimal> {
+        value.to_decimal("Decimal", Decimal::from_str_exact)
+    }
+}
+
+impl ToObjectValue for Decimal {
+    type Value<'a> = String;
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<String> {
+        OraNumber::decimal_bind_text(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stmt::ExecMode;
+    use crate::test_util;
+    use crate::types::{NativeType, OracleType};
+
+    fn from_text(s: &str) -> Result<Decimal> {
+        Decimal::from_sql(NativeValue::Bytes(Some(s.as_bytes())))
+    }
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str_exact(s).unwrap()
+    }
+
+    #[test]
+    fn fetch() -> Result<()> {
+        assert_eq!(from_text("0")?, Decimal::ZERO);
+        assert_eq!(from_text("-123.45")?, dec("-123.45"));
+        assert_eq!(from_text("1E+3")?, dec("1000"));
+        assert_eq!(from_text("79228162514264337593543950335")?, Decimal::MAX);
+        assert_eq!(
+            from_text(".0000000000000000000000000001")?,
+            dec("0.0000000000000000000000000001")
+        );
+        // out of the range of Decimal
+        assert!(from_text("79228162514264337593543950336").is_err());
+        // scale greater than 28
+        assert!(from_text(".00000000000000000000000000001").is_err());
+        assert!(matches!(
+            Decimal::from_sql(NativeValue::Bytes(None)),
+            Err(crate::Error::NullValue)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn bind_text() -> Result<()> {
+        assert_eq!(Decimal::ZERO.to_bind_value()?, "0");
+        assert_eq!(dec("-0.00").to_bind_value()?, "0");
+        assert_eq!(dec("1.500").to_bind_value()?, "15E-1");
+        assert_eq!(dec("-123.45").to_bind_value()?, "-12345E-2");
+        assert_eq!(
+            dec("0.0000000000000000000000000001").to_bind_value()?,
+            "1E-28"
+        );
+        assert_eq!(
+            Decimal::MAX.to_bind_value()?,
+            "79228162514264337593543950335"
+        );
+        assert_eq!(
+            Decimal::MIN.to_bind_value()?,
+            "-79228162514264337593543950335"
+        );
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn round_trip() -> Result<()> {
+        let conn = test_util::connect().await?;
+        for value in [
+            dec("-123.45"),
+            Decimal::MAX,
+            dec("0.0000000000000000000000000001"),
+        ] {
+            let stmt = conn.prepare_stmt(false, "select to_number(:1) from dual", "")?;
+            stmt.bind_value_by_pos(1, &value)?;
+            stmt.execute(ExecMode::DEFAULT).await?;
+            stmt.define_value(1, OracleType::Number, NativeType::Bytes, 0, false, None)?;
+            let row = stmt.fetch().await?;
+            assert!(row.is_some());
+            assert_eq!(stmt.query_value::<Decimal>(1)?, value);
+        }
+        Ok(())
+    }
+}
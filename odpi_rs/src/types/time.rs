@@ -0,0 +1,437 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+//! Conversions between Oracle date-time types and [time] types
+//!
+//! | Oracle type | odpi_rs type | time type |
+//! |---|---|---|
+//! | `DATE`, `TIMESTAMP` | [`Timestamp`] | [`Date`], [`PrimitiveDateTime`] |
+//! | `TIMESTAMP WITH TIME ZONE` | [`Timestamp`] | [`OffsetDateTime`] |
+//! | `INTERVAL DAY TO SECOND` | [`IntervalDS`] | [`Duration`] |
+//!
+//! [`OffsetDateTime`] keeps its offset in both directions. Fetched values
+//! get it from `tz_hour_offset` and `tz_minute_offset` and bind values are
+//! bound as `TIMESTAMP WITH TIME ZONE` through variables. [`Date`] and
+//! [`PrimitiveDateTime`] have no offset. They are bound as `TIMESTAMP` and
+//! the offset of fetched values is ignored.
+//!
+//! # Fractional seconds
+//!
+//! [`DataTypeInfo::fs_precision`] is applied only where the client knows the
+//! data type of the target, that is to values set to object attributes and
+//! collection elements. It isn't applied to bind values because the precision
+//! is decided by the SQL statement on the server, nor to fetched values
+//! because they never have more digits than their columns.
+//!
+//! # Years and calendars
+//!
+//! Oracle has no year zero. Its year -1 is 1 BC, which is year 0 in time, so
+//! years before 1 differ by one. Oracle dates before 1582-10-15 are in the
+//! Julian calendar while time uses the proleptic Gregorian calendar. The year,
+//! month and day are converted as they are, so such dates denote different
+//! points in time on both sides. For example, `1500-01-01` in Oracle is
+//! `1500-01-10` in the proleptic Gregorian calendar.
+//!
+//! # Errors
+//!
+//! Conversions to [`Timestamp`] fail when the year is outside 4712 BC (-4711
+//! in time) to 9999, the range of Oracle dates, or when the offset has seconds.
+//! Conversions from [`Timestamp`] fail when the year is zero. Conversions to
+//! [`IntervalDS`] fail when the number of days doesn't fit in `i32`.
+use crate::conn::Conn;
+use crate::stmt::{BindValue, ToBindValue};
+use crate::types::{
+    DataTypeInfo, FromObjectValue, FromSql, IntervalDS, NativeValue, Timestamp, ToObjectValue,
+};
+use crate::{Error, Result};
+use ::time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+use odpic_sys::*;
+
+// Converts a year in time, where 1 BC is year zero, to an Oracle year
+fn oracle_year(value: &PrimitiveDateTime) -> Result<i16> {
+    if !(-4711..=9999).contains(&value.year()) {
+        return Err(Error::other(format!(
+            "year of {} is out of the range of Oracle dates",
+            value
+        )));
+    }
+    let year = value.year() as i16;
+    Ok(if year <= 0 { year - 1 } else { year })
+}
+
+// Converts an Oracle year, where 1 BC is year -1, to a year in time
+fn time_year(value: &Timestamp) -> Result<i32> {
+    match value.year {
+        // Oracle rejects year zero with ORA-01841.
+        0 => Err(Error::other(format!("year zero in {:?}", value))),
+        year if year < 0 => Ok(i32::from(year) + 1),
+        year => Ok(year.into()),
+    }
+}
+
+fn timestamp_from_primitive(value: &PrimitiveDateTime, offset: UtcOffset) -> Result<Timestamp> {
+    let year = oracle_year(value)?;
+    if offset.seconds_past_minute() != 0 {
+        return Err(Error::other(format!(
+            "time zone offset {} has seconds",
+            offset
+        )));
+    }
+    Ok(Timestamp {
+        year,
+        month: value.month().into(),
+        day: value.day(),
+        hour: value.hour(),
+        minute: value.minute(),
+        second: value.second(),
+        fsecond: value.nanosecond(),
+        tz_hour_offset: offset.whole_hours(),
+        tz_minute_offset: offset.minutes_past_hour(),
+    })
+}
+
+fn primitive_from_timestamp(value: &Timestamp) -> Result<PrimitiveDateTime> {
+    let date = date_from_timestamp(value)?;
+    let time = Time::from_hms_nano(value.hour, value.minute, value.second, value.fsecond)
+        .map_err(|err| Error::other(format!("invalid time in {:?}: {}", value, err)))?;
+    Ok(PrimitiveDateTime::new(date, time))
+}
+
+fn date_from_timestamp(value: &Timestamp) -> Result<Date> {
+    let year = time_year(value)?;
+    Month::try_from(value.month)
+        .and_then(|month| Date::from_calendar_date(year, month, value.day))
+        .map_err(|err| Error::other(format!("invalid date in {:?}: {}", value, err)))
+}
+
+impl TryFrom<Date> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: Date) -> Result<Timestamp> {
+        timestamp_from_primitive(&value.midnight(), UtcOffset::UTC)
+    }
+}
+
+impl TryFrom<PrimitiveDateTime> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: PrimitiveDateTime) -> Result<Timestamp> {
+        timestamp_from_primitive(&value, UtcOffset::UTC)
+    }
+}
+
+impl TryFrom<OffsetDateTime> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: OffsetDateTime) -> Result<Timestamp> {
+        let primitive = PrimitiveDateTime::new(value.date(), value.time());
+        timestamp_from_primitive(&primitive, value.offset())
+    }
+}
+
+/// The time part and the time zone offset are discarded.
+impl TryFrom<Timestamp> for Date {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<Date> {
+        date_from_timestamp(&value)
+    }
+}
+
+/// The time zone offset is discarded.
+impl TryFrom<Timestamp> for PrimitiveDateTime {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<PrimitiveDateTime> {
+        primitive_from_timestamp(&value)
+    }
+}
+
+impl TryFrom<Timestamp> for OffsetDateTime {
+    type Error = Error;
+
+    fn try_from(value: Timestamp) -> Result<OffsetDateTime> {
+        let offset = UtcOffset::from_hms(value.tz_hour_offset, value.tz_minute_offset, 0)
+            .map_err(|err| Error::other(format!("invalid time zone in {:?}: {}", value, err)))?;
+        Ok(primitive_from_timestamp(&value)?.assume_offset(offset))
+    }
+}
+
+impl From<IntervalDS> for Duration {
+    fn from(value: IntervalDS) -> Duration {
+        let secs = i64::from(value.days) * 86400
+            + i64::from(value.hours) * 3600
+            + i64::from(value.minutes) * 60
+            + i64::from(value.seconds);
+        Duration::new(secs, value.fseconds)
+    }
+}
+
+impl TryFrom<Duration> for IntervalDS {
+    type Error = Error;
+
+    fn try_from(value: Duration) -> Result<IntervalDS> {
+        // Both are truncated toward zero so that all fields have the same sign.
+        let secs = value.whole_seconds();
+        Ok(IntervalDS {
+            days: (secs / 86400).try_into()?,
+            hours: (secs % 86400 / 3600) as i32,
+            minutes: (secs % 3600 / 60) as i32,
+            seconds: (secs % 60) as i32,
+            fseconds: value.subsec_nanoseconds(),
+        })
+    }
+}
+
+macro_rules! time_timestamp_impl {
+    ($($t:ty)*) => {$(
+        impl FromSql for $t {
+            fn from_sql(value: NativeValue<'_>) -> Result<$t> {
+                Timestamp::from_sql(value)?.try_into()
+            }
+        }
+
+        impl FromObjectValue for $t {
+            type Value = Timestamp;
+
+            fn from_object_value(value: Timestamp, _type_info: &DataTypeInfo) -> Result<$t> {
+                value.try_into()
+            }
+        }
+
+        impl ToObjectValue for $t {
+            type Value<'a> = Timestamp;
+
+            fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<Timestamp> {
+                Ok(Timestamp::try_from(*self)?.with_fs_precision(type_info.fs_precision))
+            }
+        }
+    )*};
+}
+
+time_timestamp_impl! { Date PrimitiveDateTime OffsetDateTime }
+
+impl ToBindValue for Date {
+    type Value<'a> = Timestamp;
+
+    fn to_bind_value(&self) -> Result<Timestamp> {
+        (*self).try_into()
+    }
+}
+
+impl ToBindValue for PrimitiveDateTime {
+    type Value<'a> = Timestamp;
+
+    fn to_bind_value(&self) -> Result<Timestamp> {
+        (*self).try_into()
+    }
+}
+
+// dpiStmt_bindValueBy* binds timestamps as TIMESTAMP, which has no offset.
+impl BindValue for OffsetDateTime {
+    fn native_type_num() -> dpiNativeTypeNum {
+        DPI_NATIVE_TYPE_TIMESTAMP
+    }
+    fn oracle_type_num() -> Option<dpiOracleTypeNum> {
+        Some(DPI_ORACLE_TYPE_TIMESTAMP_TZ)
+    }
+    fn with_data<R, F>(&self, conn: Option<&Conn>, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut dpiData) -> Result<R>,
+    {
+        Timestamp::try_from(*self)?.with_data(conn, f)
+    }
+}
+
+impl FromSql for Duration {
+    fn from_sql(value: NativeValue<'_>) -> Result<Duration> {
+        Ok(IntervalDS::from_sql(value)?.into())
+    }
+}
+
+impl ToBindValue for Duration {
+    type Value<'a> = IntervalDS;
+
+    fn to_bind_value(&self) -> Result<IntervalDS> {
+        (*self).try_into()
+    }
+}
+
+impl FromObjectValue for Duration {
+    type Value = IntervalDS;
+
+    fn from_object_value(value: IntervalDS, _type_info: &DataTypeInfo) -> Result<Duration> {
+        Ok(value.into())
+    }
+}
+
+impl ToObjectValue for Duration {
+    type Value<'a> = IntervalDS;
+
+    fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<IntervalDS> {
+        Ok(IntervalDS::try_from(*self)?.with_fs_precision(type_info.fs_precision))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stmt::ExecMode;
+    use crate::test_util;
+
+    fn timestamp(year: i16, fsecond: u32, tz_hour_offset: i8, tz_minute_offset: i8) -> Timestamp {
+        Timestamp {
+            year,
+            month: 1,
+            day: 2,
+            hour: 3,
+            minute: 4,
+            second: 5,
+            fsecond,
+            tz_hour_offset,
+            tz_minute_offset,
+        }
+    }
+
+    fn interval(days: i32, hours: i32, minutes: i32, seconds: i32, fseconds: i32) -> IntervalDS {
+        IntervalDS {
+            days,
+            hours,
+            minutes,
+            seconds,
+            fseconds,
+        }
+    }
+
+    fn primitive(nanosecond: u32) -> PrimitiveDateTime {
+        Date::from_calendar_date(2025, Month::January, 2)
+            .unwrap()
+            .with_hms_nano(3, 4, 5, nanosecond)
+            .unwrap()
+    }
+
+    #[test]
+    fn year_range() -> Result<()> {
+        // (year in time, Oracle year)
+        for (year, oracle_year) in [(-4711, -4712), (-1, -2), (0, -1), (1, 1), (9999, 9999)] {
+            let value = Date::from_calendar_date(year, Month::January, 2).unwrap();
+            let ts = Timestamp::try_from(value)?;
+            assert_eq!(ts.year, oracle_year);
+            assert_eq!(Date::try_from(ts)?, value);
+            let value = value.with_hms(3, 4, 5).unwrap();
+            let ts = Timestamp::try_from(value)?;
+            assert_eq!(ts, timestamp(oracle_year, 0, 0, 0));
+            assert_eq!(PrimitiveDateTime::try_from(ts)?, value);
+        }
+        let value = Date::from_calendar_date(-4712, Month::January, 2).unwrap();
+        assert!(Timestamp::try_from(value).is_err());
+        assert!(Date::try_from(timestamp(0, 0, 0, 0)).is_err());
+        assert!(PrimitiveDateTime::try_from(timestamp(0, 0, 0, 0)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn offset_sign() -> Result<()> {
+        for (hour, minute) in [(0, 0), (5, 30), (-3, -30), (0, -45)] {
+            let offset = UtcOffset::from_hms(hour, minute, 0).unwrap();
+            let value = primitive(0).assume_offset(offset);
+            let ts = Timestamp::try_from(value)?;
+            assert_eq!(ts, timestamp(2025, 0, hour, minute));
+            let fetched = OffsetDateTime::try_from(ts)?;
+            assert_eq!(fetched, value);
+            assert_eq!(fetched.offset(), offset);
+        }
+        let offset = UtcOffset::from_hms(1, 0, 1).unwrap();
+        assert!(Timestamp::try_from(primitive(0).assume_offset(offset)).is_err());
+
+        // The offset is discarded.
+        let ts = timestamp(2025, 0, -3, -30);
+        assert_eq!(PrimitiveDateTime::try_from(ts)?, primitive(0));
+        assert_eq!(
+            Timestamp::try_from(PrimitiveDateTime::try_from(ts)?)?,
+            timestamp(2025, 0, 0, 0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nanosecond_truncation() -> Result<()> {
+        let ts = Timestamp::try_from(primitive(123_456_789))?;
+        assert_eq!(ts, timestamp(2025, 123_456_789, 0, 0));
+        assert_eq!(ts.with_fs_precision(6).fsecond, 123_456_000);
+        assert_eq!(ts.with_fs_precision(0).fsecond, 0);
+        assert_eq!(PrimitiveDateTime::try_from(ts)?, primitive(123_456_789));
+
+        let interval = IntervalDS::try_from(Duration::new(-1, -987_654_321))?;
+        assert_eq!(interval.with_fs_precision(3).fseconds, -987_000_000);
+        Ok(())
+    }
+
+    #[test]
+    fn interval_round_trip() -> Result<()> {
+        let cases = [
+            (Duration::ZERO, interval(0, 0, 0, 0, 0)),
+            (
+                Duration::new(86400 + 2 * 3600 + 3 * 60 + 4, 5),
+                interval(1, 2, 3, 4, 5),
+            ),
+            (
+                Duration::new(-(86400 + 2 * 3600 + 3 * 60 + 4), -5),
+                interval(-1, -2, -3, -4, -5),
+            ),
+            (Duration::nanoseconds(-1), interval(0, 0, 0, 0, -1)),
+        ];
+        for (duration, interval) in cases {
+            assert_eq!(IntervalDS::try_from(duration)?, interval);
+            assert_eq!(Duration::from(interval), duration);
+        }
+        assert!(IntervalDS::try_from(Duration::MAX).is_err());
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_offset() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let offset = UtcOffset::from_hms(-3, -30, 0).unwrap();
+        let value = primitive(0).assume_offset(offset);
+        let stmt = conn.prepare_stmt(false, "select :ts, to_char(:ts, 'TZH:TZM') from dual", "")?;
+        stmt.bind_value_by_name("ts", &value)?;
+        stmt.execute(ExecMode::DEFAULT).await?;
+        let row = stmt.fetch().await?;
+        assert!(row.is_some());
+        let fetched = stmt.query_value::<OffsetDateTime>(1)?;
+        assert_eq!(fetched.offset(), offset);
+        assert_eq!(fetched, value);
+        assert_eq!(stmt.query_value::<String>(2)?, "-03:30");
+        Ok(())
+    }
+
+    #[crate::test]
+    async fn bind_bc_date() -> Result<()> {
+        let conn = test_util::connect().await?;
+        let stmt =
+            conn.prepare_stmt(false, "select :1, to_char(:1, 'SYYYY-MM-DD') from dual", "")?;
+        // year 0 in time is 1 BC.
+        for (year, text) in [(0, "-0001-01-02"), (-4711, "-4712-01-02")] {
+            let value = Date::from_calendar_date(year, Month::January, 2).unwrap();
+            stmt.bind_value_by_pos(1, &value)?;
+            stmt.execute(ExecMode::DEFAULT).await?;
+            let row = stmt.fetch().await?;
+            assert!(row.is_some());
+            assert_eq!(stmt.query_value::<Date>(1)?, value);
+            assert_eq!(stmt.query_value::<String>(2)?, text);
+        }
+        Ok(())
+    }
+}
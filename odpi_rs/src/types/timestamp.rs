@@ -14,7 +14,7 @@
 //-----------------------------------------------------------------------------
 use odpic_sys::*;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Timestamp {
     pub year: i16,
     pub month: u8,
@@ -27,6 +27,24 @@ pub struct Timestamp {
     pub tz_minute_offset: i8,
 }
 
+impl Timestamp {
+    /// Returns the timestamp whose fractional seconds are truncated to
+    /// `fs_precision` digits, such as [`DataTypeInfo::fs_precision`][crate::types::DataTypeInfo::fs_precision].
+    pub fn with_fs_precision(self, fs_precision: u8) -> Timestamp {
+        let unit = fs_unit(fs_precision);
+        Timestamp {
+            fsecond: self.fsecond - self.fsecond % unit,
+            ..self
+        }
+    }
+}
+
+/// Returns nanoseconds of the least significant digit of fractional seconds
+/// whose precision is `fs_precision`.
+pub(crate) fn fs_unit(fs_precision: u8) -> u32 {
+    10u32.pow(9 - u32::from(fs_precision.min(9)))
+}
+
 impl From<Timestamp> for dpiTimestamp {
     fn from(value: Timestamp) -> dpiTimestamp {
         dpiTimestamp {
@@ -0,0 +1,163 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::stmt::BindValue;
+use crate::types::{DataTypeInfo, IntervalDS, IntervalYM, Lob, Object, ObjectType, Timestamp};
+use crate::{Error, Result};
+
+/// Conversion from a Rust value to an Oracle object
+///
+/// This is usually implemented by `#[derive(ToObject)]`.
+/// Each field of the struct is set to the object attribute whose name is
+/// the uppercased field name. The attribute name can be changed by
+/// `#[odpi(rename = "ATTR_NAME")]` on the field. Field types must implement
+/// [`ToObjectValue`].
+///
+/// `#[odpi(type = "SCHEMA.TYPE_NAME")]` on the struct sets [`ToObject::TYPE_NAME`],
+/// which is used by [`Object::from_value`] to look up the object type.
+///
+/// ```ignore
+/// use odpi_rs::types::{Object, ToObject};
+///
+/// #[derive(ToObject)]
+/// #[odpi(type = "SCOTT.POINT")]
+/// struct Point {
+///     x: i32,
+///     #[odpi(rename = "Y_COORD")]
+///     y: Option<f64>,
+/// }
+///
+/// let obj = Object::from_value(&conn, &Point { x: 1, y: None }).await?;
+/// ```
+pub trait ToObject {
+    /// The object type name specified by `#[odpi(type = "SCHEMA.TYPE_NAME")]`
+    const TYPE_NAME: Option<&'static str> = None;
+
+    fn to_object(&self, objtype: &ObjectType) -> Result<Object>;
+}
+
+/// Conversion from a Rust value to a value of an object attribute or a collection element
+///
+/// Types implementing [`ToObject`] are converted to nested objects and
+/// `Vec<T>` is converted to a collection except `Vec<u8>`, which is
+/// converted to `RAW`. For this reason, `u8` isn't implemented.
+pub trait ToObjectValue {
+    /// The type of the value set to the attribute or the element
+    type Value<'a>: BindValue
+    where
+        Self: 'a;
+
+    /// Converts `self` to a value of the attribute or the element whose type is `type_info`.
+    fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<Self::Value<'_>>;
+}
+
+impl<T> ToObjectValue for T
+where
+    T: ToObject,
+{
+    type Value<'a>
+        = Object
+    where
+        T: 'a;
+
+    fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<Object> {
+        self.to_object(type_info.object_type_or_err()?)
+    }
+}
+
+impl<T> ToObjectValue for Option<T>
+where
+    T: ToObjectValue,
+{
+    type Value<'a>
+        = Option<T::Value<'a>>
+    where
+        T: 'a;
+
+    fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<Option<T::Value<'_>>> {
+        self.as_ref()
+            .map(|value| value.to_object_value(type_info))
+            .transpose()
+    }
+}
+
+impl<T> ToObjectValue for Vec<T>
+where
+    T: ToObjectValue,
+{
+    type Value<'a>
+        = Object
+    where
+        T: 'a;
+
+    fn to_object_value(&self, type_info: &DataTypeInfo) -> Result<Object> {
+        let objtype = type_info.object_type_or_err()?;
+        let element_type_info = objtype
+            .info()?
+            .element_type_info
+            .ok_or_else(|| Error::other(format!("{:?} isn't a collection type", objtype)))?;
+        let coll = objtype.create_object()?;
+        for element in self {
+            coll.append_element(&element.to_object_value(&element_type_info)?)?;
+        }
+        Ok(coll)
+    }
+}
+
+impl ToObjectValue for String {
+    type Value<'a> = &'a str;
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<&str> {
+        Ok(self.as_str())
+    }
+}
+
+impl ToObjectValue for Vec<u8> {
+    type Value<'a> = &'a [u8];
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<&[u8]> {
+        Ok(self.as_slice())
+    }
+}
+
+impl ToObjectValue for Object {
+    type Value<'a> = Object;
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<Object> {
+        Ok(self.clone())
+    }
+}
+
+impl ToObjectValue for Lob {
+    type Value<'a> = Lob;
+
+    fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<Lob> {
+        Ok(self.clone())
+    }
+}
+
+macro_rules! to_object_value_impl {
+    ($($t:ty)*) => {$(
+        impl ToObjectValue for $t {
+            type Value<'a> = $t;
+
+            fn to_object_value(&self, _type_info: &DataTypeInfo) -> Result<$t> {
+                Ok(*self)
+            }
+        }
+    )*};
+}
+
+to_object_value_impl! { i8 i16 i32 i64 u16 u32 u64 f32 f64 bool }
+to_object_value_impl! { Timestamp IntervalDS IntervalYM }
@@ -0,0 +1,128 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::derive_object::named_fields;
+use crate::odpi_attrs::field_name;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+pub fn from_row(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = named_fields(&input)?;
+    let mut add_columns = Vec::new();
+    let mut get_values = Vec::new();
+    for (index, field) in fields.named.iter().enumerate() {
+        let (field_ident, name) = field_name(field)?;
+        add_columns.push(quote! {
+            columns.add(#name)?;
+        });
+        get_values.push(quote! {
+            #field_ident: columns.value(batch, index, #index)?,
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::odpi_rs::stmt::FromRow for #ident #ty_generics #where_clause {
+            type Columns = ::odpi_rs::stmt::ColumnMap;
+
+            fn columns(
+                stmt: &::odpi_rs::stmt::Stmt,
+            ) -> ::odpi_rs::Result<::odpi_rs::stmt::ColumnMap> {
+                let mut columns = ::odpi_rs::stmt::ColumnMap::new(stmt)?;
+                #(#add_columns)*
+                ::std::result::Result::Ok(columns)
+            }
+
+            #[allow(unused_variables)]
+            fn from_row(
+                batch: &::odpi_rs::stmt::RowBatch<'_>,
+                index: u32,
+                columns: &::odpi_rs::stmt::ColumnMap,
+            ) -> ::odpi_rs::Result<Self> {
+                ::std::result::Result::Ok(#ident {
+                    #(#get_values)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn from_row_gets_columns_by_name() {
+        let input: DeriveInput = parse_quote! {
+            struct Emp<T> {
+                empno: i32,
+                #[odpi(rename = "MGR")]
+                manager: Option<T>,
+            }
+        };
+        let expected = quote! {
+            impl<T> ::odpi_rs::stmt::FromRow for Emp<T> {
+                type Columns = ::odpi_rs::stmt::ColumnMap;
+
+                fn columns(
+                    stmt: &::odpi_rs::stmt::Stmt,
+                ) -> ::odpi_rs::Result<::odpi_rs::stmt::ColumnMap> {
+                    let mut columns = ::odpi_rs::stmt::ColumnMap::new(stmt)?;
+                    columns.add("EMPNO")?;
+                    columns.add("MGR")?;
+                    ::std::result::Result::Ok(columns)
+                }
+
+                #[allow(unused_variables)]
+                fn from_row(
+                    batch: &::odpi_rs::stmt::RowBatch<'_>,
+                    index: u32,
+                    columns: &::odpi_rs::stmt::ColumnMap,
+                ) -> ::odpi_rs::Result<Self> {
+                    ::std::result::Result::Ok(Emp {
+                        empno: columns.value(batch, index, 0usize)?,
+                        manager: columns.value(batch, index, 1usize)?,
+                    })
+                }
+            }
+        };
+        assert_eq!(from_row(input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn unsupported_input() {
+        let input: DeriveInput = parse_quote! {
+            struct Emp(i32, String);
+        };
+        assert!(from_row(input).is_err());
+
+        let input: DeriveInput = parse_quote! {
+            enum Emp {
+                A,
+            }
+        };
+        assert!(from_row(input).is_err());
+
+        let input: DeriveInput = parse_quote! {
+            struct Emp {
+                #[odpi(name = "EMPNO")]
+                empno: i32,
+            }
+        };
+        assert!(from_row(input).is_err());
+    }
+}
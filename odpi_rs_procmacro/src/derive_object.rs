@@ -0,0 +1,188 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use crate::odpi_attrs::{field_name, OdpiAttrs};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, FieldsNamed, Result};
+
+pub(crate) fn named_fields(input: &DeriveInput) -> Result<&FieldsNamed> {
+    if let Data::Struct(data) = &input.data {
+        if let Fields::Named(fields) = &data.fields {
+            return Ok(fields);
+        }
+    }
+    Err(Error::new_spanned(
+        input,
+        "only structs with named fields are supported",
+    ))
+}
+
+fn type_name(input: &DeriveInput) -> Result<TokenStream> {
+    Ok(match OdpiAttrs::parse(&input.attrs)?.type_name {
+        Some(type_name) => quote!(::std::option::Option::Some(#type_name)),
+        None => quote!(::std::option::Option::None),
+    })
+}
+
+pub fn to_object(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let type_name = type_name(&input)?;
+    let set_values = named_fields(&input)?
+        .named
+        .iter()
+        .map(|field| {
+            let (field_ident, name) = field_name(field)?;
+            Ok(quote! {
+                attrs.set_value(&obj, #name, &self.#field_ident)?;
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::odpi_rs::types::ToObject for #ident #ty_generics #where_clause {
+            const TYPE_NAME: ::std::option::Option<&'static str> = #type_name;
+
+            #[allow(unused_variables)]
+            fn to_object(
+                &self,
+                objtype: &::odpi_rs::types::ObjectType,
+            ) -> ::odpi_rs::Result<::odpi_rs::types::Object> {
+                let attrs = objtype.attr_map()?;
+                let obj = objtype.create_object()?;
+                #(#set_values)*
+                ::std::result::Result::Ok(obj)
+            }
+        }
+    })
+}
+
+pub fn from_object(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let type_name = type_name(&input)?;
+    let get_values = named_fields(&input)?
+        .named
+        .iter()
+        .map(|field| {
+            let (field_ident, name) = field_name(field)?;
+            Ok(quote! {
+                #field_ident: attrs.value(obj, #name)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::odpi_rs::types::FromObject for #ident #ty_generics #where_clause {
+            const TYPE_NAME: ::std::option::Option<&'static str> = #type_name;
+
+            #[allow(unused_variables)]
+            fn from_object(
+                obj: &::odpi_rs::types::Object,
+                objtype: &::odpi_rs::types::ObjectType,
+            ) -> ::odpi_rs::Result<Self> {
+                let attrs = objtype.attr_map()?;
+                ::std::result::Result::Ok(#ident {
+                    #(#get_values)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn to_object_sets_attributes() {
+        let input: DeriveInput = parse_quote! {
+            #[odpi(type = "SCOTT.POINT")]
+            struct Point {
+                x: i32,
+                #[odpi(rename = "Y_COORD")]
+                y: Option<f64>,
+            }
+        };
+        let expected = quote! {
+            impl ::odpi_rs::types::ToObject for Point {
+                const TYPE_NAME: ::std::option::Option<&'static str> =
+                    ::std::option::Option::Some("SCOTT.POINT");
+
+                #[allow(unused_variables)]
+                fn to_object(
+                    &self,
+                    objtype: &::odpi_rs::types::ObjectType,
+                ) -> ::odpi_rs::Result<::odpi_rs::types::Object> {
+                    let attrs = objtype.attr_map()?;
+                    let obj = objtype.create_object()?;
+                    attrs.set_value(&obj, "X", &self.x)?;
+                    attrs.set_value(&obj, "Y_COORD", &self.y)?;
+                    ::std::result::Result::Ok(obj)
+                }
+            }
+        };
+        assert_eq!(to_object(input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn from_object_gets_attributes() {
+        let input: DeriveInput = parse_quote! {
+            struct Point<T> {
+                r#type: String,
+                value: T,
+            }
+        };
+        let expected = quote! {
+            impl<T> ::odpi_rs::types::FromObject for Point<T> {
+                const TYPE_NAME: ::std::option::Option<&'static str> =
+                    ::std::option::Option::None;
+
+                #[allow(unused_variables)]
+                fn from_object(
+                    obj: &::odpi_rs::types::Object,
+                    objtype: &::odpi_rs::types::ObjectType,
+                ) -> ::odpi_rs::Result<Self> {
+                    let attrs = objtype.attr_map()?;
+                    ::std::result::Result::Ok(Point {
+                        r#type: attrs.value(obj, "TYPE")?,
+                        value: attrs.value(obj, "VALUE")?,
+                    })
+                }
+            }
+        };
+        assert_eq!(
+            from_object(input).unwrap().to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn unsupported_input() {
+        let input: DeriveInput = parse_quote! {
+            struct Point(i32, i32);
+        };
+        assert!(to_object(input).is_err());
+
+        let input: DeriveInput = parse_quote! {
+            struct Point {
+                #[odpi(name = "X")]
+                x: i32,
+            }
+        };
+        assert!(from_object(input).is_err());
+    }
+}
@@ -17,8 +17,11 @@
 use odpic_sys::doc::OdpicDoc;
 use proc_macro::TokenStream;
 use std::sync::LazyLock;
-use syn::Error;
+use syn::{parse_macro_input, DeriveInput, Error};
 
+mod derive_from_row;
+mod derive_object;
+mod odpi_attrs;
 mod odpic_doc;
 #[cfg_attr(feature = "is_async", path = "runtime/async.rs")]
 #[cfg_attr(not(feature = "is_async"), path = "runtime/sync.rs")]
@@ -512,6 +515,113 @@ pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Derives `odpi_rs::types::ToObject` to convert a struct to an Oracle object.
+///
+/// Each field is set to the object attribute whose name is the uppercased field name.
+/// Field types must implement `odpi_rs::types::ToObjectValue`, which is implemented
+/// for scalar types, `Option<T>`, `Vec<T>` as collections and types implementing
+/// `ToObject` as nested objects.
+///
+/// # Attributes
+///
+/// * `#[odpi(type = "SCHEMA.TYPE_NAME")]` on the struct sets the object type name
+///   used by `odpi_rs::types::Object::from_value`.
+/// * `#[odpi(rename = "ATTR_NAME")]` on a field sets the attribute name.
+///
+/// # Examples
+///
+/// ```ignore
+/// use odpi_rs::types::{Object, ToObject};
+///
+/// #[derive(ToObject)]
+/// #[odpi(type = "SCOTT.ADDRESS")]
+/// struct Address {
+///     street: String,
+///     #[odpi(rename = "ZIP_CODE")]
+///     zip: Option<String>,
+///     phones: Vec<String>,
+/// }
+///
+/// let obj = Object::from_value(&conn, &address).await?;
+/// ```
+#[proc_macro_derive(ToObject, attributes(odpi))]
+pub fn derive_to_object(input: TokenStream) -> TokenStream {
+    derive_object::to_object(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `odpi_rs::types::FromObject` to convert an Oracle object to a struct.
+///
+/// Each field is got from the object attribute whose name is the uppercased field name.
+/// Field types must implement `odpi_rs::types::FromObjectValue`, which is implemented
+/// for scalar types, `Option<T>`, `Vec<T>` as collections and types implementing
+/// `FromObject` as nested objects.
+///
+/// # Attributes
+///
+/// * `#[odpi(type = "SCHEMA.TYPE_NAME")]` on the struct sets the object type name
+///   used by `odpi_rs::types::Object::to_value`.
+/// * `#[odpi(rename = "ATTR_NAME")]` on a field sets the attribute name.
+///
+/// # Examples
+///
+/// ```ignore
+/// use odpi_rs::types::FromObject;
+///
+/// #[derive(FromObject)]
+/// #[odpi(type = "SCOTT.ADDRESS")]
+/// struct Address {
+///     street: String,
+///     #[odpi(rename = "ZIP_CODE")]
+///     zip: Option<String>,
+///     phones: Vec<String>,
+/// }
+///
+/// let address: Address = obj.to_value(&conn).await?;
+/// ```
+#[proc_macro_derive(FromObject, attributes(odpi))]
+pub fn derive_from_object(input: TokenStream) -> TokenStream {
+    derive_object::from_object(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `odpi_rs::stmt::FromRow` to convert a fetched row to a struct.
+///
+/// Each field is got from the query column whose name matches the field name
+/// case-insensitively. Column positions are looked up once by
+/// `FromRow::columns` after the statement is executed. Field types must
+/// implement `odpi_rs::types::FromSql`.
+///
+/// # Attributes
+///
+/// * `#[odpi(rename = "COLUMN_NAME")]` on a field sets the column name.
+///
+/// # Examples
+///
+/// ```ignore
+/// use odpi_rs::stmt::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct Emp {
+///     empno: i32,
+///     ename: Option<String>,
+///     #[odpi(rename = "MGR")]
+///     manager: Option<i32>,
+/// }
+///
+/// for emp in stmt.rows::<Emp>()? {
+///     let emp = emp?;
+/// }
+/// ```
+#[proc_macro_derive(FromRow, attributes(odpi))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    derive_from_row::from_row(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
 static ODPIC_DOC: LazyLock<OdpicDoc> = LazyLock::new(|| OdpicDoc::read_yaml().unwrap());
 
 #[proc_macro_attribute]
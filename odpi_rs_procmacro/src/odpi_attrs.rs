@@ -0,0 +1,63 @@
+// odpi_rs - a thin wrapper over Oracle Database Programming Interface for C
+//
+// URL: https://github.com/kubo/odpi_rs
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2025 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+use syn::{Attribute, Field, Ident, LitStr, Result};
+
+/// Arguments of `#[odpi(...)]` attributes used by derive macros
+#[derive(Default)]
+pub struct OdpiAttrs {
+    /// `#[odpi(type = "SCHEMA.TYPE_NAME")]` on structs
+    pub type_name: Option<LitStr>,
+    /// `#[odpi(rename = "NAME")]` on fields
+    pub rename: Option<LitStr>,
+}
+
+impl OdpiAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<OdpiAttrs> {
+        let mut odpi_attrs = OdpiAttrs::default();
+        for attr in attrs {
+            if !attr.path().is_ident("odpi") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("type") {
+                    odpi_attrs.type_name = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    odpi_attrs.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported odpi attribute"))
+                }
+            })?;
+        }
+        Ok(odpi_attrs)
+    }
+}
+
+/// Returns the field identifier and the Oracle name for the field.
+///
+/// The Oracle name is the value of `#[odpi(rename = "NAME")]` if it is set.
+/// Otherwise, it is the uppercased field name.
+pub fn field_name(field: &Field) -> Result<(&Ident, String)> {
+    let ident = field
+        .ident
+        .as_ref()
+        .ok_or_else(|| syn::Error::new_spanned(field, "unnamed fields are not supported"))?;
+    let name = match OdpiAttrs::parse(&field.attrs)?.rename {
+        Some(rename) => rename.value(),
+        None => ident.to_string().trim_start_matches("r#").to_uppercase(),
+    };
+    Ok((ident, name))
+}